@@ -1,74 +1,193 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::Deserialize;
 
+use crate::ip_range::IpRange;
+
 #[derive(Debug, Deserialize, Clone)]
+#[serde(from = "RawAppConfig")]
 pub struct AppConfig {
     pub general: GeneralConfig,
-    pub tor: TorConfig,
-    pub i2p: I2pConfig,
-    pub zeronet: ZeronetConfig,
-    pub hyphanet: HyphanetConfig,
-    pub lokinet: LokinetConfig,
+    /// Overlay networks by name (`[networks.<name>]`); `kind` picks the driver
+    pub networks: BTreeMap<String, NetworkConfig>,
     pub database: DatabaseConfig,
     pub extraction: ExtractionConfig,
     pub frontier: FrontierConfig,
+    pub scope: ScopeConfig,
+    pub topic: TopicConfig,
+    pub blobs: BlobConfig,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct GeneralConfig {
-    pub data_dir: String,
-    pub max_depth: u32,
-    pub max_pages_per_domain: u64,
-    pub max_body_size_mb: usize,
+/// On-disk layout: `[networks.<name>]` tables plus the legacy top-level
+/// `[tor]` / `[i2p]` / `[zeronet]` / `[hyphanet]` / `[lokinet]` sections.
+#[derive(Deserialize)]
+struct RawAppConfig {
+    general: GeneralConfig,
+    #[serde(default)]
+    networks: BTreeMap<String, NetworkConfig>,
+    tor: Option<NetworkConfig>,
+    i2p: Option<NetworkConfig>,
+    zeronet: Option<NetworkConfig>,
+    hyphanet: Option<NetworkConfig>,
+    lokinet: Option<NetworkConfig>,
+    database: DatabaseConfig,
+    extraction: ExtractionConfig,
+    frontier: FrontierConfig,
+    #[serde(default)]
+    scope: ScopeConfig,
+    #[serde(default)]
+    topic: TopicConfig,
+    #[serde(default)]
+    blobs: BlobConfig,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct TorConfig {
-    pub enabled: bool,
-    pub socks_proxies: Vec<String>,
-    pub max_concurrency: usize,
-    pub min_delay_seconds: u64,
-    pub connect_timeout_seconds: u64,
-    pub request_timeout_seconds: u64,
+impl From<RawAppConfig> for AppConfig {
+    fn from(raw: RawAppConfig) -> Self {
+        let mut networks = raw.networks;
+        // A legacy section only applies when there is no [networks.<name>] table of the same name
+        let legacy = [
+            ("tor", raw.tor),
+            ("i2p", raw.i2p),
+            ("zeronet", raw.zeronet),
+            ("hyphanet", raw.hyphanet),
+            ("lokinet", raw.lokinet),
+        ];
+        for (name, section) in legacy {
+            if let Some(section) = section {
+                networks.entry(name.to_string()).or_insert(section);
+            }
+        }
+        // `kind` defaults to the table name
+        for (name, network) in networks.iter_mut() {
+            if network.kind.is_empty() {
+                network.kind = name.clone();
+            }
+        }
+        Self {
+            general: raw.general,
+            networks,
+            database: raw.database,
+            extraction: raw.extraction,
+            frontier: raw.frontier,
+            scope: raw.scope,
+            topic: raw.topic,
+            blobs: raw.blobs,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct I2pConfig {
-    pub enabled: bool,
-    pub http_proxies: Vec<String>,
-    pub max_concurrency: usize,
-    pub min_delay_seconds: u64,
-    pub connect_timeout_seconds: u64,
-    pub request_timeout_seconds: u64,
+impl AppConfig {
+    /// Enabled networks, by name.
+    pub fn enabled_networks(&self) -> impl Iterator<Item = (&str, &NetworkConfig)> {
+        self.networks
+            .iter()
+            .filter(|(_, network)| network.enabled)
+            .map(|(name, network)| (name.as_str(), network))
+    }
+
+    /// (in-site depth, domain hops) limits for a network, falling back to [general].
+    pub fn depth_limits(&self, network: &str) -> (u32, u32) {
+        let section = self.networks.get(network);
+        (
+            section.and_then(|n| n.max_depth).unwrap_or(self.general.max_depth),
+            section.and_then(|n| n.max_domain_hops).unwrap_or(self.general.max_domain_hops),
+        )
+    }
+
+    /// Concurrency limit of a network's section, if it is a known network.
+    pub fn max_concurrency(&self, network: &str) -> Option<usize> {
+        self.networks.get(network).map(|n| n.max_concurrency)
+    }
+
+    /// Recrawl budget (pages per hour) for a network, falling back to [frontier].
+    pub fn revisit_budget(&self, network: &str) -> u32 {
+        self.networks
+            .get(network)
+            .and_then(|n| n.revisit_pages_per_hour)
+            .unwrap_or(self.frontier.revisit_pages_per_hour)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
-pub struct ZeronetConfig {
-    pub enabled: bool,
-    pub http_proxies: Vec<String>,
-    pub max_concurrency: usize,
-    pub min_delay_seconds: u64,
-    pub connect_timeout_seconds: u64,
-    pub request_timeout_seconds: u64,
+pub struct GeneralConfig {
+    pub data_dir: String,
+    /// In-site link depth: links followed within one domain (resets on every new domain)
+    pub max_depth: u32,
+    /// Domain hops: how many new domains away from a seed the crawl may go
+    #[serde(default = "default_max_domain_hops")]
+    pub max_domain_hops: u32,
+    pub max_pages_per_domain: u64,
+    pub max_body_size_mb: usize,
+    /// Shared worker pool size (default: sum of the enabled networks' max_concurrency).
+    /// Set it below that sum to let busy networks burst into idle networks' share.
+    #[serde(default)]
+    pub max_workers: Option<usize>,
+    /// How often network supervisors re-check proxy health once the network is up
+    #[serde(default = "default_health_check_interval")]
+    pub health_check_interval_seconds: u64,
 }
 
+/// One overlay network (`[networks.<name>]`). The table name is the network's name
+/// everywhere else (frontier queues, storage, scope, CLI); `kind` selects the driver.
 #[derive(Debug, Deserialize, Clone)]
-pub struct HyphanetConfig {
+pub struct NetworkConfig {
+    /// Driver kind (tor, i2p, zeronet, hyphanet, lokinet, gemini, gopher, ipfs, yggdrasil);
+    /// defaults to the table name
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default = "default_true")]
     pub enabled: bool,
-    pub http_proxies: Vec<String>,
+    /// Proxy addresses: SOCKS for tor/lokinet/gemini/gopher/yggdrasil, HTTP for
+    /// i2p/zeronet/hyphanet, the gateway for ipfs. Yggdrasil without proxies connects
+    /// directly over the host's mesh interface.
+    #[serde(default, alias = "socks_proxies", alias = "http_proxies")]
+    pub proxies: Vec<String>,
+    /// Address pattern for `<NAME>_INSTANCES`, `{n}` = instance number (e.g. "tor{n}:9050").
+    /// Unset: numbered like the first proxy.
+    #[serde(default)]
+    pub proxy_template: Option<String>,
     pub max_concurrency: usize,
     pub min_delay_seconds: u64,
     pub connect_timeout_seconds: u64,
     pub request_timeout_seconds: u64,
+    /// Overrides general.max_depth for this network
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    /// Overrides general.max_domain_hops for this network
+    #[serde(default)]
+    pub max_domain_hops: Option<u32>,
+    /// Overrides frontier.revisit_pages_per_hour for this network
+    #[serde(default)]
+    pub revisit_pages_per_hour: Option<u32>,
+    /// Address ranges routed to this network (CIDR, e.g. "200::/7"), for drivers that
+    /// route by IP instead of by host suffix (yggdrasil). Empty: the driver's default
+    #[serde(default)]
+    pub ip_ranges: Vec<IpRange>,
+    /// Host name suffixes routed to this network along with `ip_ranges` (e.g. ".ygg").
+    /// Empty: the driver's default
+    #[serde(default)]
+    pub host_suffixes: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct LokinetConfig {
-    pub enabled: bool,
-    pub socks_proxies: Vec<String>,
-    pub max_concurrency: usize,
-    pub min_delay_seconds: u64,
-    pub connect_timeout_seconds: u64,
-    pub request_timeout_seconds: u64,
+impl NetworkConfig {
+    /// Proxy addresses for `count` numbered instances (1..=count), from `proxy_template`
+    /// or the first proxy's host with its trailing number replaced ("tor1:9050" → "tor2:9050").
+    pub fn instance_proxies(&self, count: usize) -> Option<Vec<String>> {
+        let template = match &self.proxy_template {
+            Some(template) => template.clone(),
+            None => {
+                let first = self.proxies.first()?;
+                let (host, port) = first.rsplit_once(':').unwrap_or((first, ""));
+                let prefix = host.trim_end_matches(|c: char| c.is_ascii_digit());
+                if port.is_empty() {
+                    format!("{}{{n}}", prefix)
+                } else {
+                    format!("{}{{n}}:{}", prefix, port)
+                }
+            }
+        };
+        Some((1..=count).map(|i| template.replace("{n}", &i.to_string())).collect())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -83,6 +202,131 @@ pub struct ExtractionConfig {
     pub extract_phones: bool,
     pub extract_pgp: bool,
     pub extract_usernames: bool,
+    /// Warrant canaries whose latest date is older than this are flagged stale
+    #[serde(default = "default_canary_stale_days")]
+    pub canary_stale_days: u32,
+    /// Download `<img>` sources of crawled pages and extract EXIF/XMP/IPTC metadata
+    #[serde(default)]
+    pub fetch_images: bool,
+    /// Images fetched per page (each URL is fetched once per run)
+    #[serde(default = "default_max_images_per_page")]
+    pub max_images_per_page: usize,
+    /// Larger images are not downloaded
+    #[serde(default = "default_max_image_size_kb")]
+    pub max_image_size_kb: usize,
+}
+
+/// Crawl scope. Everything empty/false = crawl every handled URL.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ScopeConfig {
+    /// Only crawl these networks (empty = every enabled network)
+    pub networks: Vec<String>,
+    /// Only crawl these domains and their subdomains (empty = no restriction)
+    pub allow_domains: Vec<String>,
+    /// Never crawl these domains or their subdomains
+    pub deny_domains: Vec<String>,
+    /// Regexes on path + query; when set, a URL must match at least one
+    pub include_patterns: Vec<String>,
+    /// Regexes on path + query; matching URLs are skipped
+    pub exclude_patterns: Vec<String>,
+    /// Only crawl the hosts of the seed URLs
+    pub stay_on_seed_domains: bool,
+}
+
+/// Topic profile for focused crawling. No keywords = topic scoring off.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct TopicConfig {
+    /// Keyword or phrase → weight, matched case-insensitively on word boundaries
+    pub keywords: HashMap<String, f64>,
+    /// Language code (e.g. "ru") → extra keywords, used for pages in that language
+    /// (all of them when the page language is unknown)
+    pub language_keywords: HashMap<String, HashMap<String, f64>>,
+    /// Priority multiplier for links that are fully on topic (1.0 = no boost)
+    pub boost: f64,
+    /// Drop links when both the page and the link score below this (0.0 = never prune)
+    pub min_relevance: f64,
+}
+
+impl Default for TopicConfig {
+    fn default() -> Self {
+        Self {
+            keywords: HashMap::new(),
+            language_keywords: HashMap::new(),
+            boost: default_topic_boost(),
+            min_relevance: 0.0,
+        }
+    }
+}
+
+/// Content-addressed store of raw response bodies under `general.data_dir`.
+/// Bodies are only ever as large as `general.max_body_size_mb` allows to download.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct BlobConfig {
+    pub enabled: bool,
+    /// Content types kept ("application/pdf", "image/*"); empty = every type
+    pub store_types: Vec<String>,
+    /// Content types never kept, checked before `store_types`
+    pub skip_types: Vec<String>,
+    /// Larger bodies are not kept
+    pub max_size_mb: usize,
+    /// Size limit per content type pattern, overriding `max_size_mb` (most specific wins)
+    pub type_max_size_mb: HashMap<String, usize>,
+    /// zstd level (1 = fastest, 19 = smallest)
+    pub compression_level: i32,
+}
+
+impl Default for BlobConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            store_types: Vec::new(),
+            skip_types: Vec::new(),
+            max_size_mb: default_blob_max_size_mb(),
+            type_max_size_mb: HashMap::new(),
+            compression_level: default_blob_compression_level(),
+        }
+    }
+}
+
+impl BlobConfig {
+    /// Whether a body of this content type and size is kept. Responses without a
+    /// content type are matched as application/octet-stream.
+    pub fn should_store(&self, content_type: Option<&str>, size: usize) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let mime = content_type
+            .and_then(|ct| ct.split(';').next())
+            .map(|ct| ct.trim().to_ascii_lowercase())
+            .filter(|ct| !ct.is_empty())
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        if self.skip_types.iter().any(|p| type_matches(p, &mime)) {
+            return false;
+        }
+        if !self.store_types.is_empty() && !self.store_types.iter().any(|p| type_matches(p, &mime)) {
+            return false;
+        }
+        // An exact type beats "type/*", which beats "*"
+        let limit_mb = self
+            .type_max_size_mb
+            .iter()
+            .filter(|(p, _)| type_matches(p, &mime))
+            .max_by_key(|(p, _)| p.trim_end_matches('*').len())
+            .map_or(self.max_size_mb, |(_, mb)| *mb);
+        size <= limit_mb * 1024 * 1024
+    }
+}
+
+/// "text/html" matches exactly, "image/*" by top-level type, "*" everything.
+fn type_matches(pattern: &str, mime: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    match pattern.strip_suffix('*') {
+        Some(prefix) => mime.starts_with(prefix),
+        None => pattern == mime,
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -94,8 +338,85 @@ pub struct FrontierConfig {
     pub bloom_filter_fp_rate: f64,
     #[serde(default = "default_max_retries")]
     pub max_retries: u32,
+    /// Max concurrent requests to a single host
+    #[serde(default = "default_max_in_flight_per_host")]
+    pub max_in_flight_per_host: usize,
+    /// Upper bound for robots.txt Crawl-delay and 429/503 backoff
+    #[serde(default = "default_max_host_delay_seconds")]
+    pub max_host_delay_seconds: u64,
+    /// Session-id query parameters dropped during URL canonicalization (`name*` = prefix)
+    #[serde(default = "default_strip_session_params")]
+    pub strip_session_params: Vec<String>,
+    /// Tracking query parameters dropped during URL canonicalization (`name*` = prefix)
+    #[serde(default = "default_strip_tracking_params")]
+    pub strip_tracking_params: Vec<String>,
+    /// Trap: a run of path segments repeating back to back this many times (/a/b/a/b/a/b)
+    #[serde(default = "default_trap_max_segment_repeats")]
+    pub trap_max_segment_repeats: usize,
+    /// Trap: query strings longer than this
+    #[serde(default = "default_trap_max_query_length")]
+    pub trap_max_query_length: usize,
+    /// Trap: distinct query-parameter combinations allowed on one path
+    #[serde(default = "default_trap_max_param_combinations")]
+    pub trap_max_param_combinations: usize,
+    /// Trap: far-off or content-repeating calendar/date URLs allowed per host
+    #[serde(default = "default_trap_max_calendar_urls")]
+    pub trap_max_calendar_urls: usize,
+    /// Trap: dates more than this many years past the crawl date count as far-off
+    #[serde(default = "default_trap_calendar_years_ahead")]
+    pub trap_calendar_years_ahead: u32,
+    /// Trap: pages with identical content allowed per host before their URL template is quarantined
+    #[serde(default = "default_trap_max_duplicate_content")]
+    pub trap_max_duplicate_content: usize,
+    /// Recrawls of already-known pages per network per hour (0 = no revisits)
+    #[serde(default = "default_revisit_pages_per_hour")]
+    pub revisit_pages_per_hour: u32,
+    /// Shortest revisit interval, for pages that change on every visit
+    #[serde(default = "default_revisit_min_interval_hours")]
+    pub revisit_min_interval_hours: u64,
+    /// Longest revisit interval, for pages that never change
+    #[serde(default = "default_revisit_max_interval_hours")]
+    pub revisit_max_interval_hours: u64,
+    /// How often RSS/Atom feeds are re-fetched for new items (0 = no polling)
+    #[serde(default = "default_feed_poll_interval_minutes")]
+    pub feed_poll_interval_minutes: u64,
+}
+
+impl Default for FrontierConfig {
+    fn default() -> Self {
+        Self {
+            bloom_filter_size: default_bloom_size(),
+            bloom_filter_fp_rate: default_bloom_fp(),
+            max_retries: default_max_retries(),
+            max_in_flight_per_host: default_max_in_flight_per_host(),
+            max_host_delay_seconds: default_max_host_delay_seconds(),
+            strip_session_params: default_strip_session_params(),
+            strip_tracking_params: default_strip_tracking_params(),
+            trap_max_segment_repeats: default_trap_max_segment_repeats(),
+            trap_max_query_length: default_trap_max_query_length(),
+            trap_max_param_combinations: default_trap_max_param_combinations(),
+            trap_max_calendar_urls: default_trap_max_calendar_urls(),
+            trap_calendar_years_ahead: default_trap_calendar_years_ahead(),
+            trap_max_duplicate_content: default_trap_max_duplicate_content(),
+            revisit_pages_per_hour: default_revisit_pages_per_hour(),
+            revisit_min_interval_hours: default_revisit_min_interval_hours(),
+            revisit_max_interval_hours: default_revisit_max_interval_hours(),
+            feed_poll_interval_minutes: default_feed_poll_interval_minutes(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
+fn default_max_domain_hops() -> u32 {
+    10
+}
+
+fn default_health_check_interval() -> u64 {
+    60
+}
 fn default_bloom_size() -> usize {
     10_000_000
 }
@@ -105,3 +426,87 @@ fn default_bloom_fp() -> f64 {
 fn default_max_retries() -> u32 {
     3
 }
+fn default_max_in_flight_per_host() -> usize {
+    2
+}
+fn default_max_host_delay_seconds() -> u64 {
+    300
+}
+fn default_trap_max_segment_repeats() -> usize {
+    3
+}
+fn default_trap_max_query_length() -> usize {
+    512
+}
+fn default_trap_max_param_combinations() -> usize {
+    64
+}
+fn default_trap_max_calendar_urls() -> usize {
+    50
+}
+fn default_trap_calendar_years_ahead() -> u32 {
+    1
+}
+fn default_trap_max_duplicate_content() -> usize {
+    10
+}
+
+fn default_revisit_pages_per_hour() -> u32 {
+    120
+}
+
+fn default_revisit_min_interval_hours() -> u64 {
+    1
+}
+
+fn default_revisit_max_interval_hours() -> u64 {
+    720
+}
+
+fn default_feed_poll_interval_minutes() -> u64 {
+    60
+}
+
+/// Session-id parameters (PHP, Java, ASP.NET and forum software)
+pub const DEFAULT_SESSION_PARAMS: &[&str] = &[
+    "phpsessid",
+    "jsessionid",
+    "aspsessionid*",
+    "sessionid",
+    "session_id",
+    "sid",
+];
+/// Analytics / ad-click tracking parameters
+pub const DEFAULT_TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "mc_cid", "mc_eid", "_ga", "_gl",
+];
+
+fn default_strip_session_params() -> Vec<String> {
+    DEFAULT_SESSION_PARAMS.iter().map(|s| s.to_string()).collect()
+}
+fn default_strip_tracking_params() -> Vec<String> {
+    DEFAULT_TRACKING_PARAMS.iter().map(|s| s.to_string()).collect()
+}
+fn default_canary_stale_days() -> u32 {
+    45
+}
+
+fn default_max_images_per_page() -> usize {
+    8
+}
+
+fn default_max_image_size_kb() -> usize {
+    2048
+}
+
+fn default_blob_max_size_mb() -> usize {
+    10
+}
+
+fn default_blob_compression_level() -> i32 {
+    3
+}
+
+fn default_topic_boost() -> f64 {
+    4.0
+}
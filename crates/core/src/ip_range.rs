@@ -0,0 +1,94 @@
+//! IP ranges for networks routed by address instead of by host suffix
+//! (mesh networks like Yggdrasil hand out IPv6 addresses derived from node keys).
+
+use std::fmt;
+use std::net::{IpAddr, Ipv6Addr};
+use std::str::FromStr;
+
+use serde::Deserialize;
+use url::{Host, Url};
+
+use crate::error::CrawlError;
+
+/// An address range in CIDR notation ("200::/7", "10.0.0.0/8"). A bare address is a
+/// single-host range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct IpRange {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+/// Yggdrasil's address space: node addresses (200::/8) and routed subnets (300::/8).
+pub const YGGDRASIL: IpRange = IpRange {
+    addr: IpAddr::V6(Ipv6Addr::new(0x200, 0, 0, 0, 0, 0, 0, 0)),
+    prefix: 7,
+};
+
+/// Host name suffix of Yggdrasil's Alfis DNS names.
+pub const YGGDRASIL_SUFFIX: &str = ".ygg";
+
+impl IpRange {
+    /// None if `prefix` is longer than the address.
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Self> {
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+        (prefix <= bits).then_some(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = CrawlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CrawlError::Config(format!("invalid IP range \"{}\"", s));
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.trim().parse().map_err(|_| invalid())?;
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse().map_err(|_| invalid())?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Self::new(addr, prefix).ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<String> for IpRange {
+    type Error = CrawlError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// The IP address of a URL whose host is an address literal
+/// (`http://10.1.2.3/`, `http://[200:1::2]/`); None for host names.
+pub fn host_ip(url: &Url) -> Option<IpAddr> {
+    match url.host()? {
+        Host::Ipv4(ip) => Some(IpAddr::V4(ip)),
+        Host::Ipv6(ip) => Some(IpAddr::V6(ip)),
+        Host::Domain(_) => None,
+    }
+}
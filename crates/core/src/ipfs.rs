@@ -0,0 +1,419 @@
+//! IPFS content identifiers (CIDs) and IPNS names.
+//!
+//! Addresses show up as `ipfs://<cid>/path`, `ipns://<name>/path`, gateway paths
+//! (`https://ipfs.io/ipfs/<cid>/path`) and subdomain gateways
+//! (`https://<cid>.ipfs.dweb.link/path`). All of them map to one canonical
+//! `ipfs://` / `ipns://` URL so the same content dedups to the same key:
+//! CIDs are rewritten as CIDv1 base32, key-based IPNS names as CIDv1 base36.
+
+use url::Url;
+
+const BASE32: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE36: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Multicodec of UnixFS nodes, implied by CIDv0
+const DAG_PB: u64 = 0x70;
+/// Multicodec of IPNS public keys
+const LIBP2P_KEY: u64 = 0x72;
+/// Multihash code of the identity "hash" used by inlined ed25519 peer IDs
+const IDENTITY: u64 = 0x00;
+const SHA2_256: u64 = 0x12;
+
+/// A decoded CID: codec and multihash (the version is always normalized to 1).
+struct Cid {
+    codec: u64,
+    multihash: Vec<u8>,
+}
+
+impl Cid {
+    fn parse(s: &str) -> Option<Cid> {
+        // CIDv0: a bare base58 sha2-256 multihash
+        if s.len() == 46 && s.starts_with("Qm") {
+            let multihash = decode_radix(s, BASE58)?;
+            return (multihash.len() == 34 && multihash[0] == SHA2_256 as u8 && multihash[1] == 32).then_some(Cid {
+                codec: DAG_PB,
+                multihash,
+            });
+        }
+
+        let mut chars = s.chars();
+        let bytes = match chars.next()? {
+            'b' | 'B' => decode_base32(chars.as_str())?,
+            'k' | 'K' => decode_radix(&chars.as_str().to_ascii_lowercase(), BASE36)?,
+            'z' => decode_radix(chars.as_str(), BASE58)?,
+            'f' | 'F' => decode_base16(chars.as_str())?,
+            _ => return None,
+        };
+        let (version, rest) = read_varint(&bytes)?;
+        if version != 1 {
+            return None;
+        }
+        let (codec, multihash) = read_varint(rest)?;
+        valid_multihash(multihash).then(|| Cid {
+            codec,
+            multihash: multihash.to_vec(),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![1];
+        write_varint(&mut bytes, self.codec);
+        bytes.extend_from_slice(&self.multihash);
+        bytes
+    }
+}
+
+/// Canonical (CIDv1 base32) form of a CID, or None if `s` isn't a valid CID.
+pub fn canonical_cid(s: &str) -> Option<String> {
+    let cid = Cid::parse(s)?;
+    Some(format!("b{}", encode_base32(&cid.to_bytes())))
+}
+
+/// Canonical form of an IPNS name: public keys (as a CID or a bare peer ID) become
+/// CIDv1 base36, DNSLink names are lowercased. None if `s` is neither.
+pub fn canonical_ipns_name(s: &str) -> Option<String> {
+    let multihash = match Cid::parse(s) {
+        Some(cid) => Some(cid.multihash),
+        // Legacy peer IDs: a bare base58 multihash ("Qm..." RSA, "12D3KooW..." ed25519)
+        None if s.starts_with("Qm") || s.starts_with("12D3KooW") => {
+            decode_radix(s, BASE58).filter(|mh| valid_multihash(mh))
+        }
+        None => None,
+    };
+    if let Some(multihash) = multihash {
+        // Only sha2-256 (RSA) and identity (ed25519) hashes are peer IDs
+        let (code, _) = read_varint(&multihash)?;
+        if code != SHA2_256 && code != IDENTITY {
+            return None;
+        }
+        let cid = Cid { codec: LIBP2P_KEY, multihash };
+        return Some(format!("k{}", encode_radix(&cid.to_bytes(), BASE36)));
+    }
+
+    // DNSLink: a registered domain name
+    let name = s.to_ascii_lowercase();
+    let valid = name.contains('.')
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+    valid.then_some(name)
+}
+
+/// Canonical URL for a namespace (`ipfs` or `ipns`), a CID or name, and the rest
+/// of the address (path, query, fragment).
+fn canonical_address(namespace: &str, name: &str, rest: &str) -> Option<Url> {
+    let name = match namespace {
+        "ipfs" => canonical_cid(name)?,
+        "ipns" => canonical_ipns_name(name)?,
+        _ => return None,
+    };
+    let rest = if rest.is_empty() || rest.starts_with(['/', '?', '#']) {
+        rest.to_string()
+    } else {
+        format!("/{}", rest)
+    };
+    Url::parse(&format!("{}://{}{}", namespace, name, rest)).ok()
+}
+
+/// Parse an IPFS address in text form: `ipfs://<cid>/...`, `ipns://<name>/...`,
+/// `/ipfs/<cid>/...` or `/ipns/<name>/...`.
+pub fn parse_address(s: &str) -> Option<Url> {
+    let (namespace, address) = ["ipfs", "ipns"].into_iter().find_map(|namespace| {
+        s.strip_prefix(namespace)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .or_else(|| s.strip_prefix('/')?.strip_prefix(namespace)?.strip_prefix('/'))
+            .map(|address| (namespace, address))
+    })?;
+    let end = address.find(['/', '?', '#']).unwrap_or(address.len());
+    canonical_address(namespace, &address[..end], &address[end..])
+}
+
+/// Canonical `ipfs://` / `ipns://` URL for `url` if it addresses IPFS content:
+/// a native URL, a gateway path or a subdomain gateway.
+pub fn canonical_url(url: &Url) -> Option<Url> {
+    let rest = || {
+        let mut rest = url.path().to_string();
+        if let Some(query) = url.query() {
+            rest.push('?');
+            rest.push_str(query);
+        }
+        if let Some(fragment) = url.fragment() {
+            rest.push('#');
+            rest.push_str(fragment);
+        }
+        rest
+    };
+    match url.scheme() {
+        namespace @ ("ipfs" | "ipns") => canonical_address(namespace, url.host_str()?, &rest()),
+        "http" | "https" => {
+            // Subdomain gateway: <cid>.ipfs.<gateway host>
+            let host = url.host_str()?;
+            let mut labels = host.splitn(3, '.');
+            if let (Some(name), Some(namespace @ ("ipfs" | "ipns")), Some(_)) =
+                (labels.next(), labels.next(), labels.next())
+            {
+                // DNSLink names are inlined into one label: "." → "-", "-" → "--"
+                let name = match namespace {
+                    "ipns" => name.replace("--", "\0").replace('-', ".").replace('\0', "-"),
+                    _ => name.to_string(),
+                };
+                if let Some(canonical) = canonical_address(namespace, &name, &rest()) {
+                    return Some(canonical);
+                }
+            }
+            // Path gateway: /ipfs/<cid>/...
+            let path = url.path();
+            if path.starts_with("/ipfs/") || path.starts_with("/ipns/") {
+                let mut address = path.to_string();
+                if let Some(query) = url.query() {
+                    address.push('?');
+                    address.push_str(query);
+                }
+                return parse_address(&address);
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Root CID of a gateway response, from its `X-Ipfs-Roots` header (one CID per
+/// segment of the resolved content path; the first is the root).
+pub fn root_cid(roots: &str) -> Option<String> {
+    canonical_cid(roots.split(',').next()?.trim())
+}
+
+/// CID of the node a gateway's content path resolved to: the last of `X-Ipfs-Roots`.
+pub fn target_cid(roots: &str) -> Option<String> {
+    canonical_cid(roots.split(',').next_back()?.trim())
+}
+
+fn valid_multihash(bytes: &[u8]) -> bool {
+    let Some((_, rest)) = read_varint(bytes) else {
+        return false;
+    };
+    matches!(read_varint(rest), Some((len, digest)) if digest.len() as u64 == len && len > 0)
+}
+
+/// Unsigned LEB128 varint: (value, remaining bytes)
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    for (i, &b) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(b & 0x7f) << (7 * i);
+        if b & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// RFC 4648 base32, lowercase, unpadded
+fn encode_base32(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &b in bytes {
+        buffer = (buffer << 8) | u32::from(b);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    out
+}
+
+fn decode_base32(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in s.bytes() {
+        let value = BASE32.iter().position(|&a| a == c.to_ascii_lowercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+fn decode_base16(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Big-number base conversion (base36, base58btc). Leading zero digits are zero bytes.
+fn decode_radix(s: &str, alphabet: &[u8]) -> Option<Vec<u8>> {
+    let base = alphabet.len() as u32;
+    let mut bytes: Vec<u8> = Vec::new(); // little-endian
+    for c in s.bytes() {
+        let mut carry = alphabet.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * base;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = s.bytes().take_while(|&c| c == alphabet[0]).count();
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Some(bytes)
+}
+
+fn encode_radix(bytes: &[u8], alphabet: &[u8]) -> String {
+    let base = alphabet.len() as u32;
+    let mut digits: Vec<u8> = Vec::new(); // little-endian
+    for &b in bytes {
+        let mut carry = u32::from(b);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % base) as u8;
+            carry /= base;
+        }
+        while carry > 0 {
+            digits.push((carry % base) as u8);
+            carry /= base;
+        }
+    }
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    std::iter::repeat_n(alphabet[0] as char, zeros)
+        .chain(digits.iter().rev().map(|&d| alphabet[d as usize] as char))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The same CID in every form: CIDv0 and its CIDv1 base32 (dag-pb) equivalent
+    const V0: &str = "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR";
+    const V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    /// An ed25519 peer ID (identity multihash) and its CIDv1 base36 IPNS form
+    const PEER_ID: &str = "12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA";
+
+    fn cid_bytes() -> Vec<u8> {
+        Cid::parse(V1).unwrap().to_bytes()
+    }
+
+    #[test]
+    fn cidv0_becomes_cidv1_base32() {
+        assert_eq!(canonical_cid(V0).as_deref(), Some(V1));
+        assert_eq!(canonical_cid(V1).as_deref(), Some(V1));
+    }
+
+    #[test]
+    fn every_multibase_maps_to_one_cid() {
+        let bytes = cid_bytes();
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let forms = [
+            V1.to_ascii_uppercase(),
+            format!("k{}", encode_radix(&bytes, BASE36)),
+            format!("K{}", encode_radix(&bytes, BASE36).to_ascii_uppercase()),
+            format!("z{}", encode_radix(&bytes, BASE58)),
+            format!("f{}", hex),
+            format!("F{}", hex.to_ascii_uppercase()),
+        ];
+        for form in forms {
+            assert_eq!(canonical_cid(&form).as_deref(), Some(V1), "{}", form);
+        }
+    }
+
+    #[test]
+    fn malformed_cids_are_rejected() {
+        // Truncated digest, unknown multibase, odd-length hex, CIDv0 that isn't base58
+        assert_eq!(canonical_cid(&V1[..V1.len() - 4]), None);
+        assert_eq!(canonical_cid("mAXASIA"), None);
+        assert_eq!(canonical_cid("f017"), None);
+        assert_eq!(canonical_cid("Qm0000000000000000000000000000000000000000000000"), None);
+        // CIDv2 does not exist
+        let mut bytes = cid_bytes();
+        bytes[0] = 2;
+        assert_eq!(canonical_cid(&format!("b{}", encode_base32(&bytes))), None);
+    }
+
+    #[test]
+    fn ipns_keys_become_cidv1_base36() {
+        let name = canonical_ipns_name(PEER_ID).unwrap();
+        assert!(name.starts_with("k51"), "{}", name);
+        // The base36 form, and the key as a base32 CID, are the same name
+        assert_eq!(canonical_ipns_name(&name).as_deref(), Some(name.as_str()));
+        let key = Cid::parse(&name).unwrap();
+        assert_eq!(key.codec, LIBP2P_KEY);
+        let base32 = format!("b{}", encode_base32(&key.to_bytes()));
+        assert_eq!(canonical_ipns_name(&base32), Some(name));
+    }
+
+    #[test]
+    fn dnslink_names_are_lowercased_domains() {
+        assert_eq!(canonical_ipns_name("Docs.IPFS.tech").as_deref(), Some("docs.ipfs.tech"));
+        assert_eq!(canonical_ipns_name("localhost"), None);
+        assert_eq!(canonical_ipns_name("-bad.example"), None);
+        assert_eq!(canonical_ipns_name("under_score.example"), None);
+    }
+
+    #[test]
+    fn addresses_in_every_form_share_one_url() {
+        let expected = format!("ipfs://{}/wiki/Index.html?a=1", V1);
+        let forms = [
+            format!("ipfs://{}/wiki/Index.html?a=1", V0),
+            format!("https://ipfs.io/ipfs/{}/wiki/Index.html?a=1", V0),
+            format!("https://{}.ipfs.dweb.link/wiki/Index.html?a=1", V1),
+        ];
+        for form in forms {
+            let url = Url::parse(&form).unwrap();
+            assert_eq!(canonical_url(&url).map(String::from).as_deref(), Some(expected.as_str()), "{}", form);
+        }
+        assert_eq!(
+            parse_address(&format!("/ipfs/{}/wiki/", V0)).map(String::from),
+            Some(format!("ipfs://{}/wiki/", V1))
+        );
+        // Not IPFS content
+        assert_eq!(canonical_url(&Url::parse("https://example.com/ipfs/notacid").unwrap()), None);
+    }
+
+    #[test]
+    fn inlined_dnslink_subdomains_are_expanded() {
+        let url = Url::parse("https://en-wikipedia--on--ipfs-org.ipns.dweb.link/wiki/").unwrap();
+        assert_eq!(
+            canonical_url(&url).map(String::from).as_deref(),
+            Some("ipns://en.wikipedia-on-ipfs.org/wiki/")
+        );
+    }
+
+    #[test]
+    fn roots_header_gives_root_and_target() {
+        let mut file = cid_bytes();
+        *file.last_mut().unwrap() ^= 1;
+        let target = format!("f{}", file.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+        let header = format!("{}, {}", V0, target);
+        assert_eq!(root_cid(&header).as_deref(), Some(V1));
+        assert_eq!(target_cid(&header), Some(format!("b{}", encode_base32(&file))));
+        assert_eq!(root_cid("garbage"), None);
+    }
+}
@@ -0,0 +1,46 @@
+//! Page language helpers shared by storage and search.
+
+/// Detection confidence from which the detected language overrides the declared
+/// `<html lang>` (templates are often left at "en").
+pub const MIN_DETECTION_CONFIDENCE: f64 = 0.5;
+
+/// Primary subtag of a language tag, lowercased ("en-US" → "en", "pt_BR" → "pt").
+pub fn primary_subtag(tag: &str) -> String {
+    tag.split(['-', '_']).next().unwrap_or(tag).trim().to_lowercase()
+}
+
+/// Postgres text-search configuration for a language (ISO 639-1 code or full tag).
+/// Languages without a stemmer in Postgres 16 use "simple".
+pub fn text_search_config(language: &str) -> &'static str {
+    match primary_subtag(language).as_str() {
+        "ar" => "arabic",
+        "hy" => "armenian",
+        "eu" => "basque",
+        "ca" => "catalan",
+        "da" => "danish",
+        "nl" => "dutch",
+        "en" => "english",
+        "fi" => "finnish",
+        "fr" => "french",
+        "de" => "german",
+        "el" => "greek",
+        "hi" => "hindi",
+        "hu" => "hungarian",
+        "id" => "indonesian",
+        "ga" => "irish",
+        "it" => "italian",
+        "lt" => "lithuanian",
+        "ne" => "nepali",
+        "no" | "nb" | "nn" => "norwegian",
+        "pt" => "portuguese",
+        "ro" => "romanian",
+        "ru" => "russian",
+        "sr" => "serbian",
+        "es" => "spanish",
+        "sv" => "swedish",
+        "ta" => "tamil",
+        "tr" => "turkish",
+        "yi" => "yiddish",
+        _ => "simple",
+    }
+}
@@ -1,5 +1,8 @@
 pub mod config;
 pub mod error;
+pub mod ip_range;
+pub mod ipfs;
+pub mod language;
 pub mod types;
 
 pub use config::AppConfig;
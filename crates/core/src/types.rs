@@ -11,11 +11,38 @@ use crate::error::CrawlError;
 pub trait NetworkDriver: Send + Sync + 'static {
     fn name(&self) -> &str;
     fn can_handle(&self, url: &Url) -> bool;
-    /// Fetch with progressive timeout based on retry_count (0=first, 1+=retries)
+    /// Fetch with progressive timeout based on retry_count (0=first, 1+=retries).
+    /// HTTP-based drivers send `config.validators` as conditional headers; a 304
+    /// response means the stored copy is still current.
     async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError>;
     fn max_concurrency(&self) -> usize;
     fn default_delay(&self) -> Duration;
 
+    /// Probe every proxy behind this driver. The network is usable while at least
+    /// one proxy is `Ready`. Default: nothing to probe (always usable).
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        Vec::new()
+    }
+
+    /// Other addresses of the fetched site that the response reveals, e.g. the
+    /// cryptographic address behind a human-readable name. Default: none.
+    fn alternate_addresses(&self, _url: &Url, _headers: &HashMap<String, String>, _body: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Whether the host of `url` is derived from a public key (permanent, can't be
+    /// hijacked) in a way the frontier can't tell from the host name alone, e.g. a mesh
+    /// IP in the network's configured ranges. Default: false.
+    fn is_key_address(&self, _url: &Url) -> bool {
+        false
+    }
+
+    /// Whether the content at `url` can never change (content-addressed), so a page
+    /// stored once is never fetched or revisited again. Default: false.
+    fn is_immutable(&self, _url: &Url) -> bool {
+        false
+    }
+
     /// Network-specific retry configuration
     /// Returns (clear_dead_on_startup, periodic_retry_interval_secs)
     /// - clear_dead_on_startup: Whether to clear dead URLs from previous sessions
@@ -68,12 +95,44 @@ pub trait NetworkDriver: Send + Sync + 'static {
     }
 }
 
+/// Readiness of one proxy, from `NetworkDriver::health_check`.
+#[derive(Debug, Clone)]
+pub struct ProxyHealth {
+    pub proxy: String,
+    pub state: ProxyState,
+    /// Why the proxy isn't ready (error or what the probe saw)
+    pub detail: Option<String>,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyState {
+    /// Accepting requests
+    Ready,
+    /// Reachable but not usable yet (tunnels/peers still being built, setup wizard)
+    Bootstrapping,
+    /// Not reachable
+    Down,
+}
+
+impl ProxyState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyState::Ready => "ready",
+            ProxyState::Bootstrapping => "bootstrapping",
+            ProxyState::Down => "down",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FetchConfig {
     pub timeout: Duration,
     pub max_body_size: usize,
     pub follow_redirects: bool,
     pub user_agent: String,
+    /// Validators of the stored copy; set when re-fetching a known URL
+    pub validators: Validators,
 }
 
 impl Default for FetchConfig {
@@ -83,7 +142,34 @@ impl Default for FetchConfig {
             max_body_size: 10 * 1024 * 1024,
             follow_redirects: true,
             user_agent: String::new(),
+            validators: Validators::default(),
+        }
+    }
+}
+
+/// ETag / Last-Modified from the previous fetch of a URL. Drivers send them as
+/// conditional headers, so an unchanged page comes back as a bodiless 304.
+#[derive(Debug, Clone, Default)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Conditional request headers (If-None-Match, If-Modified-Since).
+    pub fn request_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push(("If-None-Match", etag.clone()));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push(("If-Modified-Since", last_modified.clone()));
         }
+        headers
     }
 }
 
@@ -104,8 +190,12 @@ pub struct FetchResponse {
 /// What we extract from every page
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PageData {
+    /// URL as fetched (what the server serves)
     pub url: String,
     pub final_url: String,
+    /// Canonical form of `url`: the dedup key for revisits, validators and feeds
+    #[serde(default)]
+    pub canonical_url: Option<String>,
     pub network: String,
     pub title: Option<String>,
     pub h1: Vec<String>,
@@ -122,6 +212,43 @@ pub struct PageData {
     pub status_code: u16,
     pub domain: String,
     pub content_type: Option<String>,
+    /// Encoding the body was decoded from (WHATWG name, e.g. "windows-1251")
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Topic relevance in [0, 1] (None when no topic profile is configured)
+    pub relevance: Option<f64>,
+    /// Set when the page is an RSS/Atom feed
+    #[serde(default)]
+    pub feed: Option<Feed>,
+    /// SHA-256 of the raw body when it is kept in the blob store (raw_html is then not stored inline)
+    #[serde(default)]
+    pub body_hash: Option<String>,
+}
+
+impl PageData {
+    /// The key this page is stored and scheduled under: its canonical URL when the
+    /// crawler set one, else the fetched URL.
+    pub fn key(&self) -> &str {
+        self.canonical_url.as_deref().unwrap_or(&self.url)
+    }
+}
+
+/// An RSS or Atom feed: its items are crawled and the feed is polled periodically.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feed {
+    /// "rss" or "atom"
+    pub format: String,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedItem {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    /// RSS guid / Atom id
+    pub guid: Option<String>,
+    /// pubDate / published, falling back to updated
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -135,6 +262,12 @@ pub struct ExtractedEntities {
     pub phone_numbers: Vec<String>,
     pub pgp_fingerprints: Vec<String>,
     pub usernames: Vec<String>,
+    /// "lat,lon" positions from the EXIF/XMP of the page's images
+    #[serde(default)]
+    pub gps_coordinates: Vec<String>,
+    /// Camera body serial numbers from the EXIF/XMP of the page's images
+    #[serde(default)]
+    pub camera_serials: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,26 +280,77 @@ pub struct ExtractedLink {
     pub is_hyphanet: bool,
     pub is_lokinet: bool,
     pub is_external: bool,
+    /// Network whose driver routes the target (set by the crawler; None when no
+    /// enabled driver handles it)
+    #[serde(default)]
+    pub network: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PageMetadata {
     pub server_header: Option<String>,
     pub powered_by: Option<String>,
+    /// Cache validators, sent back as conditional headers on the next fetch
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
     pub meta_description: Option<String>,
     pub meta_keywords: Vec<String>,
+    /// Declared language (`<html lang>`, gemtext lang parameter)
     pub language: Option<String>,
+    /// Language identified from body_text (ISO 639-1), with detection confidence in [0, 1]
+    #[serde(default)]
+    pub detected_language: Option<String>,
+    #[serde(default)]
+    pub language_confidence: Option<f64>,
+    /// Languages of the text with their share of it, largest first (several on mixed pages)
+    #[serde(default)]
+    pub languages: Vec<(String, f64)>,
     pub has_login_form: bool,
     pub has_search_form: bool,
     pub open_graph: HashMap<String, String>,
 }
 
+impl PageMetadata {
+    /// The page's language: the detected one when detection is confident,
+    /// otherwise the declared one (primary subtag, e.g. "en").
+    pub fn effective_language(&self) -> Option<String> {
+        let confident = self
+            .language_confidence
+            .is_some_and(|c| c >= crate::language::MIN_DETECTION_CONFIDENCE);
+        match (&self.detected_language, &self.language) {
+            (Some(detected), _) if confident => Some(detected.clone()),
+            (_, Some(declared)) => Some(crate::language::primary_subtag(declared)),
+            (detected, None) => detected.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CrawlJob {
     pub url: Url,
+    /// In-site depth: links followed within this domain (0 = first page seen on the domain)
     pub depth: u32,
+    /// New domains crossed since the seed (0 = seed domain)
+    pub domain_hops: u32,
     pub source_url: Option<String>,
     pub network: String,
     pub priority: f64,
     pub retry_count: u32,
 }
+
+/// A known page scheduled to be fetched again: the key it is stored under and the job
+/// that first fetched it, so a revisit re-enters the crawl where the page was found.
+#[derive(Debug, Clone)]
+pub struct RevisitTarget {
+    /// Canonical URL (the dedup key)
+    pub key: String,
+    /// URL as fetched
+    pub url: String,
+    pub network: String,
+    pub domain: String,
+    pub depth: u32,
+    pub domain_hops: u32,
+    pub source_url: Option<String>,
+}
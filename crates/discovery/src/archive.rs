@@ -0,0 +1,376 @@
+use std::io::Cursor;
+
+use serde::Serialize;
+
+/// Entries listed per archive; the rest are only counted.
+const MAX_LISTED_ENTRIES: usize = 10_000;
+
+/// A file inside an archive, as listed by its headers (nothing is extracted).
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size: u64,
+    pub compressed_size: Option<u64>,
+    /// RFC 3339, or the archive's local time without offset (ZIP, RAR4 DOS timestamps)
+    pub modified_at: Option<String>,
+    pub is_dir: bool,
+    pub encrypted: bool,
+}
+
+/// Listing of an archive's headers.
+pub(crate) struct ArchiveListing {
+    pub format: &'static str,
+    pub entries: Vec<ArchiveEntry>,
+    /// Entries in the archive, including those beyond the listing limit
+    pub entry_count: usize,
+    /// Header encryption hides the listing (RAR -hp, 7z -mhe)
+    pub headers_encrypted: bool,
+}
+
+/// Archive formats, by magic bytes.
+pub(crate) fn archive_format(body: &[u8]) -> Option<&'static str> {
+    if body.starts_with(b"PK\x03\x04") || body.starts_with(b"PK\x05\x06") {
+        Some("zip")
+    } else if body.starts_with(b"Rar!\x1a\x07") {
+        Some("rar")
+    } else if body.starts_with(b"7z\xbc\xaf\x27\x1c") {
+        Some("7z")
+    } else {
+        None
+    }
+}
+
+/// List an archive from its headers: ZIP central directory, RAR4/RAR5 file headers,
+/// 7z header database. Returns None if the headers can't be read.
+pub(crate) fn list_archive(body: &[u8]) -> Option<ArchiveListing> {
+    match archive_format(body)? {
+        "zip" => list_zip(body),
+        "rar" => list_rar(body),
+        "7z" => list_7z(body),
+        _ => None,
+    }
+}
+
+fn list_zip(body: &[u8]) -> Option<ArchiveListing> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(body)).ok()?;
+    let entry_count = archive.len();
+    let mut entries = Vec::new();
+    for i in 0..entry_count.min(MAX_LISTED_ENTRIES) {
+        let Ok(file) = archive.by_index_raw(i) else {
+            continue;
+        };
+        entries.push(ArchiveEntry {
+            path: file.name().to_string(),
+            size: file.size(),
+            compressed_size: Some(file.compressed_size()),
+            modified_at: file.last_modified().and_then(|d| {
+                local_time(
+                    d.year() as i32,
+                    d.month() as u32,
+                    d.day() as u32,
+                    d.hour() as u32,
+                    d.minute() as u32,
+                    d.second() as u32,
+                )
+            }),
+            is_dir: file.is_dir(),
+            encrypted: file.encrypted(),
+        });
+    }
+    Some(ArchiveListing {
+        format: "zip",
+        entries,
+        entry_count,
+        headers_encrypted: false,
+    })
+}
+
+fn list_7z(body: &[u8]) -> Option<ArchiveListing> {
+    let mut cursor = Cursor::new(body);
+    match sevenz_rust::Archive::read(&mut cursor, body.len() as u64, b"") {
+        Ok(archive) => {
+            let entry_count = archive.files.len();
+            // A file is encrypted when its folder's coder chain includes AES
+            let encrypted_folder = |file_index: usize| {
+                archive
+                    .stream_map
+                    .file_folder_index
+                    .get(file_index)
+                    .copied()
+                    .flatten()
+                    .and_then(|folder| archive.folders.get(folder))
+                    .is_some_and(|folder| {
+                        folder.coders.iter().any(|c| {
+                            c.decompression_method_id()
+                                == sevenz_rust::SevenZMethod::ID_AES256SHA256
+                        })
+                    })
+            };
+            let entries = archive
+                .files
+                .iter()
+                .enumerate()
+                .take(MAX_LISTED_ENTRIES)
+                .map(|(i, f)| ArchiveEntry {
+                    path: f.name.clone(),
+                    size: f.size,
+                    compressed_size: None,
+                    modified_at: f
+                        .has_last_modified_date
+                        .then(|| filetime(f.last_modified_date.to_raw()))
+                        .flatten(),
+                    is_dir: f.is_directory,
+                    encrypted: encrypted_folder(i),
+                })
+                .collect();
+            Some(ArchiveListing {
+                format: "7z",
+                entries,
+                entry_count,
+                headers_encrypted: false,
+            })
+        }
+        // Encrypted header database: the file names are behind the password
+        Err(e) if is_password_error(&e) => Some(ArchiveListing {
+            format: "7z",
+            entries: Vec::new(),
+            entry_count: 0,
+            headers_encrypted: true,
+        }),
+        Err(_) => None,
+    }
+}
+
+/// AES is compiled out, so an encrypted header shows up as an unsupported method.
+fn is_password_error(error: &sevenz_rust::Error) -> bool {
+    match error {
+        sevenz_rust::Error::PasswordRequired | sevenz_rust::Error::MaybeBadPassword(_) => true,
+        sevenz_rust::Error::UnsupportedCompressionMethod(method) => method.contains("AES"),
+        _ => false,
+    }
+}
+
+fn list_rar(body: &[u8]) -> Option<ArchiveListing> {
+    if body.starts_with(b"Rar!\x1a\x07\x01\x00") {
+        list_rar5(body)
+    } else {
+        list_rar4(body)
+    }
+}
+
+/// RAR 1.5–4.x: fixed-layout block headers after the 7-byte signature.
+fn list_rar4(body: &[u8]) -> Option<ArchiveListing> {
+    let mut listing = ArchiveListing {
+        format: "rar",
+        entries: Vec::new(),
+        entry_count: 0,
+        headers_encrypted: false,
+    };
+    let mut pos = 7;
+    while pos + 7 <= body.len() {
+        let block_type = body[pos + 2];
+        let flags = u16_le(body, pos + 3)?;
+        let header_size = u16_le(body, pos + 5)? as usize;
+        if header_size < 7 {
+            break;
+        }
+        let mut data_size = 0u64;
+        match block_type {
+            // Main header: headers are encrypted from here on
+            0x73 if flags & 0x0080 != 0 => {
+                listing.headers_encrypted = true;
+                break;
+            }
+            0x74 => {
+                let pack_size = u32_le(body, pos + 7)? as u64;
+                let unpacked = u32_le(body, pos + 11)? as u64;
+                let dos_time = u32_le(body, pos + 20)?;
+                let name_size = u16_le(body, pos + 26)? as usize;
+                let (high_pack, high_unpacked, name_at) = if flags & 0x0100 != 0 {
+                    (
+                        u32_le(body, pos + 32)? as u64,
+                        u32_le(body, pos + 36)? as u64,
+                        pos + 40,
+                    )
+                } else {
+                    (0, 0, pos + 32)
+                };
+                // Unicode names: "ascii\0encoded"; the ASCII part is enough for a listing
+                let raw_name = body.get(name_at..name_at + name_size)?;
+                let raw_name = raw_name.split(|&b| b == 0).next().unwrap_or(raw_name);
+                data_size = (high_pack << 32) | pack_size;
+                listing.entry_count += 1;
+                if listing.entries.len() < MAX_LISTED_ENTRIES {
+                    listing.entries.push(ArchiveEntry {
+                        path: String::from_utf8_lossy(raw_name).replace('\\', "/"),
+                        size: (high_unpacked << 32) | unpacked,
+                        compressed_size: Some(data_size),
+                        modified_at: dos_datetime(dos_time),
+                        is_dir: flags & 0x00E0 == 0x00E0,
+                        encrypted: flags & 0x0004 != 0,
+                    });
+                }
+            }
+            0x7B => break,
+            _ => {
+                if flags & 0x8000 != 0 {
+                    data_size = u32_le(body, pos + 7)? as u64;
+                }
+            }
+        }
+        pos = pos
+            .checked_add(header_size)?
+            .checked_add(usize::try_from(data_size).ok()?)?;
+    }
+    Some(listing)
+}
+
+/// RAR 5.0: CRC32, vint header size, then a vint-encoded header of that size.
+fn list_rar5(body: &[u8]) -> Option<ArchiveListing> {
+    let mut listing = ArchiveListing {
+        format: "rar",
+        entries: Vec::new(),
+        entry_count: 0,
+        headers_encrypted: false,
+    };
+    let mut pos = 8;
+    while pos + 4 < body.len() {
+        let mut cursor = pos + 4;
+        let header_size = vint(body, &mut cursor)? as usize;
+        let header_start = cursor;
+        let header_end = header_start.checked_add(header_size)?;
+        if header_size == 0 || header_end > body.len() {
+            break;
+        }
+        let header_type = vint(body, &mut cursor)?;
+        let header_flags = vint(body, &mut cursor)?;
+        if header_flags & 0x1 != 0 {
+            vint(body, &mut cursor)?; // extra area size
+        }
+        let data_size = if header_flags & 0x2 != 0 {
+            vint(body, &mut cursor)?
+        } else {
+            0
+        };
+        match header_type {
+            2 => {
+                let file_flags = vint(body, &mut cursor)?;
+                let unpacked = vint(body, &mut cursor)?;
+                vint(body, &mut cursor)?; // attributes
+                let mtime = if file_flags & 0x2 != 0 {
+                    let t = u32_le(body, cursor)?;
+                    cursor += 4;
+                    chrono::DateTime::from_timestamp(t as i64, 0).map(|d| d.to_rfc3339())
+                } else {
+                    None
+                };
+                if file_flags & 0x4 != 0 {
+                    cursor += 4; // data CRC32
+                }
+                vint(body, &mut cursor)?; // compression info
+                vint(body, &mut cursor)?; // host OS
+                let name_len = vint(body, &mut cursor)? as usize;
+                let name = body.get(cursor..cursor.checked_add(name_len)?)?;
+                // Encryption record (0x01) in the extra area marks an encrypted file
+                let extra = body.get(cursor + name_len..header_end).unwrap_or(&[]);
+                let encrypted = header_flags & 0x1 != 0 && has_extra_record(extra, 0x01);
+                listing.entry_count += 1;
+                if listing.entries.len() < MAX_LISTED_ENTRIES {
+                    listing.entries.push(ArchiveEntry {
+                        path: String::from_utf8_lossy(name).into_owned(),
+                        size: unpacked,
+                        compressed_size: Some(data_size),
+                        modified_at: mtime,
+                        is_dir: file_flags & 0x1 != 0,
+                        encrypted,
+                    });
+                }
+            }
+            // Archive encryption header: every following header is encrypted
+            4 => {
+                listing.headers_encrypted = true;
+                break;
+            }
+            5 => break,
+            _ => {}
+        }
+        pos = header_end.checked_add(usize::try_from(data_size).ok()?)?;
+    }
+    Some(listing)
+}
+
+/// Whether a RAR5 extra area holds a record of the given type.
+fn has_extra_record(mut extra: &[u8], wanted: u64) -> bool {
+    while !extra.is_empty() {
+        let mut cursor = 0;
+        let Some(size) = vint(extra, &mut cursor) else {
+            return false;
+        };
+        let record_start = cursor;
+        let Some(record_type) = vint(extra, &mut cursor) else {
+            return false;
+        };
+        if record_type == wanted {
+            return true;
+        }
+        let Some(rest) = extra.get(record_start + size as usize..) else {
+            return false;
+        };
+        extra = rest;
+    }
+    false
+}
+
+/// RAR5 variable-length integer: 7 bits per byte, high bit = more bytes follow.
+fn vint(body: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *body.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn u16_le(body: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(body.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn u32_le(body: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(body.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// MS-DOS date/time (local time of the packing machine).
+fn dos_datetime(value: u32) -> Option<String> {
+    let (date, time) = (value >> 16, value & 0xFFFF);
+    local_time(
+        (date >> 9) as i32 + 1980,
+        (date >> 5) & 0xF,
+        date & 0x1F,
+        time >> 11,
+        (time >> 5) & 0x3F,
+        (time & 0x1F) * 2,
+    )
+}
+
+fn local_time(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> Option<String> {
+    chrono::NaiveDate::from_ymd_opt(year, month, day)?
+        .and_hms_opt(hour, minute, second)
+        .map(|d| d.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+/// Windows FILETIME (100 ns ticks since 1601) as RFC 3339.
+fn filetime(ticks: u64) -> Option<String> {
+    let secs = (ticks / 10_000_000) as i64 - 11_644_473_600;
+    chrono::DateTime::from_timestamp(secs, 0).map(|d| d.to_rfc3339())
+}
@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use scraper::{Html, Node};
+
+/// Phishing-clone detection for well-known onion services.
+/// Lookalike onions share a vanity prefix and serve scraped HTML with the
+/// payment addresses swapped. Sites are grouped by near-identical content
+/// or DOM structure plus vanity-prefix similarity; the canonical member is
+/// chosen by signed-mirror / seed provenance and the rest are flagged.
+pub struct CloneDetector;
+
+/// Per-site fingerprint (taken from the site's front page).
+#[derive(Debug, Clone)]
+pub struct SiteFingerprint {
+    pub domain: String,
+    pub network: String,
+    /// SimHash of word 3-shingles of the visible text
+    pub content_simhash: u64,
+    /// SimHash of tag-name 4-grams in document order
+    pub dom_simhash: u64,
+    pub first_seen_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SuspectedClone {
+    pub clone_domain: String,
+    pub canonical_domain: String,
+    pub content_distance: u32,
+    pub dom_distance: u32,
+    pub vanity_prefix_len: usize,
+    /// Why the two sites were grouped: near_identical_content, vanity_prefix_dom
+    pub reason: String,
+    /// Why the canonical site was picked: trusted_provenance, earliest_seen
+    pub canonical_basis: String,
+}
+
+/// An entity present on only one side of a clone/original pair.
+#[derive(Debug, Clone)]
+pub struct EntityDiff {
+    pub entity_type: String,
+    pub value: String,
+    /// clone_only or canonical_only
+    pub side: String,
+    /// Both sides carry this entity type with different values (e.g. swapped BTC address)
+    pub substituted: bool,
+}
+
+/// Max Hamming distance between content SimHashes for "near-identical content".
+const CONTENT_DISTANCE: u32 = 3;
+/// Max Hamming distance between DOM SimHashes when the vanity prefix also matches.
+const DOM_DISTANCE: u32 = 6;
+/// Shared leading onion characters that count as a vanity prefix.
+/// Chance of 5 random base32 chars matching is ~1 in 33 million.
+const MIN_VANITY_PREFIX: usize = 5;
+/// Word 3-shingles a page needs for its content SimHash to mean anything. Near-empty
+/// pages ("Loading...", bare login forms) would otherwise all look alike.
+const MIN_CONTENT_SHINGLES: usize = 8;
+/// Tag 4-grams a page needs for its DOM SimHash to mean anything.
+const MIN_DOM_SHINGLES: usize = 8;
+/// Content SimHashes are bucketed by 16-bit bands: two hashes within CONTENT_DISTANCE
+/// (3) bits share at least one of the 4 bands (pigeonhole), so only pages sharing a
+/// band need comparing.
+const CONTENT_BANDS: u32 = 4;
+
+/// Entity types compared between clone and original.
+pub const DIFF_ENTITY_TYPES: &[&str] = &[
+    "bitcoin",
+    "monero",
+    "ethereum",
+    "email",
+    "onion_address",
+    "pgp_fingerprint",
+];
+
+impl CloneDetector {
+    /// Fingerprint a page's visible text and HTML structure.
+    /// Returns (content_simhash, dom_simhash), or None when the page has too little text
+    /// or markup to tell it apart from other near-empty pages.
+    pub fn fingerprint(body_text: &str, html: &str) -> Option<(u64, u64)> {
+        let words: Vec<String> = body_text
+            .split_whitespace()
+            .map(|w| w.to_lowercase())
+            .collect();
+        if words.len() < MIN_CONTENT_SHINGLES + 2 {
+            return None;
+        }
+        let content = simhash(words.windows(3).map(|w| w.join(" ")));
+
+        let document = Html::parse_document(html);
+        let tags: Vec<&str> = document
+            .tree
+            .nodes()
+            .filter_map(|n| match n.value() {
+                Node::Element(el) => Some(el.name()),
+                _ => None,
+            })
+            .collect();
+        if tags.len() < MIN_DOM_SHINGLES + 3 {
+            return None;
+        }
+        let dom = simhash(tags.windows(4).map(|w| w.join(">")));
+
+        Some((content, dom))
+    }
+
+    /// Number of leading characters two onion hosts share (0 for other networks).
+    pub fn vanity_prefix_len(a: &str, b: &str) -> usize {
+        let (Some(a), Some(b)) = (a.strip_suffix(".onion"), b.strip_suffix(".onion")) else {
+            return 0;
+        };
+        a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+    }
+
+    /// Group sites into clone clusters and flag every non-canonical member.
+    /// `trusted` holds domains with strong provenance (seeds, verified signed mirrors).
+    pub fn find_clones(sites: &[SiteFingerprint], trusted: &HashSet<String>) -> Vec<SuspectedClone> {
+        let mut parent: Vec<usize> = (0..sites.len()).collect();
+        // Remember why each pair was linked, for reporting
+        let mut edges: HashMap<(usize, usize), &'static str> = HashMap::new();
+
+        for (i, j) in Self::candidate_pairs(sites) {
+            let (a, b) = (&sites[i], &sites[j]);
+            if a.network != b.network || a.domain == b.domain {
+                continue;
+            }
+            let content = (a.content_simhash ^ b.content_simhash).count_ones();
+            let reason = if content <= CONTENT_DISTANCE {
+                "near_identical_content"
+            } else if (a.dom_simhash ^ b.dom_simhash).count_ones() <= DOM_DISTANCE
+                && Self::vanity_prefix_len(&a.domain, &b.domain) >= MIN_VANITY_PREFIX
+            {
+                "vanity_prefix_dom"
+            } else {
+                continue;
+            };
+            edges.insert((i, j), reason);
+            let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+            if ri != rj {
+                parent[rj] = ri;
+            }
+        }
+
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for i in 0..sites.len() {
+            let root = find(&mut parent, i);
+            groups.entry(root).or_default().push(i);
+        }
+
+        let mut clones = Vec::new();
+        for members in groups.values().filter(|m| m.len() > 1) {
+            // Canonical: trusted provenance first, then earliest seen
+            let canonical = *members
+                .iter()
+                .min_by_key(|&&i| (!trusted.contains(&sites[i].domain), sites[i].first_seen_at))
+                .unwrap();
+            let canonical_basis = if trusted.contains(&sites[canonical].domain) {
+                "trusted_provenance"
+            } else {
+                "earliest_seen"
+            };
+            let c = &sites[canonical];
+
+            for &i in members.iter().filter(|&&i| i != canonical) {
+                let s = &sites[i];
+                let key = (i.min(canonical), i.max(canonical));
+                clones.push(SuspectedClone {
+                    clone_domain: s.domain.clone(),
+                    canonical_domain: c.domain.clone(),
+                    content_distance: (s.content_simhash ^ c.content_simhash).count_ones(),
+                    dom_distance: (s.dom_simhash ^ c.dom_simhash).count_ones(),
+                    vanity_prefix_len: Self::vanity_prefix_len(&s.domain, &c.domain),
+                    // Linked transitively when there's no direct edge to the canonical site
+                    reason: edges.get(&key).copied().unwrap_or("transitive").to_string(),
+                    canonical_basis: canonical_basis.to_string(),
+                });
+            }
+        }
+        clones
+    }
+
+    /// Pairs (i < j) that can possibly be linked: sites sharing a content SimHash band,
+    /// or onion sites sharing a vanity prefix. Every other pair is too far apart on both
+    /// tests, so the O(n²) all-pairs scan is avoided. Featureless fingerprints (stored
+    /// as 0 before pages without text were skipped) are left out.
+    fn candidate_pairs(sites: &[SiteFingerprint]) -> BTreeSet<(usize, usize)> {
+        let band_bits = 64 / CONTENT_BANDS;
+        let mut buckets: HashMap<(&str, String), Vec<usize>> = HashMap::new();
+        for (i, site) in sites.iter().enumerate() {
+            if site.content_simhash == 0 || site.dom_simhash == 0 {
+                continue;
+            }
+            for band in 0..CONTENT_BANDS {
+                let value = (site.content_simhash >> (band * band_bits)) & ((1 << band_bits) - 1);
+                buckets.entry((&site.network, format!("c{band}:{value:x}"))).or_default().push(i);
+            }
+            if let Some(onion) = site.domain.strip_suffix(".onion") {
+                if let Some(prefix) = onion.get(..MIN_VANITY_PREFIX) {
+                    buckets.entry((&site.network, format!("v:{prefix}"))).or_default().push(i);
+                }
+            }
+        }
+
+        let mut pairs = BTreeSet::new();
+        for members in buckets.values() {
+            for (n, &i) in members.iter().enumerate() {
+                for &j in &members[n + 1..] {
+                    pairs.insert((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Compare (entity_type, value) sets of the canonical site and a clone.
+    pub fn diff_entities(canonical: &[(String, String)], clone: &[(String, String)]) -> Vec<EntityDiff> {
+        let collect = |entities: &[(String, String)]| {
+            let mut by_type: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+            for (t, v) in entities {
+                if DIFF_ENTITY_TYPES.contains(&t.as_str()) {
+                    by_type.entry(t.clone()).or_default().insert(v.clone());
+                }
+            }
+            by_type
+        };
+        let canonical = collect(canonical);
+        let clone = collect(clone);
+        let empty = BTreeSet::new();
+
+        let mut diffs = Vec::new();
+        let types: BTreeSet<&String> = canonical.keys().chain(clone.keys()).collect();
+        for entity_type in types {
+            let ours = canonical.get(entity_type).unwrap_or(&empty);
+            let theirs = clone.get(entity_type).unwrap_or(&empty);
+            let clone_only: Vec<&String> = theirs.difference(ours).collect();
+            let canonical_only: Vec<&String> = ours.difference(theirs).collect();
+            let substituted = !clone_only.is_empty() && !canonical_only.is_empty();
+
+            for (side, values) in [("clone_only", clone_only), ("canonical_only", canonical_only)] {
+                for value in values {
+                    diffs.push(EntityDiff {
+                        entity_type: entity_type.clone(),
+                        value: value.clone(),
+                        side: side.to_string(),
+                        substituted,
+                    });
+                }
+            }
+        }
+        diffs
+    }
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// 64-bit SimHash over the given features (FNV-1a feature hashes, stable across builds).
+fn simhash<I: Iterator<Item = String>>(features: I) -> u64 {
+    let mut weights = [0i32; 64];
+    for feature in features {
+        let h = fnv1a(feature.as_bytes());
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if h & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, &w)| w > 0)
+        .fold(0u64, |acc, (bit, _)| acc | (1 << bit))
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -0,0 +1,268 @@
+use std::io::Cursor;
+
+use exif::{In, Tag, Value};
+
+use crate::xmp::parse_xmp;
+
+/// EXIF tags we keep, as (tag, field name). GPS is handled separately.
+const EXIF_TAGS: &[(Tag, &str)] = &[
+    (Tag::Make, "camera_make"),
+    (Tag::Model, "camera_model"),
+    (Tag::BodySerialNumber, "camera_serial"),
+    (Tag::LensMake, "lens_make"),
+    (Tag::LensModel, "lens_model"),
+    (Tag::LensSerialNumber, "lens_serial"),
+    (Tag::CameraOwnerName, "owner_name"),
+    (Tag::Artist, "author"),
+    (Tag::Copyright, "copyright"),
+    (Tag::Software, "software"),
+    (Tag::ImageUniqueID, "image_unique_id"),
+    (Tag::ImageDescription, "caption"),
+    (Tag::DateTimeOriginal, "taken_at"),
+    (Tag::DateTimeDigitized, "digitized_at"),
+    (Tag::DateTime, "modified_at"),
+];
+
+/// IPTC-IIM datasets of the application record (2:xx) we keep.
+const IPTC_DATASETS: &[(u8, &str)] = &[
+    (25, "keywords"),
+    (55, "taken_at"),
+    (80, "author"),
+    (85, "author_position"),
+    (90, "city"),
+    (92, "sublocation"),
+    (95, "state"),
+    (101, "country"),
+    (110, "credit"),
+    (115, "source"),
+    (116, "copyright"),
+    (118, "contact"),
+    (120, "caption"),
+    (122, "writer"),
+];
+
+/// Image container formats, by magic bytes.
+pub(crate) fn image_format(body: &[u8]) -> Option<&'static str> {
+    if body.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpeg")
+    } else if body.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if body.starts_with(b"II*\0") || body.starts_with(b"MM\0*") {
+        Some("tiff")
+    } else if body.len() >= 12 && &body[..4] == b"RIFF" && &body[8..12] == b"WEBP" {
+        Some("webp")
+    } else if body.len() >= 12
+        && &body[4..8] == b"ftyp"
+        && matches!(&body[8..12], b"heic" | b"heix" | b"hevc" | b"heim" | b"heis" | b"mif1" | b"msf1" | b"avif")
+    {
+        Some("heic")
+    } else {
+        None
+    }
+}
+
+/// Metadata fields of an image: EXIF (JPEG, TIFF, PNG, WebP, HEIC), XMP packets and
+/// IPTC-IIM (JPEG APP13). GPS positions come out as decimal degrees plus a
+/// "lat,lon" `gps_coordinate` field.
+pub(crate) fn parse_image(body: &[u8]) -> Option<Vec<(String, String)>> {
+    let format = image_format(body)?;
+    let mut fields = vec![("format".to_string(), format.to_string())];
+
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut Cursor::new(body)) {
+        for (tag, field) in EXIF_TAGS {
+            if let Some(value) = exif.get_field(*tag, In::PRIMARY).and_then(|f| field_text(&f.value)) {
+                let value = if field.ends_with("_at") { exif_date(&value).unwrap_or(value) } else { value };
+                push_unique(&mut fields, field, value);
+            }
+        }
+        let latitude = gps_degrees(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef);
+        let longitude = gps_degrees(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef);
+        push_gps(&mut fields, latitude, longitude);
+        if let Some(altitude) = exif.get_field(Tag::GPSAltitude, In::PRIMARY).and_then(|f| first_rational(&f.value)) {
+            push_unique(&mut fields, "gps_altitude", format!("{altitude:.1}"));
+        }
+    }
+
+    if let Some(xmp) = xmp_packet(body) {
+        let xmp_fields = parse_xmp(&xmp);
+        let coordinate = |name: &str| {
+            xmp_fields
+                .iter()
+                .find(|(k, _)| k == name)
+                .and_then(|(_, v)| xmp_gps_degrees(v))
+        };
+        let (latitude, longitude) = (coordinate("gps_latitude"), coordinate("gps_longitude"));
+        for (field, value) in xmp_fields {
+            if !field.starts_with("gps_") {
+                push_unique(&mut fields, &field, value);
+            }
+        }
+        push_gps(&mut fields, latitude, longitude);
+    }
+
+    if format == "jpeg" {
+        for (field, value) in jpeg_iptc(body) {
+            push_unique(&mut fields, field, value);
+        }
+    }
+
+    Some(fields)
+}
+
+/// Text of an ASCII/UTF-8 EXIF value (trailing NULs and padding removed).
+fn field_text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Ascii(parts) => parts
+            .iter()
+            .map(|p| String::from_utf8_lossy(p).into_owned())
+            .collect::<Vec<_>>()
+            .join(" "),
+        // UserComment-style UNDEFINED values: 8-byte charset prefix + text
+        Value::Undefined(bytes, _) if bytes.len() > 8 => String::from_utf8_lossy(&bytes[8..]).into_owned(),
+        _ => return None,
+    };
+    let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// EXIF dates ("2016:05:04 03:02:01", camera local time) as ISO 8601 without offset.
+fn exif_date(value: &str) -> Option<String> {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y:%m:%d %H:%M:%S")
+        .ok()
+        .map(|d| d.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+fn first_rational(value: &Value) -> Option<f64> {
+    match value {
+        Value::Rational(r) => r.first().filter(|r| r.denom != 0).map(|r| r.to_f64()),
+        _ => None,
+    }
+}
+
+/// Degrees/minutes/seconds rationals plus N/S/E/W reference as signed decimal degrees.
+fn gps_degrees(exif: &exif::Exif, tag: Tag, reference: Tag) -> Option<f64> {
+    let Value::Rational(dms) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+    if dms.iter().any(|r| r.denom == 0) {
+        return None;
+    }
+    let degrees = dms.iter().take(3).enumerate().map(|(i, r)| r.to_f64() / 60f64.powi(i as i32)).sum::<f64>();
+    let negative = exif
+        .get_field(reference, In::PRIMARY)
+        .and_then(|f| field_text(&f.value))
+        .is_some_and(|r| r.starts_with(['S', 'W', 's', 'w']));
+    Some(if negative { -degrees } else { degrees })
+}
+
+/// XMP GPS coordinates: "52,22.1234N" (degrees, decimal minutes) or "52,22,7.4N".
+fn xmp_gps_degrees(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let direction = value.chars().last().filter(|c| c.is_ascii_alphabetic())?;
+    let parts: Vec<f64> = value[..value.len() - 1]
+        .split(',')
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let degrees = parts.iter().enumerate().map(|(i, p)| p / 60f64.powi(i as i32)).sum::<f64>();
+    Some(if matches!(direction, 'S' | 'W' | 's' | 'w') { -degrees } else { degrees })
+}
+
+fn push_gps(fields: &mut Vec<(String, String)>, latitude: Option<f64>, longitude: Option<f64>) {
+    let (Some(latitude), Some(longitude)) = (latitude, longitude) else {
+        return;
+    };
+    // 0,0 is what cameras write when they have no fix
+    let valid = latitude.abs() <= 90.0 && longitude.abs() <= 180.0 && (latitude != 0.0 || longitude != 0.0);
+    if !valid {
+        return;
+    }
+    push_unique(fields, "gps_latitude", format!("{latitude:.6}"));
+    push_unique(fields, "gps_longitude", format!("{longitude:.6}"));
+    push_unique(fields, "gps_coordinate", format!("{latitude:.6},{longitude:.6}"));
+}
+
+/// The XMP packet embedded in the file. It is stored uncompressed in every container
+/// we read (JPEG APP1, PNG iTXt, WebP "XMP " chunk, TIFF tag 700, HEIC item).
+fn xmp_packet(body: &[u8]) -> Option<String> {
+    let start = find(body, b"<x:xmpmeta").or_else(|| find(body, b"<x:xapmeta"))?;
+    let end = find(&body[start..], b"</x:xmpmeta>")
+        .or_else(|| find(&body[start..], b"</x:xapmeta>"))
+        .map(|end| start + end + b"</x:xmpmeta>".len())?;
+    Some(String::from_utf8_lossy(&body[start..end]).into_owned())
+}
+
+/// IPTC-IIM records from a JPEG's Photoshop APP13 segment (image resource 0x0404).
+fn jpeg_iptc(body: &[u8]) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
+    let mut pos = 2;
+    while pos + 4 <= body.len() && body[pos] == 0xFF {
+        let marker = body[pos + 1];
+        // Start of scan: no more metadata segments
+        if marker == 0xDA {
+            break;
+        }
+        let length = u16::from_be_bytes([body[pos + 2], body[pos + 3]]) as usize;
+        let segment = body.get(pos + 4..pos + 2 + length).unwrap_or(&[]);
+        if marker == 0xED && segment.starts_with(b"Photoshop 3.0\0") {
+            if let Some(iptc) = photoshop_resource(&segment[14..], 0x0404) {
+                parse_iptc(iptc, &mut fields);
+            }
+        }
+        pos += 2 + length;
+    }
+    fields
+}
+
+/// Data of an image resource block ("8BIM", id, Pascal name, size, data).
+fn photoshop_resource(mut data: &[u8], wanted: u16) -> Option<&[u8]> {
+    while data.len() >= 12 && data.starts_with(b"8BIM") {
+        let id = u16::from_be_bytes([data[4], data[5]]);
+        // Pascal string name, padded to an even length including the length byte
+        let name_len = data[6] as usize;
+        let name_total = (name_len + 1 + 1) & !1;
+        let size_at = 6 + name_total;
+        let size_bytes = data.get(size_at..size_at + 4)?;
+        let size = u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]]) as usize;
+        let content = data.get(size_at + 4..size_at + 4 + size)?;
+        if id == wanted {
+            return Some(content);
+        }
+        let next = size_at + 4 + size + (size & 1);
+        data = data.get(next..)?;
+    }
+    None
+}
+
+/// Datasets of IPTC record 2 (tag marker 0x1C, record, dataset, 16-bit length).
+fn parse_iptc(mut data: &[u8], fields: &mut Vec<(&'static str, String)>) {
+    while data.len() >= 5 && data[0] == 0x1C {
+        let (record, dataset) = (data[1], data[2]);
+        let length = u16::from_be_bytes([data[3], data[4]]) as usize;
+        // Extended (>32 KiB) datasets never hold the short text fields we want
+        if length & 0x8000 != 0 {
+            break;
+        }
+        let Some(value) = data.get(5..5 + length) else {
+            break;
+        };
+        if record == 2 {
+            if let Some((_, field)) = IPTC_DATASETS.iter().find(|(d, _)| *d == dataset) {
+                let text = String::from_utf8_lossy(value).trim().to_string();
+                if !text.is_empty() {
+                    fields.push((field, text));
+                }
+            }
+        }
+        data = &data[5 + length..];
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn push_unique(fields: &mut Vec<(String, String)>, field: &str, value: String) {
+    if !fields.iter().any(|(k, v)| k == field && *v == value) {
+        fields.push((field.to_string(), value));
+    }
+}
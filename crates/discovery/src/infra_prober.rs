@@ -149,6 +149,42 @@ impl InfraProber {
         urls
     }
 
+    /// Parse the `Crawl-delay` that applies to us (the `*` user-agent group).
+    /// Fractional values are allowed ("Crawl-delay: 0.5").
+    pub fn parse_crawl_delay(content: &str) -> Option<std::time::Duration> {
+        let mut in_wildcard_group = false;
+        let mut last_was_agent = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_lowercase();
+            let value = value.trim();
+
+            if field == "user-agent" {
+                // Consecutive User-agent lines share one group
+                if !last_was_agent {
+                    in_wildcard_group = false;
+                }
+                in_wildcard_group |= value == "*";
+                last_was_agent = true;
+                continue;
+            }
+            last_was_agent = false;
+
+            if field == "crawl-delay" && in_wildcard_group {
+                if let Ok(secs) = value.parse::<f64>() {
+                    if secs.is_finite() && secs > 0.0 {
+                        return Some(std::time::Duration::from_secs_f64(secs));
+                    }
+                }
+            }
+        }
+        None
+    }
+
     /// Parse sitemap.xml content and extract <loc> URLs.
     pub fn parse_sitemap(content: &str) -> Vec<String> {
         let mut urls = Vec::new();
@@ -1,13 +1,25 @@
+mod archive;
+pub mod clone_detector;
 pub mod correlation;
 pub mod form_spider;
+mod image;
 pub mod infra_prober;
 pub mod metadata_extractor;
+pub mod mirror_harvester;
+mod office;
 pub mod pattern_mutator;
+mod pdf;
+pub mod pgp_verifier;
 pub mod source_miner;
+mod xmp;
 
+pub use archive::ArchiveEntry;
+pub use clone_detector::{CloneDetector, EntityDiff, SiteFingerprint, SuspectedClone};
 pub use correlation::{Correlation, CorrelationEngine};
 pub use form_spider::FormSpider;
 pub use infra_prober::{InfraProber, ProbeResult};
-pub use metadata_extractor::MetadataExtractor;
+pub use metadata_extractor::{DocumentMetadata, ExtractedDocument, MetadataExtractor};
+pub use mirror_harvester::{MirrorHarvester, MirrorLink};
 pub use pattern_mutator::PatternMutator;
+pub use pgp_verifier::{PgpPublicKey, PgpVerifier, SignedDocument};
 pub use source_miner::SourceMiner;
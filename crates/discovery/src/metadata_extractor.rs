@@ -1,8 +1,16 @@
+use scraper::{Html, Selector};
 use serde::Serialize;
+use url::Url;
 
-/// Extract metadata from images (EXIF-like), PDFs, and documents.
-/// Phase 1: basic content-type detection and size tracking.
-/// Phase 2: full EXIF and PDF metadata via dedicated crates.
+use crate::archive::{archive_format, list_archive, ArchiveEntry};
+use crate::image::{image_format, parse_image};
+use crate::office::parse_office;
+use crate::pdf::parse_pdf;
+
+/// Archive file names kept as the document's text (for search and entity extraction).
+const MAX_LISTING_TEXT_BYTES: usize = 256 * 1024;
+
+/// Extract metadata from images (EXIF/XMP/IPTC), PDFs, office documents and archives.
 #[derive(Debug, Clone, Serialize)]
 pub struct DocumentMetadata {
     pub url: String,
@@ -10,6 +18,22 @@ pub struct DocumentMetadata {
     pub size_bytes: usize,
     pub metadata_type: String,
     pub fields: Vec<(String, String)>,
+    /// File listing of an archive (empty for other documents)
+    pub entries: Vec<ArchiveEntry>,
+}
+
+impl DocumentMetadata {
+    /// Values of a field (a key can repeat when sources disagree).
+    pub fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.fields.iter().filter(move |(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+/// A parsed document: its metadata record and the text extracted from it.
+#[derive(Debug, Clone)]
+pub struct ExtractedDocument {
+    pub metadata: DocumentMetadata,
+    pub text: String,
 }
 
 pub struct MetadataExtractor;
@@ -20,6 +44,46 @@ impl MetadataExtractor {
         content_type.starts_with("image/jpeg")
             || content_type.starts_with("image/tiff")
             || content_type.starts_with("image/png")
+            || content_type.starts_with("image/webp")
+            || content_type.starts_with("image/hei")
+    }
+
+    /// Image URLs of a page (`<img src>` and lazy-loading `data-src`), resolved and deduplicated.
+    pub fn image_sources(html: &str, base_url: &Url) -> Vec<Url> {
+        let document = Html::parse_document(html);
+        let img_sel = Selector::parse("img").unwrap();
+        let mut sources: Vec<Url> = Vec::new();
+        for img in document.select(&img_sel) {
+            for attr in ["src", "data-src"] {
+                let Some(src) = img.value().attr(attr).map(str::trim) else {
+                    continue;
+                };
+                if src.is_empty() || src.starts_with("data:") {
+                    continue;
+                }
+                if let Ok(url) = base_url.join(src) {
+                    if !sources.contains(&url) {
+                        sources.push(url);
+                    }
+                }
+            }
+        }
+        sources
+    }
+
+    /// Parse an image's EXIF, XMP and IPTC metadata: GPS position, camera make/model/serial,
+    /// software, author and timestamps. None if the body isn't a supported image format.
+    pub fn extract_image(url: &str, body: &[u8]) -> Option<DocumentMetadata> {
+        let format = image_format(body)?;
+        let fields = parse_image(body)?;
+        Some(DocumentMetadata {
+            url: url.to_string(),
+            content_type: format!("image/{format}"),
+            size_bytes: body.len(),
+            metadata_type: "image".to_string(),
+            fields,
+            entries: Vec::new(),
+        })
     }
 
     /// Check if content is a PDF.
@@ -27,44 +91,156 @@ impl MetadataExtractor {
         content_type.contains("application/pdf")
     }
 
-    /// Phase 1: Extract basic metadata from response.
-    /// Returns metadata fields we can determine without specialized parsers.
+    /// PDF by content type, or by magic bytes for servers that send application/octet-stream.
+    pub fn looks_like_pdf(content_type: Option<&str>, body: &[u8]) -> bool {
+        content_type.is_some_and(Self::is_pdf) || body.starts_with(b"%PDF-")
+    }
+
+    /// Parse a PDF: decoded streams, Info dictionary and XMP metadata (author,
+    /// creator tool, producer, creation/modification dates) and the text of its pages.
+    pub fn extract_pdf(url: &str, body: &[u8]) -> Option<ExtractedDocument> {
+        let parsed = parse_pdf(body)?;
+        Some(ExtractedDocument {
+            metadata: DocumentMetadata {
+                url: url.to_string(),
+                content_type: "application/pdf".to_string(),
+                size_bytes: body.len(),
+                metadata_type: "pdf".to_string(),
+                fields: parsed.fields,
+                entries: Vec::new(),
+            },
+            text: parsed.text,
+        })
+    }
+
+    /// Check if a content type is an OOXML or ODF office document.
+    pub fn is_office(content_type: &str) -> bool {
+        content_type.contains("application/vnd.openxmlformats-officedocument")
+            || content_type.contains("application/vnd.oasis.opendocument")
+    }
+
+    /// Check if a content type is a ZIP, RAR or 7z archive.
+    pub fn is_archive(content_type: &str) -> bool {
+        ["application/zip", "application/x-zip", "application/vnd.rar", "application/x-rar", "application/x-7z"]
+            .iter()
+            .any(|t| content_type.contains(t))
+    }
+
+    /// PDF, office document or archive, by content type or magic bytes (office files
+    /// and archives are as often served as application/octet-stream).
+    pub fn looks_like_document(content_type: Option<&str>, body: &[u8]) -> bool {
+        Self::looks_like_pdf(content_type, body)
+            || content_type.is_some_and(|ct| Self::is_office(ct) || Self::is_archive(ct))
+            || archive_format(body).is_some()
+    }
+
+    /// Parse an office document: OOXML (docx/xlsx/pptx) or ODF (odt/ods/odp) core
+    /// properties (author, last modified by, company, template, revision count, dates)
+    /// and the text of its body, sheets or slides. None if the body isn't one.
+    pub fn extract_office(url: &str, body: &[u8]) -> Option<ExtractedDocument> {
+        let parsed = parse_office(body)?;
+        Some(ExtractedDocument {
+            metadata: DocumentMetadata {
+                url: url.to_string(),
+                content_type: parsed.content_type,
+                size_bytes: body.len(),
+                metadata_type: "office".to_string(),
+                fields: parsed.fields,
+                entries: Vec::new(),
+            },
+            text: parsed.text,
+        })
+    }
+
+    /// List a ZIP, RAR or 7z archive from its headers (names, sizes, timestamps,
+    /// encryption); nothing is decompressed. The text is the list of file names.
+    pub fn extract_archive(url: &str, body: &[u8]) -> Option<ExtractedDocument> {
+        let listing = list_archive(body)?;
+        // Sizes come from untrusted headers
+        let total_size = listing.entries.iter().fold(0u64, |sum, e| sum.saturating_add(e.size));
+        let mut fields = vec![
+            ("format".to_string(), listing.format.to_string()),
+            ("entry_count".to_string(), listing.entry_count.to_string()),
+            ("total_size".to_string(), total_size.to_string()),
+        ];
+        if listing.headers_encrypted {
+            fields.push(("headers_encrypted".to_string(), "true".to_string()));
+        }
+        if listing.entries.iter().any(|e| e.encrypted) {
+            fields.push(("encrypted".to_string(), "true".to_string()));
+        }
+
+        let mut text = String::new();
+        for entry in listing.entries.iter().filter(|e| !e.is_dir) {
+            if text.len() + entry.path.len() + 1 > MAX_LISTING_TEXT_BYTES {
+                break;
+            }
+            text.push_str(&entry.path);
+            text.push('\n');
+        }
+
+        let content_type = match listing.format {
+            "rar" => "application/vnd.rar",
+            "7z" => "application/x-7z-compressed",
+            _ => "application/zip",
+        };
+        Some(ExtractedDocument {
+            metadata: DocumentMetadata {
+                url: url.to_string(),
+                content_type: content_type.to_string(),
+                size_bytes: body.len(),
+                metadata_type: "archive".to_string(),
+                fields,
+                entries: listing.entries,
+            },
+            text,
+        })
+    }
+
+    /// Parse a PDF, office document or archive. ZIP-based office files are tried as
+    /// documents first and fall back to a plain archive listing.
+    pub fn extract_document(url: &str, content_type: Option<&str>, body: &[u8]) -> Option<ExtractedDocument> {
+        if Self::looks_like_pdf(content_type, body) {
+            return Self::extract_pdf(url, body);
+        }
+        if archive_format(body) == Some("zip") {
+            if let Some(doc) = Self::extract_office(url, body) {
+                return Some(doc);
+            }
+        }
+        Self::extract_archive(url, body)
+    }
+
+    /// Extract whatever metadata the content type allows (PDF, image, office document
+    /// or archive).
     pub fn extract_basic(url: &str, content_type: &str, body: &[u8]) -> DocumentMetadata {
         let mut fields = Vec::new();
 
-        // Check for PDF author field (simple grep)
         if Self::is_pdf(content_type) {
-            if let Ok(text) = std::str::from_utf8(body) {
-                // Very basic PDF metadata extraction from plaintext markers
-                for marker in &["/Author", "/Creator", "/Producer", "/Title", "/Subject"] {
-                    if let Some(pos) = text.find(marker) {
-                        // Try to extract the value in parentheses after the marker
-                        let after = &text[pos + marker.len()..];
-                        if let Some(start) = after.find('(') {
-                            if let Some(end) = after[start..].find(')') {
-                                let value = &after[start + 1..start + end];
-                                if !value.is_empty() && value.len() < 200 {
-                                    fields.push((
-                                        marker.trim_start_matches('/').to_string(),
-                                        value.to_string(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
+            if let Some(doc) = Self::extract_pdf(url, body) {
+                fields = doc.metadata.fields;
             }
         }
 
-        // Check for EXIF markers in JPEG
-        if Self::is_image(content_type) && body.len() > 12 {
-            // JPEG SOI + APP1 EXIF marker
-            if body[0] == 0xFF && body[1] == 0xD8 {
-                fields.push(("format".to_string(), "JPEG".to_string()));
-                // Check for EXIF APP1 marker
-                if body.len() > 4 && body[2] == 0xFF && body[3] == 0xE1 {
-                    fields.push(("has_exif".to_string(), "true".to_string()));
-                }
+        if Self::is_image(content_type) {
+            if let Some(doc) = Self::extract_image(url, body) {
+                fields = doc.fields;
+            }
+        }
+
+        let mut metadata_type = if Self::is_pdf(content_type) {
+            "pdf".to_string()
+        } else if Self::is_image(content_type) {
+            "image".to_string()
+        } else {
+            "other".to_string()
+        };
+        let mut entries = Vec::new();
+        if metadata_type == "other" {
+            if let Some(doc) = Self::extract_document(url, Some(content_type), body) {
+                metadata_type = doc.metadata.metadata_type;
+                fields = doc.metadata.fields;
+                entries = doc.metadata.entries;
             }
         }
 
@@ -72,14 +248,9 @@ impl MetadataExtractor {
             url: url.to_string(),
             content_type: content_type.to_string(),
             size_bytes: body.len(),
-            metadata_type: if Self::is_pdf(content_type) {
-                "pdf".to_string()
-            } else if Self::is_image(content_type) {
-                "image".to_string()
-            } else {
-                "other".to_string()
-            },
+            metadata_type,
             fields,
+            entries,
         }
     }
 }
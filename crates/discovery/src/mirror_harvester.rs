@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Harvest declared mirror addresses from a page.
+/// Sites advertise their onion/mirror addresses via the `Onion-Location` header,
+/// `<meta http-equiv="onion-location">`, `Alt-Svc` entries and `<link rel="alternate">`.
+pub struct MirrorHarvester;
+
+/// A "site A declares mirror B" relationship.
+#[derive(Debug, Clone)]
+pub struct MirrorLink {
+    pub source_domain: String,
+    pub mirror_domain: String,
+    pub mirror_url: String,
+    /// Which signal declared the mirror: onion_location_header, onion_location_meta,
+    /// alt_svc, link_alternate
+    pub signal: String,
+}
+
+/// Host suffixes of overlay networks we crawl — only these count as mirrors.
+const MIRROR_SUFFIXES: &[&str] = &[".onion", ".i2p", ".loki", ".bit"];
+
+impl MirrorHarvester {
+    /// Extract all mirror declarations from a page's HTML and headers.
+    /// Self-references (mirror host == page host) are dropped.
+    pub fn extract(url: &Url, html: &str, headers: &HashMap<String, String>) -> Vec<MirrorLink> {
+        let source_domain = url.host_str().unwrap_or("").to_string();
+        let mut mirrors = Vec::new();
+
+        // 1. Onion-Location header
+        if let Some(value) = headers.get("onion-location") {
+            Self::push_url(&source_domain, url, value, "onion_location_header", &mut mirrors);
+        }
+
+        // 2. Alt-Svc header: h2="xxxx.onion:443"; ma=86400, h3=":443"
+        if let Some(alt_svc) = headers.get("alt-svc") {
+            for authority in Self::parse_alt_svc(alt_svc) {
+                let host = authority.rsplit_once(':').map(|(h, _)| h).unwrap_or(&authority);
+                if host.is_empty() {
+                    continue; // same host, different port
+                }
+                let candidate = format!("{}://{}/", url.scheme(), host);
+                Self::push_url(&source_domain, url, &candidate, "alt_svc", &mut mirrors);
+            }
+        }
+
+        // 3 + 4. <meta http-equiv="onion-location"> and <link rel="alternate">
+        if html.contains("onion-location") || html.contains("Onion-Location") || html.contains("alternate") {
+            let document = Html::parse_document(html);
+
+            if let Ok(sel) = Selector::parse("meta[http-equiv]") {
+                for el in document.select(&sel) {
+                    let equiv = el.value().attr("http-equiv").unwrap_or("");
+                    if !equiv.eq_ignore_ascii_case("onion-location") {
+                        continue;
+                    }
+                    if let Some(content) = el.value().attr("content") {
+                        Self::push_url(&source_domain, url, content, "onion_location_meta", &mut mirrors);
+                    }
+                }
+            }
+
+            if let Ok(sel) = Selector::parse("link[rel][href]") {
+                for el in document.select(&sel) {
+                    let rel = el.value().attr("rel").unwrap_or("");
+                    if !rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")) {
+                        continue;
+                    }
+                    if let Some(href) = el.value().attr("href") {
+                        Self::push_url(&source_domain, url, href, "link_alternate", &mut mirrors);
+                    }
+                }
+            }
+        }
+
+        mirrors.sort_by(|a, b| (&a.mirror_url, &a.signal).cmp(&(&b.mirror_url, &b.signal)));
+        mirrors.dedup_by(|a, b| a.mirror_url == b.mirror_url && a.signal == b.signal);
+        mirrors
+    }
+
+    /// Parse the alternative authorities out of an Alt-Svc header value.
+    /// Returns the raw `host:port` strings (host may be empty for same-host entries).
+    pub fn parse_alt_svc(value: &str) -> Vec<String> {
+        let mut authorities = Vec::new();
+        if value.trim() == "clear" {
+            return authorities;
+        }
+        for entry in value.split(',') {
+            // First parameter is protocol-id="authority"; the rest are ma=, persist=
+            let Some(alternative) = entry.split(';').next() else {
+                continue;
+            };
+            let Some((_, authority)) = alternative.split_once('=') else {
+                continue;
+            };
+            let authority = authority.trim().trim_matches('"');
+            if !authority.is_empty() {
+                authorities.push(authority.to_string());
+            }
+        }
+        authorities
+    }
+
+    fn push_url(
+        source_domain: &str,
+        base_url: &Url,
+        raw: &str,
+        signal: &str,
+        mirrors: &mut Vec<MirrorLink>,
+    ) {
+        let Ok(mirror) = base_url.join(raw.trim()) else {
+            return;
+        };
+        let Some(host) = mirror.host_str() else {
+            return;
+        };
+        let host = host.to_lowercase();
+        if host == source_domain || !MIRROR_SUFFIXES.iter().any(|s| host.ends_with(s)) {
+            return;
+        }
+        mirrors.push(MirrorLink {
+            source_domain: source_domain.to_string(),
+            mirror_domain: host,
+            mirror_url: mirror.to_string(),
+            signal: signal.to_string(),
+        });
+    }
+}
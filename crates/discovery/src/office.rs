@@ -0,0 +1,238 @@
+use std::io::{Cursor, Read};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use zip::ZipArchive;
+
+/// Bytes read from any one part of the package (XML parts are small; more is a bomb).
+const MAX_PART_BYTES: u64 = 16 * 1024 * 1024;
+/// Parts declaring a larger uncompressed/compressed ratio are skipped (zip bombs).
+const MAX_COMPRESSION_RATIO: u64 = 100;
+/// Text parts read per document (slides, sheets).
+const MAX_TEXT_PARTS: usize = 500;
+/// Extracted text beyond this many bytes is dropped.
+const MAX_TEXT_BYTES: usize = 2 * 1024 * 1024;
+
+/// OOXML docProps/core.xml and docProps/app.xml properties, as (element, field name).
+const OOXML_PROPERTIES: &[(&str, &str)] = &[
+    ("dc:title", "title"),
+    ("dc:subject", "subject"),
+    ("dc:description", "description"),
+    ("dc:creator", "author"),
+    ("cp:lastModifiedBy", "last_modified_by"),
+    ("cp:revision", "revision"),
+    ("cp:keywords", "keywords"),
+    ("cp:category", "category"),
+    ("dcterms:created", "created_at"),
+    ("dcterms:modified", "modified_at"),
+    ("cp:lastPrinted", "last_printed_at"),
+    ("Company", "company"),
+    ("Manager", "manager"),
+    ("Template", "template"),
+    ("Application", "application"),
+    ("AppVersion", "app_version"),
+    ("TotalTime", "total_edit_minutes"),
+    ("HyperlinkBase", "hyperlink_base"),
+];
+
+/// ODF meta.xml properties, as (element, field name).
+const ODF_PROPERTIES: &[(&str, &str)] = &[
+    ("dc:title", "title"),
+    ("dc:subject", "subject"),
+    ("dc:description", "description"),
+    ("meta:initial-creator", "author"),
+    // dc:creator is whoever saved the document last
+    ("dc:creator", "last_modified_by"),
+    ("meta:editing-cycles", "revision"),
+    ("meta:keyword", "keywords"),
+    ("meta:creation-date", "created_at"),
+    ("dc:date", "modified_at"),
+    ("meta:print-date", "last_printed_at"),
+    ("meta:printed-by", "last_printed_by"),
+    ("meta:generator", "application"),
+    ("meta:editing-duration", "total_edit_time"),
+];
+
+/// Elements that end a paragraph, row or cell in WordprocessingML, DrawingML,
+/// SpreadsheetML shared strings and ODF text.
+static BREAK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"</(?:w:p|a:p|si|text:p|text:h|table:table-cell)>|<(?:w:tab|w:br|text:tab|text:line-break)\s*/>").unwrap()
+});
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+static ENTITY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|amp|lt|gt|quot|apos);").unwrap());
+static ODF_TEMPLATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"<meta:template\s[^>]*xlink:(?:title|href)="([^"]*)""#).unwrap());
+
+/// A parsed office document: its content type, metadata fields (the "format" field
+/// is docx, xlsx, pptx, odt, ods, odp, ...) and text.
+pub(crate) struct ParsedOffice {
+    pub content_type: String,
+    pub fields: Vec<(String, String)>,
+    pub text: String,
+}
+
+/// Parse an OOXML (Word/Excel/PowerPoint) or ODF (OpenDocument) package. Returns None
+/// for ZIP files that are neither.
+pub(crate) fn parse_office(body: &[u8]) -> Option<ParsedOffice> {
+    let mut archive = ZipArchive::new(Cursor::new(body)).ok()?;
+    if let Some(mimetype) = read_part(&mut archive, "mimetype") {
+        if let Some(parsed) = parse_odf(&mut archive, mimetype.trim()) {
+            return Some(parsed);
+        }
+    }
+    parse_ooxml(&mut archive)
+}
+
+fn parse_ooxml(archive: &mut ZipArchive<Cursor<&[u8]>>) -> Option<ParsedOffice> {
+    archive.index_for_name("[Content_Types].xml")?;
+    let names: Vec<String> = archive.file_names().map(String::from).collect();
+    let (kind, content_type, text_parts): (&str, &str, Vec<String>) =
+        if names.iter().any(|n| n == "word/document.xml") {
+            (
+                "docx",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                vec!["word/document.xml".to_string()],
+            )
+        } else if names.iter().any(|n| n == "xl/workbook.xml") {
+            (
+                "xlsx",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                vec!["xl/sharedStrings.xml".to_string()],
+            )
+        } else if names.iter().any(|n| n == "ppt/presentation.xml") {
+            (
+                "pptx",
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+                numbered_parts(&names, "ppt/slides/slide"),
+            )
+        } else {
+            return None;
+        };
+
+    let mut fields = vec![("format".to_string(), kind.to_string())];
+    for part in ["docProps/core.xml", "docProps/app.xml"] {
+        if let Some(xml) = read_part(archive, part) {
+            push_properties(&mut fields, &xml, OOXML_PROPERTIES);
+        }
+    }
+    let text = read_text(archive, &text_parts);
+    Some(ParsedOffice {
+        content_type: content_type.to_string(),
+        fields,
+        text,
+    })
+}
+
+fn parse_odf(archive: &mut ZipArchive<Cursor<&[u8]>>, mimetype: &str) -> Option<ParsedOffice> {
+    let kind = match mimetype.strip_prefix("application/vnd.oasis.opendocument.")? {
+        "text" | "text-template" => "odt",
+        "spreadsheet" | "spreadsheet-template" => "ods",
+        "presentation" | "presentation-template" => "odp",
+        "graphics" | "graphics-template" => "odg",
+        _ => "odf",
+    };
+    let mut fields = vec![("format".to_string(), kind.to_string())];
+    if let Some(xml) = read_part(archive, "meta.xml") {
+        push_properties(&mut fields, &xml, ODF_PROPERTIES);
+        if let Some(caps) = ODF_TEMPLATE_RE.captures(&xml) {
+            push_field(&mut fields, "template", &caps[1]);
+        }
+    }
+    let text = read_text(archive, &["content.xml".to_string()]);
+    Some(ParsedOffice {
+        content_type: mimetype.to_string(),
+        fields,
+        text,
+    })
+}
+
+/// "ppt/slides/slide1.xml", "slide2.xml", ... in slide order.
+fn numbered_parts(names: &[String], prefix: &str) -> Vec<String> {
+    let mut parts: Vec<(u32, String)> = names
+        .iter()
+        .filter_map(|n| {
+            let number = n.strip_prefix(prefix)?.strip_suffix(".xml")?.parse().ok()?;
+            Some((number, n.clone()))
+        })
+        .collect();
+    parts.sort();
+    parts
+        .into_iter()
+        .map(|(_, name)| name)
+        .take(MAX_TEXT_PARTS)
+        .collect()
+}
+
+/// A part of the package as text, refusing parts over the size or ratio limits.
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Option<String> {
+    let file = archive.by_name(name).ok()?;
+    let (size, compressed) = (file.size(), file.compressed_size().max(1));
+    if size > MAX_PART_BYTES || size / compressed > MAX_COMPRESSION_RATIO {
+        return None;
+    }
+    // Declared sizes can lie; never read past the limit
+    let mut buf = Vec::new();
+    file.take(MAX_PART_BYTES).read_to_end(&mut buf).ok()?;
+    Some(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn read_text(archive: &mut ZipArchive<Cursor<&[u8]>>, parts: &[String]) -> String {
+    let mut text = String::new();
+    for part in parts {
+        let Some(xml) = read_part(archive, part) else {
+            continue;
+        };
+        let with_breaks = BREAK_RE.replace_all(&xml, "\n");
+        let plain = unescape(&TAG_RE.replace_all(&with_breaks, ""));
+        for line in plain.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if text.len() + line.len() + 1 > MAX_TEXT_BYTES {
+                return text;
+            }
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn push_properties(fields: &mut Vec<(String, String)>, xml: &str, properties: &[(&str, &str)]) {
+    for (element, field) in properties {
+        let name = regex::escape(element);
+        let Ok(re) = Regex::new(&format!(r"(?s)<{name}(?:\s[^>]*)?>(.*?)</{name}>")) else {
+            continue;
+        };
+        for caps in re.captures_iter(xml) {
+            push_field(fields, field, &caps[1]);
+        }
+    }
+}
+
+fn push_field(fields: &mut Vec<(String, String)>, field: &str, raw: &str) {
+    let value = unescape(raw.trim());
+    if value.is_empty() || fields.iter().any(|(k, v)| k == field && *v == value) {
+        return;
+    }
+    fields.push((field.to_string(), value));
+}
+
+fn unescape(s: &str) -> String {
+    ENTITY_RE
+        .replace_all(s, |caps: &regex::Captures| match &caps[1] {
+            "amp" => "&".to_string(),
+            "lt" => "<".to_string(),
+            "gt" => ">".to_string(),
+            "quot" => "\"".to_string(),
+            "apos" => "'".to_string(),
+            numeric => {
+                let code = match numeric.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => numeric[1..].parse().ok(),
+                };
+                code.and_then(char::from_u32)
+                    .map(String::from)
+                    .unwrap_or_default()
+            }
+        })
+        .into_owned()
+}
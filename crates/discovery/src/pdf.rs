@@ -0,0 +1,295 @@
+use std::io::Read;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use flate2::read::ZlibDecoder;
+use pdf_extract::{Dictionary, Document, Object, PlainTextOutput};
+
+use crate::xmp::parse_xmp;
+
+/// Info dictionary keys we keep, as (PDF key, field name).
+const INFO_KEYS: &[(&[u8], &str)] = &[
+    (b"Title", "title"),
+    (b"Author", "author"),
+    (b"Subject", "subject"),
+    (b"Keywords", "keywords"),
+    (b"Creator", "creator_tool"),
+    (b"Producer", "producer"),
+    (b"CreationDate", "created_at"),
+    (b"ModDate", "modified_at"),
+    (b"Company", "company"),
+    (b"SourceModified", "source_modified_at"),
+];
+
+/// Extracted text beyond this many bytes is dropped (search and entities don't need more).
+const MAX_TEXT_BYTES: usize = 2 * 1024 * 1024;
+
+/// A Flate stream inflating past this many bytes is a decompression bomb.
+const MAX_STREAM_BYTES: u64 = 64 * 1024 * 1024;
+/// Decompressed bytes across all streams of one document.
+const MAX_DOCUMENT_BYTES: u64 = 256 * 1024 * 1024;
+/// Deflate's worst-case expansion: the most a stream that can't be inflated yet may hold.
+const MAX_DEFLATE_RATIO: u64 = 1032;
+
+/// A parsed PDF: metadata fields and the text of its pages.
+pub(crate) struct ParsedPdf {
+    pub fields: Vec<(String, String)>,
+    pub text: String,
+}
+
+/// Parse a PDF from memory. Object streams and compressed streams are decoded,
+/// documents encrypted with an empty user password (common for "protected" leaks)
+/// are decrypted. Returns None if the file can't be parsed at all.
+pub(crate) fn parse_pdf(body: &[u8]) -> Option<ParsedPdf> {
+    // lopdf inflates object and cross-reference streams while loading, without a limit
+    if !load_streams_within_limits(body) {
+        return Some(ParsedPdf {
+            fields: vec![("decompression_limit".to_string(), "exceeded".to_string())],
+            text: String::new(),
+        });
+    }
+
+    let mut doc = Document::load_mem(body).ok()?;
+    let encrypted = doc.is_encrypted();
+    if encrypted && doc.decrypt("").is_err() {
+        // Metadata of encrypted files is encrypted too; only the structure is readable
+        return Some(ParsedPdf {
+            fields: vec![("encrypted".to_string(), "true".to_string()), ("pdf_version".to_string(), doc.version.clone())],
+            text: String::new(),
+        });
+    }
+
+    let mut fields = vec![
+        ("pdf_version".to_string(), doc.version.clone()),
+        ("page_count".to_string(), doc.get_pages().len().to_string()),
+    ];
+    if encrypted {
+        fields.push(("encrypted".to_string(), "true".to_string()));
+    }
+    // Content, font and metadata streams are inflated on use: empty the oversized ones first
+    if !cap_streams(&mut doc) {
+        fields.push(("decompression_limit".to_string(), "exceeded".to_string()));
+    }
+
+    if let Some(info) = info_dictionary(&doc) {
+        for (key, field) in INFO_KEYS {
+            let Some(value) = info.get(key).ok().and_then(|v| text_value(&doc, v)) else {
+                continue;
+            };
+            let value = if field.ends_with("_at") { normalize_date(&value).unwrap_or(value) } else { value };
+            push_unique(&mut fields, field, value);
+        }
+    }
+    // XMP is often richer than the Info dictionary (and the only metadata in PDF 2.0)
+    if let Some(xmp) = xmp_packet(&doc) {
+        for (field, value) in parse_xmp(&xmp) {
+            push_unique(&mut fields, &field, value);
+        }
+    }
+
+    // Text extraction follows font programs and content streams of arbitrary shape;
+    // a malformed file must not take the worker down with it
+    let mut text = String::new();
+    let extracted = catch_unwind(AssertUnwindSafe(|| {
+        let mut output = PlainTextOutput::new(&mut text);
+        pdf_extract::output_doc(&doc, &mut output).is_ok()
+    }));
+    if !matches!(extracted, Ok(true)) && text.is_empty() {
+        fields.push(("text_extraction".to_string(), "failed".to_string()));
+    }
+    if text.len() > MAX_TEXT_BYTES {
+        let mut end = MAX_TEXT_BYTES;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+
+    Some(ParsedPdf { fields, text })
+}
+
+/// Whether the object and cross-reference streams in `body` inflate within the per-stream
+/// and per-document limits. Streams of encrypted documents can't be inflated before lopdf
+/// decrypts them, which it does while loading, so they count at deflate's worst case.
+fn load_streams_within_limits(body: &[u8]) -> bool {
+    let mut total = 0u64;
+    let mut from = 0;
+    while let Some(offset) = find(&body[from..], b"stream") {
+        let keyword = from + offset;
+        from = keyword + b"stream".len();
+        if body[..keyword].ends_with(b"end") {
+            continue;
+        }
+        // The dictionary of the stream: back to the start of its indirect object
+        let window = &body[keyword.saturating_sub(4096)..keyword];
+        let dict = rfind(window, b"obj").map_or(window, |start| &window[start..]);
+        if find(dict, b"/ObjStm").is_none() && find(dict, b"/XRef").is_none() {
+            continue;
+        }
+
+        let data = body[from..]
+            .strip_prefix(b"\r\n")
+            .or_else(|| body[from..].strip_prefix(b"\n"))
+            .unwrap_or(&body[from..]);
+        let size = match inflated_len(data, MAX_STREAM_BYTES) {
+            0 => find(data, b"endstream").unwrap_or(data.len()) as u64 * MAX_DEFLATE_RATIO,
+            size => size,
+        };
+        total += size;
+        if size > MAX_STREAM_BYTES || total > MAX_DOCUMENT_BYTES {
+            return false;
+        }
+    }
+    true
+}
+
+/// Empty every Flate stream of the loaded document that inflates past the per-stream
+/// limit or past what is left of the per-document one. Returns false if any was emptied.
+fn cap_streams(doc: &mut Document) -> bool {
+    let mut total = 0u64;
+    let mut within = true;
+    for object in doc.objects.values_mut() {
+        let Ok(stream) = object.as_stream_mut() else {
+            continue;
+        };
+        if stream.filters().ok().and_then(|filters| filters.first().copied()) != Some(b"FlateDecode".as_slice()) {
+            continue;
+        }
+        let limit = MAX_STREAM_BYTES.min(MAX_DOCUMENT_BYTES - total);
+        let size = inflated_len(&stream.content, limit);
+        if size > limit {
+            stream.set_content(Vec::new());
+            within = false;
+        } else {
+            total += size;
+        }
+    }
+    within
+}
+
+/// Bytes a zlib stream inflates to, counting at most `limit + 1` (0 if it isn't zlib).
+fn inflated_len(data: &[u8], limit: u64) -> u64 {
+    let mut decoder = ZlibDecoder::new(data).take(limit + 1);
+    let mut buf = [0u8; 64 * 1024];
+    let mut total = 0;
+    while let Ok(n @ 1..) = decoder.read(&mut buf) {
+        total += n as u64;
+    }
+    total
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+fn info_dictionary(doc: &Document) -> Option<&Dictionary> {
+    let info = doc.trailer.get(b"Info").ok()?;
+    doc.dereference(info).ok()?.1.as_dict().ok()
+}
+
+/// The XMP packet referenced by the document catalog's /Metadata stream.
+fn xmp_packet(doc: &Document) -> Option<String> {
+    let metadata = doc.catalog().ok()?.get(b"Metadata").ok()?;
+    let stream = doc.dereference(metadata).ok()?.1.as_stream().ok()?;
+    let content = stream.decompressed_content().unwrap_or_else(|_| stream.content.clone());
+    Some(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// A text string (PDFDocEncoding or UTF-16BE), following references.
+fn text_value(doc: &Document, value: &Object) -> Option<String> {
+    let value = doc.dereference(value).ok()?.1;
+    let text = pdf_extract::decode_text_string(value)
+        .ok()
+        .or_else(|| value.as_str().ok().map(|s| s.iter().map(|&b| b as char).collect()))?;
+    let text = text.trim_matches(char::from(0)).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// PDF dates ("D:20230415093012+02'00'") as RFC 3339.
+fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim().trim_start_matches("D:");
+    let digits_len = value.chars().take_while(char::is_ascii_digit).count().min(14);
+    if digits_len < 8 {
+        return None;
+    }
+    // Missing month/day/time parts default to their minimum
+    let padded = format!("{}{}", &value[..digits_len], &"0101000000"[digits_len - 4..]);
+    let naive = chrono::NaiveDateTime::parse_from_str(&padded, "%Y%m%d%H%M%S").ok()?;
+
+    // Timezone: Z, +HH'mm' or -HH'mm' (absent = UTC)
+    let rest = &value[digits_len..];
+    let offset_secs = match rest.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let tz: String = rest[1..].chars().filter(char::is_ascii_digit).collect();
+            let hours: i32 = tz.get(..2).and_then(|h| h.parse().ok()).unwrap_or(0);
+            let minutes: i32 = tz.get(2..4).and_then(|m| m.parse().ok()).unwrap_or(0);
+            let secs = hours * 3600 + minutes * 60;
+            if sign == '-' { -secs } else { secs }
+        }
+        _ => 0,
+    };
+    let offset = chrono::FixedOffset::east_opt(offset_secs)?;
+    Some(naive.and_local_timezone(offset).single()?.to_rfc3339())
+}
+
+/// Add a field unless the same value is already there (Info and XMP usually agree).
+fn push_unique(fields: &mut Vec<(String, String)>, field: &str, value: String) {
+    let duplicate = fields.iter().any(|(k, v)| {
+        k == field && (v == &value || (field.ends_with("_at") && same_instant(v, &value)))
+    });
+    if !duplicate {
+        fields.push((field.to_string(), value));
+    }
+}
+
+fn same_instant(a: &str, b: &str) -> bool {
+    match (chrono::DateTime::parse_from_rfc3339(a), chrono::DateTime::parse_from_rfc3339(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    /// An object stream of incompressible bytes, its keyword lines ending in `eol`.
+    fn object_stream(eol: &str) -> Vec<u8> {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let raw: Vec<u8> = (0..256 * 1024)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(compressed.len() as u64 * MAX_DEFLATE_RATIO > MAX_STREAM_BYTES);
+
+        let mut body = format!(
+            "%PDF-1.5{eol}1 0 obj{eol}<< /Type /ObjStm /N 1 /First 0 /Length {} /Filter /FlateDecode >>{eol}stream{eol}",
+            compressed.len()
+        )
+        .into_bytes();
+        body.extend_from_slice(&compressed);
+        body.extend_from_slice(format!("{eol}endstream{eol}endobj{eol}").as_bytes());
+        body
+    }
+
+    #[test]
+    fn crlf_terminated_object_stream_inflates_within_limits() {
+        assert!(load_streams_within_limits(&object_stream("\r\n")));
+        assert!(load_streams_within_limits(&object_stream("\n")));
+    }
+}
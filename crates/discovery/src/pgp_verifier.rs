@@ -0,0 +1,210 @@
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use pgp::composed::{CleartextSignedMessage, Deserializable, SignedPublicKey};
+use pgp::types::KeyDetails;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+
+/// Detect PGP clearsigned messages (signed mirror lists, warrant canaries) and
+/// verify them against public keys previously seen on the same site.
+/// Phishing clones can copy the text but cannot produce a valid signature.
+pub struct PgpVerifier;
+
+/// A public key block found on a page.
+#[derive(Debug, Clone)]
+pub struct PgpPublicKey {
+    /// SHA-256 of the armored block — same value as the `pgp_key_hash` correlation
+    pub key_hash: String,
+    /// Primary key fingerprint (uppercase hex)
+    pub fingerprint: String,
+    pub armored: String,
+}
+
+/// A clearsigned message found on a page and the outcome of verifying it.
+#[derive(Debug, Clone)]
+pub struct SignedDocument {
+    /// SHA-256 of the signed text, used to track the same document over time
+    pub content_hash: String,
+    pub signed_text: String,
+    pub verified: bool,
+    /// Fingerprint of the (sub)key that produced the valid signature
+    pub signer_fingerprint: Option<String>,
+    /// Overlay-network URLs listed inside the signed text
+    pub mirror_urls: Vec<String>,
+    pub is_canary: bool,
+    /// Most recent date mentioned in a canary
+    pub canary_date: Option<NaiveDate>,
+}
+
+static PUBLIC_KEY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"-----BEGIN PGP PUBLIC KEY BLOCK-----[\s\S]+?-----END PGP PUBLIC KEY BLOCK-----")
+        .unwrap()
+});
+
+static SIGNED_MESSAGE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"-----BEGIN PGP SIGNED MESSAGE-----[\s\S]+?-----END PGP SIGNATURE-----").unwrap()
+});
+
+// Body of a clearsigned message when the armor itself fails to parse
+static SIGNED_BODY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"-----BEGIN PGP SIGNED MESSAGE-----\r?\n(?:[A-Za-z]+:[^\n]*\r?\n)*\r?\n([\s\S]*?)\r?\n-----BEGIN PGP SIGNATURE-----")
+        .unwrap()
+});
+
+static MIRROR_HOST_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:https?://)?(?:[a-z2-7]{56}\.onion|[a-z2-7]{52}\.b32\.i2p|[a-z0-9]{52}\.loki)(?:/[^\s<>]*)?")
+        .unwrap()
+});
+
+static CANARY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bcanary\b").unwrap());
+
+static ISO_DATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(20\d{2})[-/.](\d{1,2})[-/.](\d{1,2})\b").unwrap());
+
+// "October 1, 2026" / "Oct 1 2026"
+static MONTH_FIRST_DATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(20\d{2})\b")
+        .unwrap()
+});
+
+// "1 October 2026" / "1st of Oct, 2026"
+static DAY_FIRST_DATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:st|nd|rd|th)?\s+(?:of\s+)?(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?,?\s+(20\d{2})\b")
+        .unwrap()
+});
+
+const MONTHS: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+impl PgpVerifier {
+    /// Whether the text contains anything this module cares about (cheap pre-check).
+    pub fn has_pgp_content(text: &str) -> bool {
+        text.contains("-----BEGIN PGP")
+    }
+
+    /// Extract and parse all public key blocks in the text.
+    /// Blocks that fail to parse are skipped.
+    pub fn extract_public_keys(text: &str) -> Vec<PgpPublicKey> {
+        let mut keys = Vec::new();
+        for m in PUBLIC_KEY_RE.find_iter(text) {
+            let armored = m.as_str();
+            let Ok((key, _)) = SignedPublicKey::from_string(&unescape_html(armored)) else {
+                continue;
+            };
+            let mut hasher = Sha256::new();
+            hasher.update(armored.as_bytes());
+            keys.push(PgpPublicKey {
+                key_hash: format!("{:x}", hasher.finalize()),
+                fingerprint: key.fingerprint().to_string().to_uppercase(),
+                armored: armored.to_string(),
+            });
+        }
+        keys
+    }
+
+    /// Find every clearsigned message in the text and verify it against
+    /// the given armored public keys (primary keys and signing subkeys).
+    pub fn verify_signed_messages(text: &str, known_keys: &[String]) -> Vec<SignedDocument> {
+        let keys: Vec<SignedPublicKey> = known_keys
+            .iter()
+            .filter_map(|armored| SignedPublicKey::from_string(&unescape_html(armored)).ok())
+            .map(|(key, _)| key)
+            .collect();
+
+        SIGNED_MESSAGE_RE
+            .find_iter(text)
+            .filter_map(|m| Self::verify_one(&unescape_html(m.as_str()), &keys))
+            .collect()
+    }
+
+    fn verify_one(armored: &str, keys: &[SignedPublicKey]) -> Option<SignedDocument> {
+        let (signed_text, signer_fingerprint) = match CleartextSignedMessage::from_string(armored) {
+            Ok((msg, _)) => {
+                let signer = keys.iter().find_map(|key| {
+                    if msg.verify(key).is_ok() {
+                        return Some(key.fingerprint().to_string().to_uppercase());
+                    }
+                    key.public_subkeys
+                        .iter()
+                        .find(|sub| msg.verify(*sub).is_ok())
+                        .map(|sub| sub.fingerprint().to_string().to_uppercase())
+                });
+                (msg.signed_text().replace("\r\n", "\n"), signer)
+            }
+            // Mangled armor (e.g. HTML line breaks): keep the text, can't verify
+            Err(_) => (SIGNED_BODY_RE.captures(armored)?[1].to_string(), None),
+        };
+
+        let mut hasher = Sha256::new();
+        hasher.update(signed_text.as_bytes());
+        let content_hash = format!("{:x}", hasher.finalize());
+
+        let is_canary = CANARY_RE.is_match(&signed_text);
+        let canary_date = if is_canary {
+            Self::latest_date(&signed_text)
+        } else {
+            None
+        };
+
+        Some(SignedDocument {
+            content_hash,
+            mirror_urls: Self::extract_mirror_urls(&signed_text),
+            verified: signer_fingerprint.is_some(),
+            signer_fingerprint,
+            is_canary,
+            canary_date,
+            signed_text,
+        })
+    }
+
+    /// Overlay-network addresses listed in a signed text, normalized to URLs.
+    pub fn extract_mirror_urls(text: &str) -> Vec<String> {
+        let mut urls: Vec<String> = MIRROR_HOST_RE
+            .find_iter(text)
+            .map(|m| {
+                let found = m.as_str().trim_end_matches(['.', ',', ')', ';']);
+                if found.starts_with("http") {
+                    found.to_string()
+                } else {
+                    format!("http://{}", found)
+                }
+            })
+            .collect();
+        urls.sort();
+        urls.dedup();
+        urls
+    }
+
+    /// The most recent calendar date mentioned in the text.
+    pub fn latest_date(text: &str) -> Option<NaiveDate> {
+        let mut dates = Vec::new();
+        for cap in ISO_DATE_RE.captures_iter(text) {
+            if let (Ok(y), Ok(m), Ok(d)) = (cap[1].parse(), cap[2].parse(), cap[3].parse()) {
+                dates.extend(NaiveDate::from_ymd_opt(y, m, d));
+            }
+        }
+        for cap in MONTH_FIRST_DATE_RE.captures_iter(text) {
+            dates.extend(month_date(&cap[3], &cap[1], &cap[2]));
+        }
+        for cap in DAY_FIRST_DATE_RE.captures_iter(text) {
+            dates.extend(month_date(&cap[3], &cap[2], &cap[1]));
+        }
+        dates.into_iter().max()
+    }
+}
+
+fn month_date(year: &str, month: &str, day: &str) -> Option<NaiveDate> {
+    let month = month.to_lowercase();
+    let m = MONTHS.iter().position(|name| month.starts_with(name))? as u32 + 1;
+    NaiveDate::from_ymd_opt(year.parse().ok()?, m, day.parse().ok()?)
+}
+
+/// Signed blocks embedded in HTML are usually entity-escaped inside <pre>.
+fn unescape_html(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
@@ -3,6 +3,8 @@ use regex::Regex;
 use scraper::{Html, Selector};
 use url::Url;
 
+use darkscraper_core::ipfs;
+
 /// Extract URLs from JS string literals, HTML comments, data-* attributes,
 /// inline event handlers, hidden/invisible DOM elements, and <noscript> tags.
 pub struct SourceMiner;
@@ -53,6 +55,16 @@ static LOKI_IN_TEXT_RE: Lazy<Regex> =
 static HYPHANET_IN_TEXT_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r#"(?:hyphanet|freenet):[A-Z]{2,3}@[^\s"'<>]+"#).unwrap());
 
+/// Mesh URLs: IPv6 literal hosts (Yggdrasil is routed by address range) and .ygg names
+static MESH_IN_TEXT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"https?://(?:\[[0-9a-fA-F:]+\]|[a-zA-Z0-9\-.]+\.ygg)(?::[0-9]+)?[^\s"'<>]*"#).unwrap()
+});
+
+/// ipfs:// and ipns:// URLs, and /ipfs/<cid> paths (bare or on a gateway URL)
+static IPFS_IN_TEXT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:ipfs|ipns)://[a-zA-Z0-9.\-]+[^\s"'<>]*|/(?:ipfs|ipns)/[a-zA-Z0-9.\-]{8,}[^\s"'<>]*"#).unwrap()
+});
+
 impl SourceMiner {
     /// Mine the raw HTML source for URLs beyond standard <a href> links.
     /// Returns a list of discovered URL strings.
@@ -113,7 +125,7 @@ impl SourceMiner {
         // 6. <noscript> tags
         urls.extend(extract_noscript_links(html, base_url));
 
-        // 7. Bare .onion/.i2p/.bit URLs anywhere in source
+        // 7. Bare .onion/.i2p/.bit URLs, IPFS addresses and mesh URLs anywhere in source
         for m in ONION_IN_TEXT_RE.find_iter(html) {
             urls.push(m.as_str().to_string());
         }
@@ -129,6 +141,10 @@ impl SourceMiner {
         for m in HYPHANET_IN_TEXT_RE.find_iter(html) {
             urls.push(m.as_str().to_string());
         }
+        urls.extend(ipfs_addresses(html));
+        for m in MESH_IN_TEXT_RE.find_iter(html) {
+            urls.push(m.as_str().to_string());
+        }
 
         // Dedup
         urls.sort();
@@ -154,6 +170,10 @@ fn extract_urls_from_text(text: &str, base_url: &Url) -> Vec<String> {
     for m in HYPHANET_IN_TEXT_RE.find_iter(text) {
         urls.push(m.as_str().to_string());
     }
+    urls.extend(ipfs_addresses(text));
+    for m in MESH_IN_TEXT_RE.find_iter(text) {
+        urls.push(m.as_str().to_string());
+    }
     for cap in JS_URL_RE.captures_iter(text) {
         urls.push(cap[1].to_string());
     }
@@ -165,6 +185,14 @@ fn extract_urls_from_text(text: &str, base_url: &Url) -> Vec<String> {
     urls
 }
 
+/// IPFS addresses in `text`, as canonical ipfs:// / ipns:// URLs. Invalid CIDs are dropped.
+fn ipfs_addresses(text: &str) -> impl Iterator<Item = String> + '_ {
+    IPFS_IN_TEXT_RE
+        .find_iter(text)
+        .filter_map(|m| ipfs::parse_address(m.as_str()))
+        .map(|url| url.to_string())
+}
+
 fn extract_hidden_links(html: &str, base_url: &Url) -> Vec<String> {
     let document = Html::parse_document(html);
     let mut urls = Vec::new();
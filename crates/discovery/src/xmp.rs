@@ -0,0 +1,96 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// XMP properties we keep, as (qualified name, field name).
+const PROPERTIES: &[(&str, &str)] = &[
+    ("dc:creator", "author"),
+    ("dc:title", "title"),
+    ("dc:description", "subject"),
+    ("dc:rights", "rights"),
+    ("pdf:Keywords", "keywords"),
+    ("pdf:Producer", "producer"),
+    ("xmp:CreatorTool", "creator_tool"),
+    ("xmp:CreateDate", "created_at"),
+    ("xmp:ModifyDate", "modified_at"),
+    ("xmp:MetadataDate", "metadata_date"),
+    ("xmpMM:DocumentID", "document_id"),
+    ("xmpMM:InstanceID", "instance_id"),
+    ("xmpMM:OriginalDocumentID", "original_document_id"),
+    // Photos
+    ("tiff:Make", "camera_make"),
+    ("tiff:Model", "camera_model"),
+    ("aux:SerialNumber", "camera_serial"),
+    ("exifEX:BodySerialNumber", "camera_serial"),
+    ("aux:LensSerialNumber", "lens_serial"),
+    ("exifEX:LensSerialNumber", "lens_serial"),
+    ("exifEX:CameraOwnerName", "owner_name"),
+    ("exif:DateTimeOriginal", "taken_at"),
+    ("photoshop:DateCreated", "taken_at"),
+    ("exif:GPSLatitude", "gps_latitude"),
+    ("exif:GPSLongitude", "gps_longitude"),
+    ("photoshop:City", "city"),
+    ("photoshop:State", "state"),
+    ("photoshop:Country", "country"),
+    ("photoshop:Credit", "credit"),
+    ("photoshop:AuthorsPosition", "author_position"),
+];
+
+/// Element or attribute form of a property: `<xmp:CreatorTool>…</xmp:CreatorTool>`
+/// or `xmp:CreatorTool="…"`.
+static PROPERTY_RES: Lazy<Vec<(Regex, Regex, &'static str)>> = Lazy::new(|| {
+    PROPERTIES
+        .iter()
+        .map(|(name, field)| {
+            let name = regex::escape(name);
+            (
+                Regex::new(&format!(r"(?s)<{name}(?:\s[^>]*)?>(.*?)</{name}>")).unwrap(),
+                Regex::new(&format!(r#"\s{name}="([^"]*)""#)).unwrap(),
+                *field,
+            )
+        })
+        .collect()
+});
+
+/// `<rdf:li>` entries of a Seq/Bag/Alt container.
+static LI_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<rdf:li(?:\s[^>]*)?>(.*?)</rdf:li>").unwrap());
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Fields of an XMP packet (embedded in PDFs, JPEG APP1, PNG iTXt, office files).
+/// Container properties (authors, titles in several languages) yield one field per entry.
+pub(crate) fn parse_xmp(xml: &str) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    for (element_re, attribute_re, field) in PROPERTY_RES.iter() {
+        for caps in element_re.captures_iter(xml) {
+            let inner = &caps[1];
+            let entries: Vec<&str> = if inner.contains("<rdf:li") {
+                LI_RE.captures_iter(inner).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect()
+            } else {
+                vec![inner]
+            };
+            for entry in entries {
+                push_field(&mut fields, field, entry);
+            }
+        }
+        for caps in attribute_re.captures_iter(xml) {
+            push_field(&mut fields, field, &caps[1]);
+        }
+    }
+    fields
+}
+
+fn push_field(fields: &mut Vec<(String, String)>, field: &str, raw: &str) {
+    let value = unescape(TAG_RE.replace_all(raw, " ").trim());
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.is_empty() || fields.iter().any(|(k, v)| k == field && *v == value) {
+        return;
+    }
+    fields.push((field.to_string(), value));
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
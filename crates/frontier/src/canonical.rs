@@ -0,0 +1,177 @@
+use url::Url;
+
+use darkscraper_core::config::{DEFAULT_SESSION_PARAMS, DEFAULT_TRACKING_PARAMS};
+use darkscraper_core::ipfs;
+
+/// URL canonicalizer used as the dedup key for the bloom filter, dead URLs and storage.
+///
+/// The `url` crate already lowercases scheme and host, drops default ports and
+/// resolves dot segments when parsing. On top of that we:
+/// - rewrite IPFS CIDs and IPNS keys in their canonical encoding
+/// - leave path and query case untouched (servers are case-sensitive)
+/// - normalize percent-encoding (uppercase hex, decode unreserved characters except
+///   `.` in the path, whose dot segments were already resolved)
+/// - drop session / tracking query parameters and `;jsessionid=` path parameters
+/// - sort the remaining query parameters
+/// - collapse `index.php` / `index.html` into their directory
+///
+/// The trailing slash is kept: `/dir/` and `/dir` are different resources on many
+/// servers, and relative links resolve differently against them.
+#[derive(Debug, Clone)]
+pub struct UrlCanonicalizer {
+    /// Parameter names to strip; a trailing `*` matches by prefix (e.g. `utm_*`)
+    strip_params: Vec<String>,
+}
+
+/// Directory index files that serve the same page as the directory itself.
+const INDEX_FILES: &[&str] = &["index.php", "index.html", "index.htm"];
+
+impl Default for UrlCanonicalizer {
+    fn default() -> Self {
+        let params: Vec<String> = DEFAULT_SESSION_PARAMS
+            .iter()
+            .chain(DEFAULT_TRACKING_PARAMS)
+            .map(|p| p.to_string())
+            .collect();
+        Self::new(&params)
+    }
+}
+
+impl UrlCanonicalizer {
+    /// Create a canonicalizer that strips the given query parameter names (case-insensitive).
+    pub fn new(strip_params: &[String]) -> Self {
+        Self {
+            strip_params: strip_params.iter().map(|p| p.to_lowercase()).collect(),
+        }
+    }
+
+    /// Canonical string form of a URL.
+    pub fn canonicalize(&self, url: &Url) -> String {
+        // The same IPFS content has many CID spellings (v0, v1 in any base)
+        let mut canonical = match url.scheme() {
+            "ipfs" | "ipns" => ipfs::canonical_url(url).unwrap_or_else(|| url.clone()),
+            _ => url.clone(),
+        };
+        canonical.set_fragment(None);
+
+        // Opaque URLs (hyphanet:USK@..., mailto:) have no hierarchy to normalize
+        if canonical.cannot_be_a_base() {
+            return canonical.to_string();
+        }
+
+        // Path: drop ;jsessionid=... parameters, normalize escapes, collapse index files
+        let mut segments: Vec<String> = canonical
+            .path()
+            .split('/')
+            .map(|segment| {
+                let mut parts = segment.split(';');
+                let mut kept = parts.next().unwrap_or("").to_string();
+                for param in parts {
+                    let name = param.split('=').next().unwrap_or("");
+                    if !self.is_stripped(name) {
+                        kept.push(';');
+                        kept.push_str(param);
+                    }
+                }
+                normalize_percent_encoding(&kept, true)
+            })
+            .collect();
+        if let Some(last) = segments.last_mut() {
+            if INDEX_FILES.iter().any(|f| last.eq_ignore_ascii_case(f)) {
+                last.clear();
+            }
+        }
+        canonical.set_path(&segments.join("/"));
+
+        // Query: drop empty and stripped parameters, normalize escapes, sort
+        if let Some(query) = canonical.query() {
+            let mut params: Vec<String> = query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .filter(|pair| {
+                    let name = pair.split('=').next().unwrap_or("");
+                    !self.is_stripped(&percent_decode(name))
+                })
+                .map(|pair| normalize_percent_encoding(pair, false))
+                .collect();
+            params.sort();
+            if params.is_empty() {
+                canonical.set_query(None);
+            } else {
+                canonical.set_query(Some(&params.join("&")));
+            }
+        }
+
+        canonical.to_string()
+    }
+
+    fn is_stripped(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        if name.is_empty() {
+            return false;
+        }
+        self.strip_params.iter().any(|p| match p.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == *p,
+        })
+    }
+}
+
+/// Uppercase the hex digits of every escape and decode escaped unreserved
+/// characters (RFC 3986 §6.2.2.2), so `%7e`, `%7E` and `~` compare equal.
+/// In a path `%2E` stays encoded: the `url` crate resolved dot segments while parsing,
+/// so decoding it afterwards would turn `/a/%2E%2E/b` into a literal `..` segment.
+/// Input comes from a serialized `Url`, so it is always ASCII.
+fn normalize_percent_encoding(s: &str, in_path: bool) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                let decoded = hi * 16 + lo;
+                let unreserved = decoded.is_ascii_alphanumeric() || b"-._~".contains(&decoded);
+                if unreserved && !(in_path && decoded == b'.') {
+                    out.push(decoded as char);
+                } else {
+                    out.push('%');
+                    out.push(bytes[i + 1].to_ascii_uppercase() as char);
+                    out.push(bytes[i + 2].to_ascii_uppercase() as char);
+                }
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i] as char);
+        i += 1;
+    }
+    out
+}
+
+/// Decode escapes in a query parameter name (for matching against the strip-list only).
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                out.push(hi * 16 + lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
@@ -1,17 +1,32 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use growable_bloom_filter::GrowableBloom;
 use priority_queue::PriorityQueue;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::debug;
 use url::Url;
 
+use darkscraper_core::config::FrontierConfig;
 use darkscraper_core::CrawlJob;
 
+mod canonical;
+mod revisit;
+mod scope;
+mod topic;
+mod traps;
+mod yields;
+
+pub use canonical::UrlCanonicalizer;
+pub use revisit::RevisitPolicy;
+pub use scope::{CrawlScope, ScopeRule};
+pub use topic::TopicScorer;
+pub use traps::{TrapDetector, TrapReason};
+pub use yields::{YieldKind, YieldStats, YieldTracker};
+
 /// Wrapper for priority ordering (higher priority = dequeued first)
 #[derive(Debug, Clone)]
 struct Priority(f64);
@@ -75,13 +90,66 @@ impl NetworkQueue {
     }
 }
 
+/// Per-host politeness state.
+#[derive(Debug, Default)]
+struct HostState {
+    /// When the last request to this host was started
+    last_visit: Option<Instant>,
+    /// Requests currently being fetched from this host
+    in_flight: usize,
+    /// `Crawl-delay` from the host's robots.txt
+    crawl_delay: Option<Duration>,
+    /// Adaptive delay, grown on 429/503 and shrunk on success
+    backoff: Duration,
+}
+
+impl HostState {
+    /// Effective delay between requests: the largest of the network default,
+    /// robots.txt Crawl-delay and the adaptive backoff.
+    fn delay(&self, default_delay: Duration) -> Duration {
+        default_delay
+            .max(self.crawl_delay.unwrap_or_default())
+            .max(self.backoff)
+    }
+}
+
+/// How many queued jobs a polite pop inspects before giving up
+/// (jobs for busy hosts are skipped and pushed back).
+const POLITE_SCAN_LIMIT: usize = 256;
+/// First backoff step after a 429/503 with no Retry-After.
+const MIN_BACKOFF: Duration = Duration::from_secs(5);
+
+/// A URL held back from the queues because it looks like a crawler trap.
+#[derive(Debug, Clone)]
+pub struct QuarantinedUrl {
+    /// Canonical URL
+    pub url: String,
+    pub network: String,
+    pub domain: String,
+    pub reason: TrapReason,
+}
+
 pub struct CrawlFrontier {
     /// Per-network priority queues — workers only pop from their own network
     networks: DashMap<String, Arc<RwLock<NetworkQueue>>>,
     /// Global bloom filter for URL dedup (shared across all networks)
     seen_urls: Arc<RwLock<GrowableBloom>>,
-    /// Per-host last-request timestamp for politeness
-    host_last_seen: DashMap<String, Instant>,
+    /// Per-host politeness state (last visit, in-flight count, delays)
+    hosts: DashMap<String, HostState>,
+    /// Max concurrent requests to a single host
+    max_in_flight_per_host: usize,
+    /// Upper bound for Crawl-delay and adaptive backoff
+    max_host_delay: Duration,
+    /// Canonical URL form used as the dedup key
+    canonicalizer: UrlCanonicalizer,
+    /// Crawler trap detection for newly discovered URLs
+    traps: TrapDetector,
+    /// Quarantined URLs not yet persisted (see `drain_quarantined`)
+    quarantine: Mutex<Vec<QuarantinedUrl>>,
+    /// Quarantined URLs per reason this session
+    quarantine_counts: DashMap<TrapReason, usize>,
+    /// Learned yield per source page, domain and URL pattern (boosts fresh jobs)
+    yields: YieldTracker,
 }
 
 impl Default for CrawlFrontier {
@@ -91,16 +159,35 @@ impl Default for CrawlFrontier {
 }
 
 impl CrawlFrontier {
-    /// Create a new empty frontier.
+    /// Create a new empty frontier with default settings.
     pub fn new() -> Self {
+        Self::from_config(&FrontierConfig::default())
+    }
+
+    /// Create a new empty frontier from the `[frontier]` config section.
+    pub fn from_config(config: &FrontierConfig) -> Self {
         // GrowableBloom starts small and auto-grows as needed.
         // target FP rate 0.1% with initial capacity hint of 100k.
         let bloom = GrowableBloom::new(0.001, 100_000);
 
+        let strip_params: Vec<String> = config
+            .strip_session_params
+            .iter()
+            .chain(&config.strip_tracking_params)
+            .cloned()
+            .collect();
+
         Self {
             networks: DashMap::new(),
             seen_urls: Arc::new(RwLock::new(bloom)),
-            host_last_seen: DashMap::new(),
+            hosts: DashMap::new(),
+            max_in_flight_per_host: config.max_in_flight_per_host.max(1),
+            max_host_delay: Duration::from_secs(config.max_host_delay_seconds),
+            canonicalizer: UrlCanonicalizer::new(&strip_params),
+            traps: TrapDetector::new(config),
+            quarantine: Mutex::new(Vec::new()),
+            quarantine_counts: DashMap::new(),
+            yields: YieldTracker::new(),
         }
     }
 
@@ -111,39 +198,38 @@ impl CrawlFrontier {
         let mut bloom = self.seen_urls.write().await;
         for url_str in urls {
             if let Ok(url) = Url::parse(url_str) {
-                let normalized = Self::normalize_url(&url);
+                let normalized = self.normalize_url(&url);
                 bloom.insert(&normalized);
             } else {
-                // For non-parseable URLs (e.g. hyphanet keys), use as-is
-                bloom.insert(url_str.to_lowercase());
+                // For non-parseable URLs, use as-is
+                bloom.insert(url_str);
             }
         }
     }
 
-    /// Normalize URL for dedup: lowercase host, remove fragment, strip trailing slash
-    fn normalize_url(url: &Url) -> String {
-        let mut normalized = url.clone();
-        normalized.set_fragment(None);
-        let path = normalized.path().to_string();
-        if path.len() > 1 && path.ends_with('/') {
-            normalized.set_path(&path[..path.len() - 1]);
-        }
-        normalized.to_string().to_lowercase()
+    /// Canonical form of a URL — the dedup key for the bloom filter,
+    /// and the form the crawler uses for dead URLs and storage.
+    pub fn normalize_url(&self, url: &Url) -> String {
+        self.canonicalizer.canonicalize(url)
     }
 
-    /// Calculate priority based on URL characteristics and depth.
+    /// Calculate priority based on URL characteristics, in-site depth and domain hops.
     /// Higher priority = dequeued first.
     ///
     /// Priority tiers:
     /// - Tier 1 (2.0+): Cryptographic addresses (permanent, can't be hijacked)
     /// - Tier 2 (1.0-2.0): Human-readable names (addressbook/ONS/namecoin)
     /// - Depth penalty: Divided by (depth + 2) to prioritize shallower URLs
-    pub fn calculate_priority(url: &Url, depth: u32) -> f64 {
+    /// - Hop penalty: Divided by (domain_hops + 1) to prioritize domains closer to the seeds
+    ///
+    /// `key_address`: the routing driver reports the host as derived from a public key
+    /// (`NetworkDriver::is_key_address`), e.g. a mesh IP in its configured ranges.
+    pub fn calculate_priority(url: &Url, key_address: bool, depth: u32, domain_hops: u32) -> f64 {
         let host = url.host_str().unwrap_or("");
-        let base_priority = Self::classify_address_type(host);
+        let base_priority = if key_address { 2.0 } else { Self::classify_address_type(host) };
 
         // Depth penalty: shallower URLs get higher priority
-        base_priority / (depth as f64 + 2.0)
+        base_priority / (depth as f64 + 2.0) / (domain_hops as f64 + 1.0)
     }
 
     /// Classify address type to determine base priority.
@@ -210,8 +296,8 @@ impl CrawlFrontier {
     }
 
     /// Add a URL to the frontier. Returns true if added, false if already seen.
-    pub async fn push(&self, job: CrawlJob) -> bool {
-        let normalized = Self::normalize_url(&job.url);
+    pub async fn push(&self, mut job: CrawlJob) -> bool {
+        let normalized = self.normalize_url(&job.url);
         let is_retry = job.retry_count > 0;
         let network = job.network.clone();
 
@@ -233,12 +319,74 @@ impl CrawlFrontier {
             bloom.insert(&normalized);
         }
 
+        // Trap check for fresh URLs (still marked seen, so they aren't re-checked)
+        if !is_retry {
+            if self.quarantine_if_trap(&job, &normalized).await {
+                return false;
+            }
+            job.priority *= self.yields.multiplier(&job);
+        }
+
         let nq = self.get_network_queue(&network);
         let mut queue = nq.write().await;
         queue.push(normalized, job);
         true
     }
 
+    /// Quarantine the job if its URL looks like a crawler trap. Returns true if quarantined.
+    async fn quarantine_if_trap(&self, job: &CrawlJob, normalized: &str) -> bool {
+        let Some(reason) = self.traps.check(&job.url) else {
+            return false;
+        };
+        debug!(url = %job.url, reason = reason.as_str(), "quarantined suspected crawler trap");
+        *self.quarantine_counts.entry(reason).or_default() += 1;
+        self.quarantine.lock().await.push(QuarantinedUrl {
+            url: normalized.to_string(),
+            network: job.network.clone(),
+            domain: job.url.host_str().unwrap_or("").to_string(),
+            reason,
+        });
+        true
+    }
+
+    /// Record a fetched page's content hash for low-novelty trap detection.
+    /// Returns true if the page's URL template was just quarantined.
+    pub fn record_page_content(&self, url: &Url, content_hash: &str) -> bool {
+        self.traps.record_content(url, content_hash)
+    }
+
+    /// Take the quarantined URLs recorded since the last call (for persisting).
+    pub async fn drain_quarantined(&self) -> Vec<QuarantinedUrl> {
+        std::mem::take(&mut *self.quarantine.lock().await)
+    }
+
+    /// Quarantined URLs per trap reason this session.
+    pub fn quarantine_counts(&self) -> Vec<(&'static str, usize)> {
+        let mut counts: Vec<(&'static str, usize)> = self
+            .quarantine_counts
+            .iter()
+            .map(|e| (e.key().as_str(), *e.value()))
+            .collect();
+        counts.sort();
+        counts
+    }
+
+    /// Credit a fetched page's outcome (new domains, entities, correlations, errors)
+    /// to its domain, URL pattern and the page that linked to it.
+    pub fn record_yield(&self, url: &Url, source_url: Option<&str>, delta: YieldStats) {
+        self.yields.record(url, source_url, delta);
+    }
+
+    /// Load a persisted yield score (at startup).
+    pub fn load_yield(&self, kind: YieldKind, key: String, stats: YieldStats) {
+        self.yields.load(kind, key, stats);
+    }
+
+    /// Take the yield scores changed since the last call (for persisting).
+    pub fn drain_dirty_yields(&self) -> Vec<(YieldKind, String, YieldStats)> {
+        self.yields.drain_dirty()
+    }
+
     /// Add multiple URLs to the frontier in one batch, acquiring locks minimally.
     /// Returns the number of URLs actually enqueued (after dedup).
     pub async fn push_batch(&self, jobs: Vec<CrawlJob>) -> usize {
@@ -250,22 +398,31 @@ impl CrawlFrontier {
         let (retries, fresh): (Vec<_>, Vec<_>) = jobs.into_iter().partition(|j| j.retry_count > 0);
 
         // Single bloom lock for all fresh URLs
-        let mut to_enqueue = retries;
+        let mut unseen = Vec::new();
         if !fresh.is_empty() {
             let mut bloom = self.seen_urls.write().await;
             for job in fresh {
-                let normalized = Self::normalize_url(&job.url);
+                let normalized = self.normalize_url(&job.url);
                 if !bloom.contains(&normalized) {
                     bloom.insert(&normalized);
-                    to_enqueue.push(job);
+                    unseen.push((normalized, job));
                 }
             }
         }
 
+        // Trap check for fresh URLs (outside the bloom lock)
+        let mut to_enqueue = retries;
+        for (normalized, mut job) in unseen {
+            if !self.quarantine_if_trap(&job, &normalized).await {
+                job.priority *= self.yields.multiplier(&job);
+                to_enqueue.push(job);
+            }
+        }
+
         // Group by network, one lock per network
         let mut by_network: HashMap<String, Vec<(String, CrawlJob)>> = HashMap::new();
         for job in to_enqueue {
-            let normalized = Self::normalize_url(&job.url);
+            let normalized = self.normalize_url(&job.url);
             by_network
                 .entry(job.network.clone())
                 .or_default()
@@ -317,7 +474,7 @@ impl CrawlFrontier {
         let nq = self.get_network_queue(network);
         let mut queue = nq.write().await;
         for job in jobs {
-            let normalized = Self::normalize_url(&job.url);
+            let normalized = self.normalize_url(&job.url);
             queue.push(normalized, job);
         }
     }
@@ -347,19 +504,109 @@ impl CrawlFrontier {
         }
     }
 
-    /// Record that we visited a host now (for politeness delays)
+    /// Record that a request to this host is starting now.
+    /// Must be paired with `finish_host_visit` once the fetch completes.
     pub fn record_host_visit(&self, host: &str) {
-        self.host_last_seen.insert(host.to_string(), Instant::now());
+        let mut state = self.hosts.entry(host.to_string()).or_default();
+        state.last_visit = Some(Instant::now());
+        state.in_flight += 1;
     }
 
-    /// Check if enough time has elapsed since the last visit to this host
-    pub fn can_visit_host(&self, host: &str, min_delay: std::time::Duration) -> bool {
-        match self.host_last_seen.get(host) {
-            Some(last) => last.elapsed() >= min_delay,
+    /// Check if the host has a free in-flight slot and enough time has elapsed
+    /// since the last visit (`min_delay`, robots.txt Crawl-delay or backoff, whichever is larger).
+    pub fn can_visit_host(&self, host: &str, min_delay: Duration) -> bool {
+        match self.hosts.get(host) {
+            Some(state) => {
+                state.in_flight < self.max_in_flight_per_host
+                    && state
+                        .last_visit
+                        .map(|last| last.elapsed() >= state.delay(min_delay))
+                        .unwrap_or(true)
+            }
             None => true,
         }
     }
 
+    /// Reserve the host (as `record_host_visit`) if `can_visit_host` allows it now.
+    /// Checked and recorded under one lock, so concurrent callers can't both get the slot.
+    pub fn try_reserve_host(&self, host: &str, min_delay: Duration) -> bool {
+        let mut state = self.hosts.entry(host.to_string()).or_default();
+        let ready = state.in_flight < self.max_in_flight_per_host
+            && state
+                .last_visit
+                .map(|last| last.elapsed() >= state.delay(min_delay))
+                .unwrap_or(true);
+        if ready {
+            state.last_visit = Some(Instant::now());
+            state.in_flight += 1;
+        }
+        ready
+    }
+
+    /// Release the host's in-flight slot and adapt its delay to the response.
+    /// 429/503 double the backoff (or honor Retry-After); successes halve it.
+    /// `status` is None when the fetch failed without a response.
+    pub fn finish_host_visit(&self, host: &str, status: Option<u16>, retry_after: Option<Duration>) {
+        let Some(mut state) = self.hosts.get_mut(host) else {
+            return;
+        };
+        state.in_flight = state.in_flight.saturating_sub(1);
+        match status {
+            Some(429) | Some(503) => {
+                let doubled = (state.backoff * 2).max(MIN_BACKOFF);
+                state.backoff = retry_after.unwrap_or(doubled).min(self.max_host_delay);
+                debug!(host, backoff_secs = state.backoff.as_secs(), "host throttling, backing off");
+            }
+            Some(s) if s < 400 => {
+                state.backoff /= 2;
+                if state.backoff < Duration::from_secs(1) {
+                    state.backoff = Duration::ZERO;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Set the host's robots.txt Crawl-delay (capped at the max host delay).
+    pub fn set_crawl_delay(&self, host: &str, delay: Duration) {
+        self.hosts.entry(host.to_string()).or_default().crawl_delay =
+            Some(delay.min(self.max_host_delay));
+    }
+
+    /// Pop the highest-priority job for a network whose host can be visited now,
+    /// and reserve the host (see `record_host_visit`).
+    /// Jobs for busy hosts are pushed back untouched, so workers move on to other
+    /// hosts instead of blocking. Returns None if the queue is empty or every
+    /// inspected job belongs to a busy host.
+    pub async fn pop_polite_for_network(&self, network: &str, default_delay: Duration) -> Option<CrawlJob> {
+        let nq = self.networks.get(network)?.clone();
+        let mut queue = nq.write().await;
+        let mut skipped = Vec::new();
+        let mut found = None;
+        while skipped.len() < POLITE_SCAN_LIMIT {
+            let Some(job) = queue.pop() else {
+                break;
+            };
+            match job.url.host_str() {
+                // Opaque URLs (hyphanet keys) have no host to be polite to
+                None => {
+                    found = Some(job);
+                    break;
+                }
+                Some(host) if self.try_reserve_host(host, default_delay) => {
+                    found = Some(job);
+                    break;
+                }
+                Some(_) => skipped.push(job),
+            }
+        }
+        for job in skipped {
+            let normalized = self.normalize_url(&job.url);
+            queue.push(normalized, job);
+        }
+        found
+    }
+
     /// How many URLs have been seen (bloom filter estimate).
     pub async fn seen_count(&self) -> usize {
         // GrowableBloom doesn't expose count, but we can track it externally if needed
@@ -370,39 +617,62 @@ impl CrawlFrontier {
     /// Seeds ALWAYS bypass the bloom filter — they are re-crawled every session
     /// because they're directories/registries that may have new links.
     pub async fn add_seeds(&self, urls: &[String], network: &str) -> usize {
-        let mut added = 0;
-        for url_str in urls {
-            if let Ok(url) = Url::parse(url_str) {
-                let normalized = Self::normalize_url(&url);
-                let priority = Self::calculate_priority(&url, 0);
-                let job = CrawlJob {
+        let added = self.add_unconditionally(urls, network).await;
+        debug!(
+            added,
+            total = urls.len(),
+            network,
+            "added seeds to frontier"
+        );
+        added
+    }
+
+    /// Re-queue known pages that are due for a revisit, bypassing the bloom filter
+    /// (they were crawled before, possibly this session). Jobs keep the depth, domain
+    /// hops and referrer of the visit that first found the page.
+    pub async fn add_revisits(&self, jobs: Vec<CrawlJob>, network: &str) -> usize {
+        let added = jobs.len();
+        self.push_unconditionally(jobs, network).await;
+        debug!(added, network, "added revisits to frontier");
+        added
+    }
+
+    /// Queue URLs as depth-0 jobs without the bloom check, marking them seen.
+    async fn add_unconditionally(&self, urls: &[String], network: &str) -> usize {
+        let jobs: Vec<CrawlJob> = urls
+            .iter()
+            .filter_map(|url_str| Url::parse(url_str).ok())
+            .map(|url| {
+                let priority = Self::calculate_priority(&url, false, 0, 0);
+                CrawlJob {
                     url,
                     depth: 0,
+                    domain_hops: 0,
                     source_url: None,
                     network: network.to_string(),
                     priority,
                     retry_count: 0,
-                };
-
-                // Add directly to network queue, bypassing bloom check.
-                // Mark as seen so discovered links TO seeds are deduped.
-                {
-                    let mut bloom = self.seen_urls.write().await;
-                    bloom.insert(&normalized);
                 }
-                let nq = self.get_network_queue(network);
-                let mut queue = nq.write().await;
-                queue.push(normalized, job);
-                added += 1;
+            })
+            .collect();
+        let added = jobs.len();
+        self.push_unconditionally(jobs, network).await;
+        added
+    }
+
+    async fn push_unconditionally(&self, jobs: Vec<CrawlJob>, network: &str) {
+        let nq = self.get_network_queue(network);
+        for job in jobs {
+            let normalized = self.normalize_url(&job.url);
+            // Add directly to network queue, bypassing bloom check.
+            // Mark as seen so discovered links to these URLs are deduped.
+            {
+                let mut bloom = self.seen_urls.write().await;
+                bloom.insert(&normalized);
             }
+            let mut queue = nq.write().await;
+            queue.push(normalized, job);
         }
-        debug!(
-            added,
-            total = urls.len(),
-            network,
-            "added seeds to frontier"
-        );
-        added
     }
 
     // === Backwards compatibility aliases ===
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use darkscraper_core::config::FrontierConfig;
+
+/// Revisit interval from a page's observed change rate.
+/// The rate is estimated as changes seen over the time the page has been
+/// observed (plus half a pseudo-change over one minimum interval, so new pages
+/// start near the minimum and back off while they stay unchanged). Forum
+/// indexes converge on the minimum interval, static pages on the maximum.
+pub struct RevisitPolicy {
+    min_interval: Duration,
+    max_interval: Duration,
+}
+
+impl RevisitPolicy {
+    pub fn new(config: &FrontierConfig) -> Self {
+        let min_interval = Duration::from_secs(config.revisit_min_interval_hours.max(1) * 3600);
+        let max_interval = Duration::from_secs(config.revisit_max_interval_hours * 3600).max(min_interval);
+        Self { min_interval, max_interval }
+    }
+
+    /// Next revisit interval for a page observed for `observed` (first to latest
+    /// visit) during which its content hash changed `changes` times.
+    pub fn next_interval(&self, observed: Duration, changes: u32) -> Duration {
+        let rate = (changes as f64 + 0.5) / (observed + self.min_interval).as_secs_f64();
+        Duration::from_secs_f64(1.0 / rate).clamp(self.min_interval, self.max_interval)
+    }
+
+    /// How long a claimed revisit stays reserved before it becomes due again
+    /// (covers URLs claimed but never fetched, e.g. on shutdown).
+    pub fn lease(&self) -> Duration {
+        self.min_interval
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+
+use dashmap::DashMap;
+use regex::Regex;
+use url::{Position, Url};
+
+use darkscraper_core::config::ScopeConfig;
+use darkscraper_core::CrawlJob;
+
+/// Crawl scope: which discovered URLs may enter the frontier.
+/// Rules are checked in a fixed order and the first failing rule is counted.
+pub struct CrawlScope {
+    networks: HashSet<String>,
+    allow_domains: Vec<String>,
+    deny_domains: Vec<String>,
+    include_patterns: Vec<Regex>,
+    exclude_patterns: Vec<Regex>,
+    /// Seed hosts, when the crawl must stay on them
+    seed_domains: Option<HashSet<String>>,
+    /// Rejections per rule this session
+    rejections: DashMap<ScopeRule, usize>,
+}
+
+/// The scope rule that rejected a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScopeRule {
+    /// Network not in `scope.networks`
+    Network,
+    /// Host matches `scope.deny_domains`
+    DenyDomain,
+    /// Host not in a non-empty `scope.allow_domains`
+    AllowDomain,
+    /// Host is not a seed host while `stay_on_seed_domains` is set
+    SeedDomain,
+    /// Path matches `scope.exclude_patterns`
+    ExcludePattern,
+    /// Path matches none of a non-empty `scope.include_patterns`
+    IncludePattern,
+}
+
+impl ScopeRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScopeRule::Network => "network",
+            ScopeRule::DenyDomain => "deny_domain",
+            ScopeRule::AllowDomain => "allow_domain",
+            ScopeRule::SeedDomain => "seed_domain",
+            ScopeRule::ExcludePattern => "exclude_pattern",
+            ScopeRule::IncludePattern => "include_pattern",
+        }
+    }
+}
+
+impl CrawlScope {
+    /// Build the scope from config. `seed_hosts` are only used with `stay_on_seed_domains`.
+    pub fn new(config: &ScopeConfig, seed_hosts: &[String]) -> Result<Self, regex::Error> {
+        let compile = |patterns: &[String]| -> Result<Vec<Regex>, regex::Error> {
+            patterns.iter().map(|p| Regex::new(p)).collect()
+        };
+        let lower = |domains: &[String]| -> Vec<String> {
+            domains
+                .iter()
+                .map(|d| d.trim().trim_start_matches('.').to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect()
+        };
+
+        Ok(Self {
+            networks: config.networks.iter().map(|n| n.to_lowercase()).collect(),
+            allow_domains: lower(&config.allow_domains),
+            deny_domains: lower(&config.deny_domains),
+            include_patterns: compile(&config.include_patterns)?,
+            exclude_patterns: compile(&config.exclude_patterns)?,
+            seed_domains: config
+                .stay_on_seed_domains
+                .then(|| seed_hosts.iter().map(|h| h.to_lowercase()).collect()),
+            rejections: DashMap::new(),
+        })
+    }
+
+    /// Whether workers should run for this network at all.
+    pub fn network_enabled(&self, network: &str) -> bool {
+        self.networks.is_empty() || self.networks.contains(network)
+    }
+
+    /// Check a job against every rule, counting the rejection if one fails.
+    pub fn check(&self, job: &CrawlJob) -> Result<(), ScopeRule> {
+        let result = self.evaluate(&job.network, &job.url);
+        if let Err(rule) = result {
+            *self.rejections.entry(rule).or_default() += 1;
+        }
+        result
+    }
+
+    fn evaluate(&self, network: &str, url: &Url) -> Result<(), ScopeRule> {
+        if !self.network_enabled(network) {
+            return Err(ScopeRule::Network);
+        }
+
+        // Opaque URLs (hyphanet keys) have no host and can't match a domain rule
+        let host = url.host_str().map(|h| h.to_lowercase());
+        let host = host.as_deref();
+        if let Some(host) = host {
+            if self.deny_domains.iter().any(|d| domain_matches(host, d)) {
+                return Err(ScopeRule::DenyDomain);
+            }
+        }
+        if !self.allow_domains.is_empty()
+            && !host.is_some_and(|h| self.allow_domains.iter().any(|d| domain_matches(h, d)))
+        {
+            return Err(ScopeRule::AllowDomain);
+        }
+        if let Some(seeds) = &self.seed_domains {
+            if !host.is_some_and(|h| seeds.contains(h)) {
+                return Err(ScopeRule::SeedDomain);
+            }
+        }
+
+        // Path rules see path + query
+        let path = &url[Position::BeforePath..];
+        if self.exclude_patterns.iter().any(|re| re.is_match(path)) {
+            return Err(ScopeRule::ExcludePattern);
+        }
+        if !self.include_patterns.is_empty() && !self.include_patterns.iter().any(|re| re.is_match(path)) {
+            return Err(ScopeRule::IncludePattern);
+        }
+        Ok(())
+    }
+
+    /// Rejections per rule this session.
+    pub fn rejection_counts(&self) -> Vec<(&'static str, usize)> {
+        let mut counts: Vec<(&'static str, usize)> = self
+            .rejections
+            .iter()
+            .map(|e| (e.key().as_str(), *e.value()))
+            .collect();
+        counts.sort();
+        counts
+    }
+}
+
+/// `host` is `domain` or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
@@ -0,0 +1,129 @@
+use regex::Regex;
+use url::Url;
+
+use darkscraper_core::config::TopicConfig;
+
+/// Keyword relevance scoring against a topic profile (focused crawling).
+/// Pages are scored on title + body text, links on anchor text + URL path.
+/// Scores are in [0, 1]; links from relevant pages or with relevant anchors
+/// get a priority boost, and with `min_relevance` set the rest are pruned.
+pub struct TopicScorer {
+    keywords: Vec<Keyword>,
+    /// (language code, keywords) — only applied to pages in that language
+    language_keywords: Vec<(String, Vec<Keyword>)>,
+    boost: f64,
+    min_relevance: f64,
+}
+
+struct Keyword {
+    pattern: Regex,
+    weight: f64,
+}
+
+/// Weighted keyword hits at which a page scores ~0.63 (1 - 1/e).
+const PAGE_SATURATION: f64 = 5.0;
+/// Same for links — anchors are short, one strong keyword should count.
+const LINK_SATURATION: f64 = 2.0;
+/// Title hits count this much more than body hits.
+const TITLE_WEIGHT: f64 = 2.0;
+/// Body text beyond this many bytes is not scored.
+const MAX_BODY_BYTES: usize = 200_000;
+
+impl TopicScorer {
+    pub fn new(config: &TopicConfig) -> Result<Self, regex::Error> {
+        let mut language_keywords = Vec::with_capacity(config.language_keywords.len());
+        for (language, keywords) in &config.language_keywords {
+            language_keywords.push((language.to_lowercase(), compile(keywords)?));
+        }
+        Ok(Self {
+            keywords: compile(&config.keywords)?,
+            language_keywords,
+            boost: config.boost.max(1.0),
+            min_relevance: config.min_relevance,
+        })
+    }
+
+    /// Whether a topic profile is configured at all.
+    pub fn is_enabled(&self) -> bool {
+        !self.keywords.is_empty() || self.language_keywords.iter().any(|(_, k)| !k.is_empty())
+    }
+
+    /// Page relevance from title and body text. `language` is the page's declared
+    /// language ("en-US" matches "en"); `None` applies every language's keywords.
+    pub fn score_page(&self, title: Option<&str>, body: &str, language: Option<&str>) -> f64 {
+        let body = truncate(body, MAX_BODY_BYTES);
+        let mut raw = 0.0;
+        for keyword in self.active_keywords(language) {
+            let hits = keyword.pattern.find_iter(body).count();
+            raw += keyword.weight * (1.0 + hits as f64).ln();
+            if title.is_some_and(|t| keyword.pattern.is_match(t)) {
+                raw += keyword.weight * TITLE_WEIGHT;
+            }
+        }
+        saturate(raw, PAGE_SATURATION)
+    }
+
+    /// Link relevance from anchor text and the URL path (slugs like /ransomware-leaks/).
+    pub fn score_link(&self, anchor_text: Option<&str>, url: &Url, language: Option<&str>) -> f64 {
+        // '_' is a word character, so split snake_case slugs for word-boundary matches
+        let path = url.path().replace(['_', '/'], " ");
+        let mut raw = 0.0;
+        for keyword in self.active_keywords(language) {
+            if anchor_text.is_some_and(|a| keyword.pattern.is_match(a)) || keyword.pattern.is_match(&path) {
+                raw += keyword.weight;
+            }
+        }
+        saturate(raw, LINK_SATURATION)
+    }
+
+    /// Priority multiplier for a link: 1.0 off topic, up to `boost` fully on topic.
+    pub fn link_multiplier(&self, page_relevance: f64, link_relevance: f64) -> f64 {
+        1.0 + (self.boost - 1.0) * (page_relevance + link_relevance) / 2.0
+    }
+
+    /// Whether a link should be dropped: neither the page nor the link is relevant enough.
+    pub fn should_prune(&self, page_relevance: f64, link_relevance: f64) -> bool {
+        page_relevance < self.min_relevance && link_relevance < self.min_relevance
+    }
+
+    fn active_keywords<'a>(&'a self, language: Option<&str>) -> impl Iterator<Item = &'a Keyword> {
+        let primary = language.map(|l| l.split(['-', '_']).next().unwrap_or(l).to_lowercase());
+        self.keywords.iter().chain(
+            self.language_keywords
+                .iter()
+                .filter(move |(lang, _)| primary.as_deref().is_none_or(|p| p == lang))
+                .flat_map(|(_, keywords)| keywords),
+        )
+    }
+}
+
+fn compile(keywords: &std::collections::HashMap<String, f64>) -> Result<Vec<Keyword>, regex::Error> {
+    keywords
+        .iter()
+        .filter(|(term, weight)| !term.trim().is_empty() && **weight > 0.0)
+        .map(|(term, weight)| {
+            // Phrases match across any run of whitespace
+            let words: Vec<String> = term.split_whitespace().map(regex::escape).collect();
+            Ok(Keyword {
+                pattern: Regex::new(&format!(r"(?i)\b{}\b", words.join(r"\s+")))?,
+                weight: *weight,
+            })
+        })
+        .collect()
+}
+
+fn saturate(raw: f64, scale: f64) -> f64 {
+    1.0 - (-raw / scale).exp()
+}
+
+/// Longest prefix of `s` of at most `max` bytes, on a char boundary.
+fn truncate(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
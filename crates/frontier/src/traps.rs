@@ -0,0 +1,290 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use chrono::{Datelike, Utc};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use url::Url;
+
+use darkscraper_core::config::FrontierConfig;
+
+/// Crawler trap detection.
+/// Block explorers, forum calendars, faceted shop filters and our own pattern
+/// mutations can generate unbounded URL spaces. URLs that look like traps are
+/// quarantined before they reach a queue, so they don't eat the domain's page budget.
+pub struct TrapDetector {
+    max_segment_repeats: usize,
+    max_query_length: usize,
+    max_param_combinations: usize,
+    max_calendar_urls: usize,
+    calendar_years_ahead: i32,
+    max_duplicate_content: usize,
+    /// host+path → distinct sets of query parameter names seen
+    param_sets: DashMap<String, HashSet<u64>>,
+    /// host → suspicious calendar/date URLs accepted so far
+    calendar_counts: DashMap<String, usize>,
+    /// host+URL template → calendar/date pages that returned content already seen on the host
+    repeating_calendar_templates: DashMap<String, ()>,
+    /// host → content hash → pages with that content
+    content_counts: DashMap<String, HashMap<String, usize>>,
+    /// host+URL template → quarantined because its pages repeat the same content
+    low_novelty_templates: DashMap<String, ()>,
+}
+
+/// Why a URL was quarantined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapReason {
+    /// The same path segment repeats (/a/b/a/b/a/b, relative-link loops)
+    RepeatedPathSegments,
+    /// Query string is too long or repeats a parameter (ever-growing filters)
+    GrowingQuery,
+    /// Too many far-off or content-repeating calendar/date URLs on one host
+    Calendar,
+    /// Too many parameter combinations on one path (faceted search)
+    ParamExplosion,
+    /// Pages with this URL template keep returning content already seen on the host
+    LowNovelty,
+}
+
+impl TrapReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrapReason::RepeatedPathSegments => "repeated_path_segments",
+            TrapReason::GrowingQuery => "growing_query",
+            TrapReason::Calendar => "calendar",
+            TrapReason::ParamExplosion => "param_explosion",
+            TrapReason::LowNovelty => "low_novelty",
+        }
+    }
+}
+
+// 2026/10, 2026-10-18, 2026_10
+static DATE_PATH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|[/=_-])((?:19|20)\d{2})[/_-](?:0?[1-9]|1[0-2])(?:[/_-]\d{1,2})?(?:$|[/&_.-])").unwrap());
+
+// Year inside a calendar parameter value: year=2031, date=2031-05-01
+static YEAR_VALUE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\D)(\d{4})(?:\D|$)").unwrap());
+
+/// Query parameter names used by calendar widgets.
+const CALENDAR_PARAMS: &[&str] = &["date", "day", "month", "year", "week", "cal", "calendar"];
+
+/// Dates before this are calendar widgets paging backwards: no site archive predates the web.
+const EARLIEST_ARCHIVE_YEAR: i32 = 1990;
+
+static DIGITS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+impl TrapDetector {
+    pub fn new(config: &FrontierConfig) -> Self {
+        Self {
+            max_segment_repeats: config.trap_max_segment_repeats,
+            max_query_length: config.trap_max_query_length,
+            max_param_combinations: config.trap_max_param_combinations,
+            max_calendar_urls: config.trap_max_calendar_urls,
+            calendar_years_ahead: config.trap_calendar_years_ahead as i32,
+            max_duplicate_content: config.trap_max_duplicate_content,
+            param_sets: DashMap::new(),
+            calendar_counts: DashMap::new(),
+            repeating_calendar_templates: DashMap::new(),
+            content_counts: DashMap::new(),
+            low_novelty_templates: DashMap::new(),
+        }
+    }
+
+    /// Check a URL before it is enqueued. Returns the trap reason if it should be quarantined.
+    /// Stateful: accepted URLs count towards the per-host calendar and parameter budgets.
+    /// Ordinary date archives (/2024/05/) don't count towards the calendar budget: only
+    /// dates far from the crawl date, or templates whose pages repeat content, do.
+    pub fn check(&self, url: &Url) -> Option<TrapReason> {
+        let host = url.host_str()?;
+
+        if self
+            .low_novelty_templates
+            .contains_key(&format!("{}{}", host, url_template(url)))
+        {
+            return Some(TrapReason::LowNovelty);
+        }
+
+        // Repeated path segments
+        let segments: Vec<&str> = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect();
+        if has_repeating_cycle(&segments, self.max_segment_repeats) {
+            return Some(TrapReason::RepeatedPathSegments);
+        }
+
+        // Ever-growing query strings
+        let param_names: Vec<String> = url
+            .query_pairs()
+            .map(|(name, _)| name.to_lowercase())
+            .collect();
+        if let Some(query) = url.query() {
+            let distinct: HashSet<&String> = param_names.iter().collect();
+            if query.len() > self.max_query_length || distinct.len() + 2 < param_names.len() {
+                return Some(TrapReason::GrowingQuery);
+            }
+        }
+
+        // Calendar / date pages
+        if is_calendar_url(url) && self.is_suspicious_calendar(host, url) {
+            let mut count = self.calendar_counts.entry(host.to_string()).or_default();
+            if *count >= self.max_calendar_urls {
+                return Some(TrapReason::Calendar);
+            }
+            *count += 1;
+        }
+
+        // Parameter-permutation explosion on one path
+        if !param_names.is_empty() {
+            let mut names = param_names;
+            names.sort();
+            names.dedup();
+            let mut hasher = DefaultHasher::new();
+            names.hash(&mut hasher);
+            let combination = hasher.finish();
+
+            let mut seen = self
+                .param_sets
+                .entry(format!("{}{}", host, url.path()))
+                .or_default();
+            if !seen.contains(&combination) {
+                if seen.len() >= self.max_param_combinations {
+                    return Some(TrapReason::ParamExplosion);
+                }
+                seen.insert(combination);
+            }
+        }
+
+        None
+    }
+
+    /// Record a fetched page's content hash. Once the same content has been seen
+    /// too often on a host, the page's URL template is quarantined (low novelty).
+    /// Returns true when this call quarantined the template.
+    pub fn record_content(&self, url: &Url, content_hash: &str) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let mut hashes = self.content_counts.entry(host.to_string()).or_default();
+        let count = hashes.entry(content_hash.to_string()).or_default();
+        *count += 1;
+        // A calendar whose pages repeat content ("no events this day") is a generated
+        // URL space, not an archive: its dates start counting towards the calendar cap
+        if *count > 1 && is_calendar_url(url) {
+            self.repeating_calendar_templates
+                .insert(format!("{}{}", host, url_template(url)), ());
+        }
+        if *count <= self.max_duplicate_content {
+            return false;
+        }
+        let key = format!("{}{}", host, url_template(url));
+        self.low_novelty_templates.insert(key, ()).is_none()
+    }
+
+    /// Whether a calendar/date URL counts towards the host's calendar cap: its date is
+    /// far from the crawl date, or pages with its URL template have repeated content.
+    fn is_suspicious_calendar(&self, host: &str, url: &Url) -> bool {
+        let this_year = Utc::now().year();
+        let far_off = url_year(url).is_some_and(|year| {
+            year > this_year + self.calendar_years_ahead || year < EARLIEST_ARCHIVE_YEAR
+        });
+        far_off
+            || self
+                .repeating_calendar_templates
+                .contains_key(&format!("{}{}", host, url_template(url)))
+    }
+}
+
+/// Whether a run of segments repeats back to back `max_repeats` times: /a/a/a or
+/// /a/b/a/b/a/b (relative-link loops). IDs recurring apart (/page/1/sort/1/filter/1) don't count.
+fn has_repeating_cycle(segments: &[&str], max_repeats: usize) -> bool {
+    let max_repeats = max_repeats.max(2);
+    for period in 1..=segments.len() / max_repeats {
+        // Length of the current stretch where each segment equals the one `period` back
+        let mut matching = 0;
+        for i in period..segments.len() {
+            if segments[i] == segments[i - period] {
+                matching += 1;
+                if matching >= period * (max_repeats - 1) {
+                    return true;
+                }
+            } else {
+                matching = 0;
+            }
+        }
+    }
+    false
+}
+
+/// Whether the URL looks like a calendar/date page (date in the path or a calendar parameter).
+fn is_calendar_url(url: &Url) -> bool {
+    DATE_PATH_RE.is_match(url.path())
+        || url
+            .query_pairs()
+            .any(|(name, _)| CALENDAR_PARAMS.contains(&name.to_lowercase().as_str()))
+}
+
+/// Year of a calendar/date URL, from its path or its calendar parameters.
+fn url_year(url: &Url) -> Option<i32> {
+    if let Some(captures) = DATE_PATH_RE.captures(url.path()) {
+        return captures[1].parse().ok();
+    }
+    url.query_pairs()
+        .filter(|(name, _)| CALENDAR_PARAMS.contains(&name.to_lowercase().as_str()))
+        .find_map(|(_, value)| YEAR_VALUE_RE.captures(&value).and_then(|c| c[1].parse().ok()))
+}
+
+/// URL shape with numbers and query values erased: /block/123?page=4 → /block/{n}?page
+pub(crate) fn url_template(url: &Url) -> String {
+    let path = DIGITS_RE.replace_all(url.path(), "{n}");
+    let mut names: Vec<String> = url.query_pairs().map(|(name, _)| name.into_owned()).collect();
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        path.into_owned()
+    } else {
+        format!("{}?{}", path, names.join("&"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> TrapDetector {
+        TrapDetector::new(&FrontierConfig::default())
+    }
+
+    fn check(detector: &TrapDetector, url: &str) -> Option<TrapReason> {
+        detector.check(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn repeated_ids_in_legitimate_paths_pass() {
+        let traps = detector();
+        assert_eq!(check(&traps, "http://a.onion/page/1/sort/1/filter/1"), None);
+        assert_eq!(check(&traps, "http://a.onion/a/b/1/c/1/d/1"), None);
+        assert_eq!(check(&traps, "http://a.onion/forum/thread/7/post/7/reply/7/quote/7"), None);
+    }
+
+    #[test]
+    fn repeating_runs_and_cycles_are_traps() {
+        let traps = detector();
+        assert_eq!(
+            check(&traps, "http://a.onion/x/x/x"),
+            Some(TrapReason::RepeatedPathSegments)
+        );
+        assert_eq!(
+            check(&traps, "http://a.onion/a/b/a/b/a/b"),
+            Some(TrapReason::RepeatedPathSegments)
+        );
+        assert_eq!(
+            check(&traps, "http://a.onion/docs/img/css/img/css/img/css/style.css"),
+            Some(TrapReason::RepeatedPathSegments)
+        );
+        assert_eq!(check(&traps, "http://a.onion/a/b/a/b"), None);
+    }
+}
@@ -0,0 +1,152 @@
+use dashmap::{DashMap, DashSet};
+use url::Url;
+
+use darkscraper_core::CrawlJob;
+
+use crate::traps::url_template;
+
+/// Learned crawl yield per source page, domain and URL pattern.
+/// Every fetched page credits what it produced (new domains, new entities,
+/// new correlations, errors) to its domain, its URL pattern and the page that
+/// linked to it. New jobs are boosted or penalized by those scores, so
+/// low-value pagination sinks while productive directories rise.
+pub struct YieldTracker {
+    stats: DashMap<(YieldKind, String), YieldStats>,
+    /// Keys changed since the last `drain_dirty`
+    dirty: DashSet<(YieldKind, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum YieldKind {
+    /// Keyed by the linking page's URL: scores what its links led to
+    SourcePage,
+    Domain,
+    /// Keyed by host + URL template (numbers and query values erased)
+    Pattern,
+}
+
+impl YieldKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            YieldKind::SourcePage => "source_page",
+            YieldKind::Domain => "domain",
+            YieldKind::Pattern => "pattern",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "source_page" => Some(YieldKind::SourcePage),
+            "domain" => Some(YieldKind::Domain),
+            "pattern" => Some(YieldKind::Pattern),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome counters. Used both as running totals and as the delta for one page.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YieldStats {
+    pub pages: u64,
+    pub new_domains: u64,
+    pub new_entities: u64,
+    pub new_correlations: u64,
+    pub errors: u64,
+}
+
+impl YieldStats {
+    fn add(&mut self, other: &YieldStats) {
+        self.pages += other.pages;
+        self.new_domains += other.new_domains;
+        self.new_entities += other.new_entities;
+        self.new_correlations += other.new_correlations;
+        self.errors += other.errors;
+    }
+
+    /// Value produced per fetch, smoothed towards 1.0 while there's little data.
+    /// A new domain is worth 3, a new correlation 2, a new entity 1; errors are
+    /// fetches that produced nothing.
+    pub fn score(&self) -> f64 {
+        let value = self.new_domains as f64 * 3.0
+            + self.new_correlations as f64 * 2.0
+            + self.new_entities as f64;
+        let fetches = (self.pages + self.errors) as f64;
+        (value + PRIOR_FETCHES) / (fetches + PRIOR_FETCHES)
+    }
+}
+
+/// Pseudo-fetches of average (1.0) yield added to every score.
+const PRIOR_FETCHES: f64 = 5.0;
+/// Bounds for the priority multiplier.
+const MIN_MULTIPLIER: f64 = 0.1;
+const MAX_MULTIPLIER: f64 = 10.0;
+
+impl Default for YieldTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl YieldTracker {
+    pub fn new() -> Self {
+        Self {
+            stats: DashMap::new(),
+            dirty: DashSet::new(),
+        }
+    }
+
+    /// Load persisted totals (at startup).
+    pub fn load(&self, kind: YieldKind, key: String, stats: YieldStats) {
+        self.stats.insert((kind, key), stats);
+    }
+
+    /// Credit a fetched page's outcome to its domain, URL pattern and linking page.
+    pub fn record(&self, url: &Url, source_url: Option<&str>, delta: YieldStats) {
+        for key in Self::keys(url, source_url) {
+            self.stats.entry(key.clone()).or_default().add(&delta);
+            self.dirty.insert(key);
+        }
+    }
+
+    /// Priority multiplier for a job: geometric mean of the known scores of its
+    /// domain, URL pattern and source page (1.0 when nothing is known yet).
+    pub fn multiplier(&self, job: &CrawlJob) -> f64 {
+        let mut log_sum = 0.0;
+        let mut known = 0;
+        for key in Self::keys(&job.url, job.source_url.as_deref()) {
+            if let Some(stats) = self.stats.get(&key) {
+                log_sum += stats.score().ln();
+                known += 1;
+            }
+        }
+        if known == 0 {
+            return 1.0;
+        }
+        (log_sum / known as f64).exp().clamp(MIN_MULTIPLIER, MAX_MULTIPLIER)
+    }
+
+    /// Take the totals changed since the last call (for persisting).
+    pub fn drain_dirty(&self) -> Vec<(YieldKind, String, YieldStats)> {
+        let keys: Vec<(YieldKind, String)> = self.dirty.iter().map(|k| k.key().clone()).collect();
+        let mut changed = Vec::with_capacity(keys.len());
+        for key in keys {
+            self.dirty.remove(&key);
+            if let Some(stats) = self.stats.get(&key) {
+                changed.push((key.0, key.1, *stats));
+            }
+        }
+        changed
+    }
+
+    fn keys(url: &Url, source_url: Option<&str>) -> Vec<(YieldKind, String)> {
+        let mut keys = Vec::with_capacity(3);
+        if let Some(host) = url.host_str() {
+            keys.push((YieldKind::Domain, host.to_string()));
+            keys.push((YieldKind::Pattern, format!("{}{}", host, url_template(url))));
+        }
+        if let Some(source) = source_url {
+            keys.push((YieldKind::SourcePage, source.to_string()));
+        }
+        keys
+    }
+}
@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio_rustls::rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use tokio_rustls::TlsConnector;
+use tracing::{debug, warn};
+use url::Url;
+
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
+use crate::socks;
+use crate::tor::is_v3_onion;
+
+const DEFAULT_PORT: u16 = 1965;
+/// Gemini requests are the absolute URL, at most 1024 bytes
+const MAX_REQUEST_URL: usize = 1024;
+/// Status line: two digits, a space, up to 1024 bytes of meta, CRLF
+const MAX_HEADER: usize = 1029;
+const MAX_REDIRECTS: usize = 5;
+
+/// Gemini capsules on onion services, through Tor's SOCKS proxies.
+/// Capsules use self-signed certificates, so certificates are pinned trust-on-first-use:
+/// the first certificate seen for a host:port is remembered for the session and a
+/// different one later fails the fetch.
+pub struct GeminiDriver {
+    name: String,
+    /// SOCKS addresses
+    proxy_addrs: Vec<String>,
+    tls: TlsConnector,
+    /// host:port → SHA-256 of the pinned certificate
+    pins: Mutex<HashMap<String, String>>,
+    counter: AtomicUsize,
+    max_concurrency: usize,
+    min_delay: Duration,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+}
+
+/// Parsed response: (gemini status, meta, body)
+type GeminiReply = (u16, String, Vec<u8>);
+
+impl GeminiDriver {
+    pub fn new(
+        socks_addrs: &[String],
+        max_concurrency: usize,
+        min_delay_seconds: u64,
+        connect_timeout_seconds: u64,
+        request_timeout_seconds: u64,
+    ) -> Result<Self, CrawlError> {
+        if socks_addrs.is_empty() {
+            return Err(CrawlError::Proxy("no gemini socks proxies configured".into()));
+        }
+
+        let provider = Arc::new(ring::default_provider());
+        let tls = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| CrawlError::Config(e.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(TofuVerifier(provider)))
+            .with_no_client_auth();
+
+        Ok(Self {
+            name: "gemini".to_string(),
+            proxy_addrs: socks_addrs.to_vec(),
+            tls: TlsConnector::from(Arc::new(tls)),
+            pins: Mutex::new(HashMap::new()),
+            counter: AtomicUsize::new(rand::random::<usize>()),
+            max_concurrency,
+            min_delay: Duration::from_secs(min_delay_seconds),
+            connect_timeout: Duration::from_secs(connect_timeout_seconds),
+            request_timeout: Duration::from_secs(request_timeout_seconds),
+        })
+    }
+
+    fn next_proxy(&self) -> &str {
+        let idx = self.counter.fetch_add(1, Ordering::Relaxed) % self.proxy_addrs.len();
+        &self.proxy_addrs[idx]
+    }
+
+    /// Compare the certificate against the host's pin, pinning it if it's the first.
+    fn check_pin(&self, key: &str, cert: &[u8]) -> Result<(), CrawlError> {
+        let fingerprint = format!("{:x}", Sha256::digest(cert));
+        let mut pins = self.pins.lock().unwrap_or_else(|e| e.into_inner());
+        match pins.get(key) {
+            Some(pinned) if *pinned != fingerprint => Err(CrawlError::Network(format!(
+                "certificate for {} changed (pinned {}, got {})",
+                key, pinned, fingerprint
+            ))),
+            Some(_) => Ok(()),
+            None => {
+                pins.insert(key.to_string(), fingerprint);
+                Ok(())
+            }
+        }
+    }
+
+    /// One request/response exchange.
+    async fn request(&self, url: &Url, max_body: usize) -> Result<GeminiReply, CrawlError> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| CrawlError::InvalidUrl(url.to_string()))?;
+        let port = url.port().unwrap_or(DEFAULT_PORT);
+        let mut request_url = url.clone();
+        request_url.set_fragment(None);
+        let request = format!("{}\r\n", request_url);
+        if request.len() - 2 > MAX_REQUEST_URL {
+            return Err(CrawlError::InvalidUrl(format!("gemini URL longer than {} bytes", MAX_REQUEST_URL)));
+        }
+
+        let stream = socks::connect(self.next_proxy(), host, port, self.connect_timeout).await?;
+        let server_name = ServerName::try_from(host.to_string())
+            .map_err(|e| CrawlError::InvalidUrl(format!("{}: {}", host, e)))?;
+        let mut tls = self
+            .tls
+            .connect(server_name, stream)
+            .await
+            .map_err(|e| CrawlError::Network(format!("tls handshake: {}", e)))?;
+
+        let cert = tls
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .ok_or_else(|| CrawlError::Network("server sent no certificate".into()))?;
+        self.check_pin(&format!("{}:{}", host, port), cert.as_ref())?;
+
+        tls.write_all(request.as_bytes())
+            .await
+            .map_err(|e| CrawlError::Network(e.to_string()))?;
+        let raw = socks::read_limited(tls, max_body + MAX_HEADER).await?;
+        parse_reply(raw)
+    }
+}
+
+/// Split a raw response into (status, meta, body).
+fn parse_reply(mut raw: Vec<u8>) -> Result<GeminiReply, CrawlError> {
+    let header_end = raw
+        .iter()
+        .take(MAX_HEADER + 1)
+        .position(|&b| b == b'\n')
+        .ok_or_else(|| CrawlError::Parse("gemini response has no status line".into()))?;
+    let header = String::from_utf8_lossy(&raw[..header_end]).trim_end_matches('\r').to_string();
+    let body = raw.split_off(header_end + 1);
+
+    let status = header
+        .get(..2)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| CrawlError::Parse(format!("bad gemini status line: {:?}", header)))?;
+    let meta = header.get(2..).unwrap_or("").trim().to_string();
+    Ok((status, meta, body))
+}
+
+/// HTTP equivalent of a gemini status, for the crawler's status handling.
+fn http_status(status: u16) -> u16 {
+    match status {
+        10..=19 => 400, // input required: a query endpoint, nothing to crawl
+        20..=29 => 200,
+        30 => 302,
+        31 => 301,
+        44 => 429, // slow down
+        40..=49 => 503,
+        51 => 404,
+        52 => 410,
+        53 => 403, // proxy request refused
+        59 => 400,
+        50..=59 => 500,
+        60..=69 => 401, // client certificate required
+        _ => 500,
+    }
+}
+
+impl BuiltinDriver for GeminiDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl NetworkDriver for GeminiDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn can_handle(&self, url: &Url) -> bool {
+        url.scheme() == "gemini" && url.host_str().is_some_and(is_v3_onion)
+    }
+
+    /// The capsules are reached through Tor, so readiness is Tor's SOCKS port
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_addrs.len());
+        for addr in &self.proxy_addrs {
+            health.push(tcp_probe(addr).await);
+        }
+        health
+    }
+
+    async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
+        let start = Instant::now();
+        debug!(url = %url, retry_count, "fetching via gemini");
+
+        let mut current = url.clone();
+        let mut redirects = 0;
+        let (status, meta, body) = loop {
+            let reply = tokio::time::timeout(self.request_timeout, self.request(&current, config.max_body_size))
+                .await
+                .map_err(|_| CrawlError::Timeout(self.request_timeout.as_secs()))?
+                .inspect_err(|e| warn!(url = %current, error = %e, "gemini fetch failed"))?;
+
+            // Follow redirects that stay on gemini-over-tor
+            if (30..40).contains(&reply.0) && config.follow_redirects && redirects < MAX_REDIRECTS {
+                if let Ok(target) = current.join(&reply.1) {
+                    if self.can_handle(&target) {
+                        current = target;
+                        redirects += 1;
+                        continue;
+                    }
+                }
+            }
+            break reply;
+        };
+
+        let mut headers = HashMap::new();
+        headers.insert("gemini-status".to_string(), status.to_string());
+        headers.insert("gemini-meta".to_string(), meta.clone());
+        let content_type = match status {
+            20..=29 => Some(if meta.is_empty() {
+                "text/gemini; charset=utf-8".to_string()
+            } else {
+                meta.clone()
+            }),
+            30..=39 => {
+                if let Ok(target) = current.join(&meta) {
+                    headers.insert("location".to_string(), target.to_string());
+                }
+                None
+            }
+            44 => {
+                headers.insert("retry-after".to_string(), meta.clone());
+                None
+            }
+            _ => None,
+        };
+
+        let domain = current.host_str().unwrap_or("unknown").to_string();
+        Ok(FetchResponse {
+            url: url.clone(),
+            final_url: current,
+            status: http_status(status),
+            headers,
+            body,
+            content_type,
+            fetched_at: chrono::Utc::now(),
+            network: self.name.clone(),
+            response_time_ms: start.elapsed().as_millis() as u64,
+            domain,
+        })
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    fn default_delay(&self) -> Duration {
+        self.min_delay
+    }
+
+    fn max_retries(&self) -> u32 {
+        // Same onion services as tor: fail fast
+        3
+    }
+
+    fn classify_error(&self, error: &str) -> &'static str {
+        let error_lower = error.to_lowercase();
+        // A changed certificate won't change back by retrying
+        if error_lower.contains("certificate for") || error_lower.contains("bad gemini status") {
+            return "dead";
+        }
+        if error_lower.contains("host unreachable") || error_lower.contains("ttl expired") {
+            return "dead"; // SOCKS reply for an onion service that isn't published
+        }
+        "unreachable"
+    }
+}
+
+/// Accepts any certificate during the handshake (capsules are self-signed); the
+/// TOFU pin is checked right after. Handshake signatures are still verified.
+#[derive(Debug)]
+struct TofuVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for TofuVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio_rustls::rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::TlsAcceptor;
+
+    const CAPSULE: &str = "gemini://aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion/";
+
+    /// TLS acceptor with a fresh self-signed certificate.
+    fn certificate() -> TlsAcceptor {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.key_pair.serialize_der()));
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert.cert.der().clone()], key)
+            .unwrap();
+        TlsAcceptor::from(Arc::new(config))
+    }
+
+    /// A capsule behind a stub SOCKS proxy answering every request line with `reply`.
+    /// Connection n presents `certs[n]` (the last one once they run out). Returns the
+    /// driver and the number of connections made.
+    async fn capsule(certs: Vec<TlsAcceptor>, reply: fn(&str) -> String) -> (GeminiDriver, Arc<AtomicUsize>) {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&connections);
+        let proxy = socks::stub::proxy(move |stream| {
+            let n = count.fetch_add(1, Ordering::SeqCst);
+            let acceptor = certs[n.min(certs.len() - 1)].clone();
+            async move {
+                let Ok(mut tls) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let mut line = String::new();
+                if BufReader::new(&mut tls).read_line(&mut line).await.is_err() {
+                    return;
+                }
+                let _ = tls.write_all(reply(line.trim_end()).as_bytes()).await;
+                let _ = tls.shutdown().await;
+            }
+        })
+        .await;
+        (GeminiDriver::new(&[proxy], 1, 0, 5, 5).unwrap(), connections)
+    }
+
+    fn page(_request: &str) -> String {
+        "20 text/gemini; lang=en\r\n# Hello\n=> /next.gmi Next\n".to_string()
+    }
+
+    #[tokio::test]
+    async fn fetches_a_page() {
+        let (driver, _) = capsule(vec![certificate()], page).await;
+        let resp = driver
+            .fetch(&Url::parse(CAPSULE).unwrap(), &FetchConfig::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.content_type.as_deref(), Some("text/gemini; lang=en"));
+        assert_eq!(resp.body, b"# Hello\n=> /next.gmi Next\n");
+        assert_eq!(resp.headers["gemini-status"], "20");
+    }
+
+    #[tokio::test]
+    async fn certificate_is_pinned_on_first_use() {
+        let first = certificate();
+        let (driver, _) = capsule(vec![first.clone(), first, certificate()], page).await;
+        let url = Url::parse(CAPSULE).unwrap();
+        let config = FetchConfig::default();
+
+        // First certificate is pinned, the same one is accepted again
+        assert!(driver.fetch(&url, &config, 0).await.is_ok());
+        assert!(driver.fetch(&url, &config, 0).await.is_ok());
+
+        // A different certificate for the same host:port fails, permanently
+        let err = driver.fetch(&url, &config, 0).await.unwrap_err().to_string();
+        assert!(err.contains("certificate for"), "{err}");
+        assert_eq!(driver.classify_error(&err), "dead");
+    }
+
+    #[tokio::test]
+    async fn redirects_stop_at_the_limit() {
+        let (driver, connections) = capsule(vec![certificate()], |_| "31 /loop\r\n".to_string()).await;
+        let resp = driver
+            .fetch(&Url::parse(CAPSULE).unwrap(), &FetchConfig::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), MAX_REDIRECTS + 1);
+        assert_eq!(resp.status, 301);
+        assert!(resp.headers["location"].ends_with(".onion/loop"));
+        assert!(resp.final_url.as_str().ends_with(".onion/loop"));
+    }
+
+    #[tokio::test]
+    async fn redirects_leaving_onion_capsules_are_not_followed() {
+        let (driver, connections) =
+            capsule(vec![certificate()], |_| "30 gemini://example.org/\r\n".to_string()).await;
+        let resp = driver
+            .fetch(&Url::parse(CAPSULE).unwrap(), &FetchConfig::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+        assert_eq!(resp.status, 302);
+        assert_eq!(resp.headers["location"], "gemini://example.org/");
+    }
+
+    #[tokio::test]
+    async fn request_line_is_the_url_without_fragment() {
+        let (driver, _) = capsule(vec![certificate()], |request| format!("20 text/plain\r\n{request}")).await;
+        let url = Url::parse(&format!("{CAPSULE}path?q=1#frag")).unwrap();
+        let resp = driver.fetch(&url, &FetchConfig::default(), 0).await.unwrap();
+        assert_eq!(String::from_utf8(resp.body).unwrap(), format!("{CAPSULE}path?q=1"));
+    }
+
+    #[test]
+    fn parse_reply_splits_status_meta_and_body() {
+        let (status, meta, body) = parse_reply(b"20 text/gemini\r\nbody\n".to_vec()).unwrap();
+        assert_eq!((status, meta.as_str(), body.as_slice()), (20, "text/gemini", &b"body\n"[..]));
+
+        // No meta, bare LF
+        let (status, meta, body) = parse_reply(b"51\n".to_vec()).unwrap();
+        assert_eq!((status, meta.as_str(), body.len()), (51, "", 0));
+
+        // Longest allowed meta
+        let longest = format!("20 {}\r\n", "m".repeat(1024));
+        assert_eq!(parse_reply(longest.into_bytes()).unwrap().1.len(), 1024);
+    }
+
+    #[test]
+    fn parse_reply_rejects_bad_headers() {
+        let missing = parse_reply(b"20 text/gemini".to_vec()).unwrap_err();
+        assert!(missing.to_string().contains("no status line"));
+
+        // The status line must end within MAX_HEADER bytes, wherever the body ends
+        let oversized = format!("20 {}\r\nbody\n", "m".repeat(2000));
+        assert!(parse_reply(oversized.into_bytes()).is_err());
+
+        assert!(parse_reply(b"OK text/gemini\r\n".to_vec()).is_err());
+        assert!(parse_reply(b"\r\n".to_vec()).is_err());
+    }
+
+    #[test]
+    fn statuses_map_to_http() {
+        for (gemini, http) in [
+            (10, 400),
+            (11, 400),
+            (20, 200),
+            (30, 302),
+            (31, 301),
+            (40, 503),
+            (41, 503),
+            (44, 429),
+            (50, 500),
+            (51, 404),
+            (52, 410),
+            (53, 403),
+            (59, 400),
+            (60, 401),
+            (62, 401),
+            (99, 500),
+        ] {
+            assert_eq!(http_status(gemini), http, "gemini status {gemini}");
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use percent_encoding::percent_decode_str;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+use url::Url;
+
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
+use crate::socks;
+use crate::tor::is_v3_onion;
+
+const DEFAULT_PORT: u16 = 70;
+
+/// Content type for gopher menus (item types 1 and 7); the parser keys on it.
+const GOPHER_MENU: &str = "application/gopher-menu";
+
+/// Gopher holes on onion services, through Tor's SOCKS proxies.
+/// URLs follow RFC 4266: gopher://host:port/<item type><selector>[?search].
+pub struct GopherDriver {
+    name: String,
+    /// SOCKS addresses
+    proxy_addrs: Vec<String>,
+    counter: AtomicUsize,
+    max_concurrency: usize,
+    min_delay: Duration,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+}
+
+impl GopherDriver {
+    pub fn new(
+        socks_addrs: &[String],
+        max_concurrency: usize,
+        min_delay_seconds: u64,
+        connect_timeout_seconds: u64,
+        request_timeout_seconds: u64,
+    ) -> Result<Self, CrawlError> {
+        if socks_addrs.is_empty() {
+            return Err(CrawlError::Proxy("no gopher socks proxies configured".into()));
+        }
+        Ok(Self {
+            name: "gopher".to_string(),
+            proxy_addrs: socks_addrs.to_vec(),
+            counter: AtomicUsize::new(rand::random::<usize>()),
+            max_concurrency,
+            min_delay: Duration::from_secs(min_delay_seconds),
+            connect_timeout: Duration::from_secs(connect_timeout_seconds),
+            request_timeout: Duration::from_secs(request_timeout_seconds),
+        })
+    }
+
+    fn next_proxy(&self) -> &str {
+        let idx = self.counter.fetch_add(1, Ordering::Relaxed) % self.proxy_addrs.len();
+        &self.proxy_addrs[idx]
+    }
+
+    async fn request(&self, host: &str, port: u16, request: &str, max_body: usize) -> Result<Vec<u8>, CrawlError> {
+        let mut stream = socks::connect(self.next_proxy(), host, port, self.connect_timeout).await?;
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| CrawlError::Network(e.to_string()))?;
+        socks::read_limited(stream, max_body).await
+    }
+}
+
+/// (item type, request line) for a gopher URL. The root path is the top-level menu.
+fn request_line(url: &Url) -> (char, String) {
+    let path = percent_decode_str(url.path()).decode_utf8_lossy();
+    let mut chars = path.trim_start_matches('/').chars();
+    let item_type = chars.next().unwrap_or('1');
+    let mut selector: String = chars.collect();
+    // Full-text search (type 7): the query is sent after a tab
+    if let Some(query) = url.query() {
+        selector.push('\t');
+        selector.push_str(&percent_decode_str(query).decode_utf8_lossy());
+    }
+    (item_type, format!("{}\r\n", selector))
+}
+
+/// Content type for an item type. Unknown types are treated as binary.
+fn content_type(item_type: char) -> &'static str {
+    match item_type {
+        '1' | '7' => GOPHER_MENU,
+        '0' => "text/plain",
+        'h' => "text/html",
+        'g' => "image/gif",
+        'p' => "image/png",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Text items end with a lone "." line; strip it.
+fn strip_terminator(body: &mut Vec<u8>) {
+    let end = body.iter().rposition(|&b| b != b'\r' && b != b'\n').map_or(0, |i| i + 1);
+    if end > 0 && body[end - 1] == b'.' && (end == 1 || body[end - 2] == b'\n') {
+        body.truncate(end - 1);
+    }
+}
+
+impl BuiltinDriver for GopherDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl NetworkDriver for GopherDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn can_handle(&self, url: &Url) -> bool {
+        url.scheme() == "gopher" && url.host_str().is_some_and(is_v3_onion)
+    }
+
+    /// The holes are reached through Tor, so readiness is Tor's SOCKS port
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_addrs.len());
+        for addr in &self.proxy_addrs {
+            health.push(tcp_probe(addr).await);
+        }
+        health
+    }
+
+    async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
+        let start = Instant::now();
+        let host = url
+            .host_str()
+            .ok_or_else(|| CrawlError::InvalidUrl(url.to_string()))?;
+        let port = url.port().unwrap_or(DEFAULT_PORT);
+        let (item_type, request) = request_line(url);
+        debug!(url = %url, item_type = %item_type, retry_count, "fetching via gopher");
+
+        let mut body = tokio::time::timeout(
+            self.request_timeout,
+            self.request(host, port, &request, config.max_body_size),
+        )
+        .await
+        .map_err(|_| CrawlError::Timeout(self.request_timeout.as_secs()))?
+        .inspect_err(|e| warn!(url = %url, error = %e, "gopher fetch failed"))?;
+
+        let content_type = content_type(item_type);
+        if content_type == GOPHER_MENU || item_type == '0' {
+            strip_terminator(&mut body);
+        }
+        // Servers answer unknown selectors with a menu holding a single error item
+        let status = if content_type == GOPHER_MENU && body.first() == Some(&b'3') {
+            404
+        } else {
+            200
+        };
+
+        Ok(FetchResponse {
+            url: url.clone(),
+            final_url: url.clone(),
+            status,
+            headers: HashMap::new(),
+            body,
+            content_type: Some(content_type.to_string()),
+            fetched_at: chrono::Utc::now(),
+            network: self.name.clone(),
+            response_time_ms: start.elapsed().as_millis() as u64,
+            domain: host.to_string(),
+        })
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    fn default_delay(&self) -> Duration {
+        self.min_delay
+    }
+
+    fn max_retries(&self) -> u32 {
+        // Same onion services as tor: fail fast
+        3
+    }
+
+    fn classify_error(&self, error: &str) -> &'static str {
+        let error_lower = error.to_lowercase();
+        if error_lower.contains("host unreachable") || error_lower.contains("ttl expired") {
+            return "dead"; // SOCKS reply for an onion service that isn't published
+        }
+        "unreachable"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    const HOLE: &str = "gopher://aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion";
+
+    /// A hole behind a stub SOCKS proxy answering each request line with `reply`.
+    /// Returns the driver and the request lines received.
+    async fn hole(reply: fn(&str) -> &'static str) -> (GopherDriver, Arc<Mutex<Vec<String>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        let proxy = crate::socks::stub::proxy(move |mut stream| {
+            let seen = Arc::clone(&seen);
+            async move {
+                let mut line = String::new();
+                if BufReader::new(&mut stream).read_line(&mut line).await.is_err() {
+                    return;
+                }
+                seen.lock().unwrap().push(line.clone());
+                let _ = stream.write_all(reply(line.trim_end_matches("\r\n")).as_bytes()).await;
+            }
+        })
+        .await;
+        (GopherDriver::new(&[proxy], 1, 0, 5, 5).unwrap(), requests)
+    }
+
+    fn url(path: &str) -> Url {
+        Url::parse(&format!("{HOLE}{path}")).unwrap()
+    }
+
+    #[test]
+    fn request_line_decodes_type_and_selector() {
+        assert_eq!(request_line(&url("")), ('1', "\r\n".to_string()));
+        assert_eq!(request_line(&url("/")), ('1', "\r\n".to_string()));
+        assert_eq!(request_line(&url("/1/phlog")), ('1', "/phlog\r\n".to_string()));
+        assert_eq!(request_line(&url("/0/notes/a%20b.txt")), ('0', "/notes/a b.txt\r\n".to_string()));
+        assert_eq!(request_line(&url("/1/files/50%25")), ('1', "/files/50%\r\n".to_string()));
+        // Selectors without a leading slash are sent as they are
+        assert_eq!(request_line(&url("/0about")), ('0', "about\r\n".to_string()));
+        assert_eq!(request_line(&url("/7/search?dark%20web")), ('7', "/search\tdark web\r\n".to_string()));
+    }
+
+    #[test]
+    fn item_types_map_to_content_types() {
+        assert_eq!(content_type('1'), GOPHER_MENU);
+        assert_eq!(content_type('7'), GOPHER_MENU);
+        assert_eq!(content_type('0'), "text/plain");
+        assert_eq!(content_type('h'), "text/html");
+        assert_eq!(content_type('9'), "application/octet-stream");
+    }
+
+    #[test]
+    fn terminator_line_is_stripped() {
+        let strip = |body: &[u8]| {
+            let mut body = body.to_vec();
+            strip_terminator(&mut body);
+            body
+        };
+        assert_eq!(strip(b"iHello\t\t\t\r\n.\r\n"), b"iHello\t\t\t\r\n");
+        assert_eq!(strip(b".\r\n"), b"");
+        // A dot ending a line of text is content
+        assert_eq!(strip(b"The end.\r\n"), b"The end.\r\n");
+        assert_eq!(strip(b"no terminator"), b"no terminator");
+    }
+
+    #[tokio::test]
+    async fn fetches_a_menu() {
+        let (driver, requests) = hole(|_| "iWelcome\t\tnull\t0\r\n0About\t/about.txt\there\t70\r\n.\r\n").await;
+        let resp = driver.fetch(&url("/1/dir"), &FetchConfig::default(), 0).await.unwrap();
+        assert_eq!(requests.lock().unwrap().as_slice(), ["/dir\r\n"]);
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.content_type.as_deref(), Some(GOPHER_MENU));
+        assert_eq!(resp.body, b"iWelcome\t\tnull\t0\r\n0About\t/about.txt\there\t70\r\n");
+    }
+
+    #[tokio::test]
+    async fn error_menu_is_not_found() {
+        let (driver, _) = hole(|_| "3'/missing' does not exist\t\terror.host\t1\r\n.\r\n").await;
+        let resp = driver.fetch(&url("/1/missing"), &FetchConfig::default(), 0).await.unwrap();
+        assert_eq!(resp.status, 404);
+    }
+
+    #[tokio::test]
+    async fn binary_items_are_kept_whole() {
+        let (driver, _) = hole(|_| "GIF89a.\r\n.\r\n").await;
+        let resp = driver.fetch(&url("/g/logo.gif"), &FetchConfig::default(), 0).await.unwrap();
+        assert_eq!(resp.content_type.as_deref(), Some("image/gif"));
+        assert_eq!(resp.body, b"GIF89a.\r\n.\r\n");
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use darkscraper_core::{ProxyHealth, ProxyState};
+
+/// How long a single proxy probe may take.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Ready if the proxy's port accepts a TCP connection (SOCKS proxies, gateways
+/// that have no bootstrapping page).
+pub(crate) async fn tcp_probe(addr: &str) -> ProxyHealth {
+    let start = Instant::now();
+    let result = tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(addr)).await;
+    let (state, detail) = match result {
+        Ok(Ok(_)) => (ProxyState::Ready, None),
+        Ok(Err(e)) => (ProxyState::Down, Some(e.to_string())),
+        Err(_) => (ProxyState::Down, Some("connect timeout".to_string())),
+    };
+    ProxyHealth {
+        proxy: addr.to_string(),
+        state,
+        detail,
+        latency_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+/// Fetch the proxy's own front page (directly, not through the proxy).
+/// Reachable but showing one of `not_ready_markers` = still bootstrapping.
+pub(crate) async fn http_probe(addr: &str, not_ready_markers: &[&str]) -> ProxyHealth {
+    let start = Instant::now();
+    let (state, detail) = match fetch_text(&format!("http://{}/", addr)).await {
+        Ok(text) => match not_ready_markers.iter().find(|m| text.contains(**m)) {
+            Some(marker) => (ProxyState::Bootstrapping, Some(format!("proxy page shows \"{}\"", marker))),
+            None => (ProxyState::Ready, None),
+        },
+        Err(e) => (ProxyState::Down, Some(e.to_string())),
+    };
+    ProxyHealth {
+        proxy: addr.to_string(),
+        state,
+        detail,
+        latency_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+async fn fetch_text(url: &str) -> Result<String, reqwest::Error> {
+    let client = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build()?;
+    client.get(url).send().await?.text().await
+}
@@ -6,7 +6,14 @@ use async_trait::async_trait;
 use tracing::{debug, warn};
 use url::Url;
 
-use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::http_probe;
+use crate::registry::BuiltinDriver;
+
+/// FProxy front-page text shown until the node is set up.
+const NOT_READY_MARKERS: &[&str] = &["Set Up Freenet", "First Time Wizard"];
 
 /// Hyphanet (formerly Freenet) driver.
 ///
@@ -22,6 +29,7 @@ use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver};
 /// The driver rewrites these into FProxy gateway URLs:
 ///   http://hyphanet1:8888/USK@<key>/<name>/<version>/
 pub struct HyphanetDriver {
+    name: String,
     clients: Vec<reqwest::Client>,
     proxy_bases: Vec<String>,
     counter: AtomicUsize,
@@ -83,6 +91,7 @@ impl HyphanetDriver {
         }
 
         Ok(Self {
+            name: "hyphanet".to_string(),
             clients,
             proxy_bases,
             counter: AtomicUsize::new(rand::random::<usize>()),
@@ -111,14 +120,42 @@ impl HyphanetDriver {
     }
 }
 
+impl BuiltinDriver for HyphanetDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
 #[async_trait]
 impl NetworkDriver for HyphanetDriver {
     fn name(&self) -> &str {
-        "hyphanet"
+        &self.name
     }
 
     fn can_handle(&self, url: &Url) -> bool {
-        url.scheme() == "freenet" || url.scheme() == "hyphanet"
+        // Keys are opaque (hyphanet:USK@...); hyphanet://host/ URLs come from relative
+        // paths resolved against a key and are bogus
+        (url.scheme() == "freenet" || url.scheme() == "hyphanet") && !url.has_host()
+    }
+
+    /// FProxy serves its setup wizard until the node has been configured and has peers
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_bases.len());
+        for base in &self.proxy_bases {
+            health.push(http_probe(base.trim_start_matches("http://"), NOT_READY_MARKERS).await);
+        }
+        health
     }
 
     async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
@@ -138,10 +175,15 @@ impl NetworkDriver for HyphanetDriver {
 
         debug!(url = %url, proxy_url = %proxy_url, timeout_secs, retry_count, "fetching via hyphanet");
 
-        let resp = client
+        // Conditional re-fetch: a 304 saves pulling the whole freesite through the network again
+        let mut request = client
             .get(&proxy_url)
             .header("Accept", "text/html,application/xhtml+xml,*/*")
-            .timeout(timeout)
+            .timeout(timeout);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| {
@@ -205,7 +247,7 @@ impl NetworkDriver for HyphanetDriver {
             body: body.to_vec(),
             content_type,
             fetched_at: chrono::Utc::now(),
-            network: "hyphanet".to_string(),
+            network: self.name.clone(),
             response_time_ms: elapsed.as_millis() as u64,
             domain,
         })
@@ -7,10 +7,20 @@ use regex::Regex;
 use tracing::{debug, warn};
 use url::Url;
 
-use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::http_probe;
+use crate::registry::BuiltinDriver;
+
+/// Proxy front-page text shown while tunnels aren't built yet.
+const NOT_READY_MARKERS: &[&str] = &["Proxy error", "Can't create connection", "Host is down"];
 
 pub struct I2pDriver {
+    name: String,
     clients: Vec<reqwest::Client>,
+    /// HTTP proxy addresses, parallel to `clients`
+    proxy_addrs: Vec<String>,
     counter: AtomicUsize,
     max_concurrency: usize,
     min_delay: Duration,
@@ -75,7 +85,9 @@ impl I2pDriver {
         }
 
         Ok(Self {
+            name: "i2p".to_string(),
             clients,
+            proxy_addrs: proxy_addrs.to_vec(),
             counter: AtomicUsize::new(rand::random::<usize>()),
             max_concurrency,
             min_delay: Duration::from_secs(min_delay_seconds),
@@ -88,14 +100,50 @@ impl I2pDriver {
     }
 }
 
+impl BuiltinDriver for I2pDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
 #[async_trait]
 impl NetworkDriver for I2pDriver {
     fn name(&self) -> &str {
-        "i2p"
+        &self.name
     }
 
     fn can_handle(&self, url: &Url) -> bool {
-        url.host_str().map(|h| h.ends_with(".i2p")).unwrap_or(false)
+        matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|h| h.ends_with(".i2p"))
+    }
+
+    /// Human-readable .i2p names are aliases; the b32 address is the permanent one
+    fn alternate_addresses(&self, url: &Url, headers: &HashMap<String, String>, body: &str) -> Vec<String> {
+        let aliased = url.host_str().is_some_and(|h| h.ends_with(".i2p") && !h.ends_with(".b32.i2p"));
+        if !aliased {
+            return Vec::new();
+        }
+        Self::extract_base32_address(headers, body, url).into_iter().collect()
+    }
+
+    /// The HTTP proxy answers long before tunnels are built; until then its
+    /// front page is an error page
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_addrs.len());
+        for addr in &self.proxy_addrs {
+            health.push(http_probe(addr, NOT_READY_MARKERS).await);
+        }
+        health
     }
 
     async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
@@ -113,8 +161,11 @@ impl NetworkDriver for I2pDriver {
 
         debug!(url = %url, timeout_secs, retry_count, "fetching via i2p");
 
-        let resp = client.get(url.as_str())
-            .timeout(timeout)
+        let mut request = client.get(url.as_str()).timeout(timeout);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| {
@@ -157,7 +208,7 @@ impl NetworkDriver for I2pDriver {
             body: body.to_vec(),
             content_type,
             fetched_at: chrono::Utc::now(),
-            network: "i2p".to_string(),
+            network: self.name.clone(),
             response_time_ms: elapsed.as_millis() as u64,
             domain,
         })
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use tracing::{debug, warn};
+use url::Url;
+
+use darkscraper_core::ipfs::{canonical_url, root_cid, target_cid};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
+
+/// Characters escaped in a directory entry name used as a path segment
+const SEGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'/').add(b'<').add(b'>').add(b'?');
+
+/// UnixFS `Data` of a plain directory node: protobuf field 1 (Type) = Directory
+const UNIXFS_DIRECTORY: [u8; 2] = [0x08, 0x01];
+
+/// IPFS content through local HTTP gateways (kubo's `Gateway` port).
+/// ipfs://<cid>/path is fetched as http://gateway/ipfs/<cid>/path, ipns://<name>/path
+/// as /ipns/<name>/path. CIDs are immutable: a stored ipfs:// page is never fetched
+/// again. IPNS names are mutable; every fetch reports the immutable ipfs:// snapshot
+/// the name resolved to as an alternate address (the crawler keeps the history).
+pub struct IpfsDriver {
+    name: String,
+    clients: Vec<reqwest::Client>,
+    gateway_bases: Vec<String>,
+    counter: AtomicUsize,
+    max_concurrency: usize,
+    min_delay: Duration,
+}
+
+impl IpfsDriver {
+    pub fn new(
+        gateway_addrs: &[String],
+        max_concurrency: usize,
+        min_delay_seconds: u64,
+        connect_timeout_seconds: u64,
+        request_timeout_seconds: u64,
+    ) -> Result<Self, CrawlError> {
+        if gateway_addrs.is_empty() {
+            return Err(CrawlError::Proxy("no ipfs gateways configured".into()));
+        }
+
+        let mut clients = Vec::with_capacity(gateway_addrs.len());
+        let mut gateway_bases = Vec::with_capacity(gateway_addrs.len());
+        for addr in gateway_addrs {
+            let client = reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(connect_timeout_seconds))
+                .timeout(Duration::from_secs(request_timeout_seconds))
+                .pool_max_idle_per_host(4)
+                // Directories without a trailing slash redirect to the slashed path
+                .redirect(reqwest::redirect::Policy::limited(10))
+                .build()
+                .map_err(|e| CrawlError::Network(e.to_string()))?;
+
+            clients.push(client);
+            gateway_bases.push(format!("http://{}", addr));
+        }
+
+        Ok(Self {
+            name: "ipfs".to_string(),
+            clients,
+            gateway_bases,
+            counter: AtomicUsize::new(rand::random::<usize>()),
+            max_concurrency,
+            min_delay: Duration::from_secs(min_delay_seconds),
+        })
+    }
+
+    fn next_index(&self) -> usize {
+        self.counter.fetch_add(1, Ordering::Relaxed) % self.clients.len()
+    }
+
+    /// Convert an ipfs:// or ipns:// URL to a gateway path.
+    /// e.g. ipfs://bafy.../docs/a.txt → http://ipfs1:8080/ipfs/bafy.../docs/a.txt
+    fn to_gateway_url(&self, url: &Url, idx: usize) -> String {
+        let name = url.host_str().unwrap_or("");
+        let query = url.query().map(|q| format!("?{}", q)).unwrap_or_default();
+        format!("{}/{}/{}{}{}", self.gateway_bases[idx], url.scheme(), name, url.path(), query)
+    }
+
+    /// Render a UnixFS directory as a page of links, from the gateway's dag-json
+    /// view of the node. None for HAMT-sharded directories (entry names in the
+    /// node are prefixed with shard indexes) or if the gateway can't convert it;
+    /// the gateway's own listing is kept then.
+    async fn directory_page(&self, idx: usize, cid: &str, dir_url: &Url) -> Option<Vec<u8>> {
+        let dag_url = format!("{}/ipfs/{}?format=dag-json", self.gateway_bases[idx], cid);
+        let resp = self.clients[idx].get(&dag_url).send().await.ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        let node: serde_json::Value = serde_json::from_slice(&resp.bytes().await.ok()?).ok()?;
+        let data = node["Data"]["/"]["bytes"].as_str()?;
+        if !STANDARD_NO_PAD.decode(data).ok()?.starts_with(&UNIXFS_DIRECTORY) {
+            return None;
+        }
+
+        let mut base = dir_url.clone();
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        let title = html_escape(&format!("Index of {}{}", dir_url.host_str().unwrap_or(""), base.path()));
+        let mut page = format!(
+            "<!DOCTYPE html>\n<html><head><title>{}</title></head><body><h1>{}</h1><ul>\n",
+            title, title
+        );
+        for link in node["Links"].as_array()? {
+            let Some(name) = link["Name"].as_str().filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            let Ok(target) = base.join(&utf8_percent_encode(name, SEGMENT).to_string()) else {
+                continue;
+            };
+            let size = link["Tsize"].as_u64().unwrap_or(0);
+            page.push_str(&format!(
+                "<li><a href=\"{}\">{}</a> ({} bytes)</li>\n",
+                html_escape(target.as_str()),
+                html_escape(name),
+                size
+            ));
+        }
+        page.push_str("</ul></body></html>\n");
+        Some(page.into_bytes())
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl BuiltinDriver for IpfsDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl NetworkDriver for IpfsDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn can_handle(&self, url: &Url) -> bool {
+        matches!(url.scheme(), "ipfs" | "ipns") && url.has_host()
+    }
+
+    fn is_immutable(&self, url: &Url) -> bool {
+        url.scheme() == "ipfs"
+    }
+
+    /// The immutable snapshot an IPNS name currently points to
+    fn alternate_addresses(&self, url: &Url, headers: &HashMap<String, String>, _body: &str) -> Vec<String> {
+        if url.scheme() != "ipns" {
+            return Vec::new();
+        }
+        headers
+            .get("x-ipfs-roots")
+            .and_then(|roots| root_cid(roots))
+            .map(|root| format!("ipfs://{}{}", root, url.path()))
+            .into_iter()
+            .collect()
+    }
+
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.gateway_bases.len());
+        for base in &self.gateway_bases {
+            health.push(tcp_probe(base.trim_start_matches("http://")).await);
+        }
+        health
+    }
+
+    async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
+        let start = Instant::now();
+        let idx = self.next_index();
+        let gateway_url = self.to_gateway_url(url, idx);
+        debug!(url = %url, gateway_url = %gateway_url, retry_count, "fetching via ipfs gateway");
+
+        let mut request = self.clients[idx]
+            .get(&gateway_url)
+            .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8")
+            .header("User-Agent", &config.user_agent);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| {
+                warn!(url = %url, gateway_url = %gateway_url, error = %e, "ipfs fetch failed");
+                CrawlError::Network(e.to_string())
+            })?;
+
+        let status = resp.status().as_u16();
+        // Map the gateway path (after directory redirects) back to ipfs:// / ipns://
+        let final_url = canonical_url(resp.url()).unwrap_or_else(|| url.clone());
+
+        let mut headers = HashMap::new();
+        for (k, v) in resp.headers() {
+            if let Ok(val) = v.to_str() {
+                headers.insert(k.as_str().to_string(), val.to_string());
+            }
+        }
+        let mut content_type = headers.get("content-type").cloned();
+
+        let mut body = resp
+            .bytes()
+            .await
+            .map_err(|e| CrawlError::Network(e.to_string()))?
+            .to_vec();
+        if body.len() > config.max_body_size {
+            return Err(CrawlError::BodyTooLarge {
+                size: body.len(),
+                max: config.max_body_size,
+            });
+        }
+
+        // Gateways tag their generated directory listings with a DirIndex ETag
+        let is_directory = headers.get("etag").is_some_and(|etag| etag.contains("DirIndex-"));
+        if status == 200 && is_directory {
+            if let Some(cid) = headers.get("x-ipfs-roots").and_then(|roots| target_cid(roots)) {
+                if let Some(page) = self.directory_page(idx, &cid, &final_url).await {
+                    body = page;
+                    content_type = Some("text/html; charset=utf-8".to_string());
+                }
+            }
+        }
+
+        let domain = url.host_str().unwrap_or("unknown").to_string();
+        Ok(FetchResponse {
+            url: url.clone(),
+            final_url,
+            status,
+            headers,
+            body,
+            content_type,
+            fetched_at: chrono::Utc::now(),
+            network: self.name.clone(),
+            response_time_ms: start.elapsed().as_millis() as u64,
+            domain,
+        })
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    fn default_delay(&self) -> Duration {
+        self.min_delay
+    }
+
+    fn max_pages_per_domain(&self) -> usize {
+        // A CID is one content tree; leak dumps and mirrors hold thousands of files
+        1000
+    }
+
+    fn classify_error(&self, error: &str) -> &'static str {
+        let error_lower = error.to_lowercase();
+        // Malformed CIDs and paths never resolve
+        if error_lower.contains("invalid cid") || error_lower.contains("invalid path") || error_lower.contains("404") {
+            return "dead";
+        }
+        // Content with no reachable providers may be reprovided later
+        "unreachable"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const ROOT: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    /// CIDv0 form of ROOT, as gateways report it in X-Ipfs-Roots
+    const ROOT_V0: &str = "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR";
+    const DOCS: &str = "bafybeicgwqvuekonpi44kzhhqc5wmwul3zh564raa7ueopywp7st5vfzsu";
+
+    type Reply = (u16, Vec<(&'static str, String)>, String);
+
+    /// A gateway answering each request target (path and query) with `reply`.
+    /// Returns the driver and the request targets received.
+    async fn gateway(reply: fn(&str) -> Reply) -> (IpfsDriver, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let seen = Arc::clone(&seen);
+                tokio::spawn(async move {
+                    let mut head = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !head.ends_with(b"\r\n\r\n") {
+                        match stream.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => head.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&head);
+                    let target = head.split(' ').nth(1).unwrap_or("").to_string();
+                    seen.lock().unwrap().push(target.clone());
+                    let (status, headers, body) = reply(&target);
+                    let mut response = format!("HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+                    for (name, value) in headers {
+                        response.push_str(&format!("{}: {}\r\n", name, value));
+                    }
+                    response.push_str("\r\n");
+                    response.push_str(&body);
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (IpfsDriver::new(&[addr], 1, 0, 5, 5).unwrap(), requests)
+    }
+
+    /// A directory at ipfs://ROOT/docs, which the gateway lists with a DirIndex ETag.
+    /// `data` is the UnixFS Data of the node in its dag-json view.
+    fn directory(target: &str, data: &str) -> Reply {
+        let docs = format!("/ipfs/{}/docs", ROOT);
+        match target {
+            t if t == docs => (301, vec![("Location", format!("{}/", docs))], String::new()),
+            t if t == format!("{}/", docs) => (
+                200,
+                vec![
+                    ("Content-Type", "text/html".to_string()),
+                    ("Etag", "\"DirIndex-2b567f6r5vvdg_CID-x\"".to_string()),
+                    ("X-Ipfs-Roots", format!("{},{}", ROOT_V0, DOCS)),
+                ],
+                "gateway listing".to_string(),
+            ),
+            t if t == format!("/ipfs/{}?format=dag-json", DOCS) => {
+                let node = serde_json::json!({
+                    "Data": {"/": {"bytes": data}},
+                    "Links": [
+                        {"Hash": {"/": ROOT}, "Name": "a b.txt", "Tsize": 12},
+                        {"Hash": {"/": ROOT}, "Name": "", "Tsize": 1},
+                        {"Hash": {"/": ROOT}, "Name": "<sub>", "Tsize": 0},
+                    ],
+                });
+                (200, vec![("Content-Type", "application/vnd.ipld.dag-json".to_string())], node.to_string())
+            }
+            _ => (404, Vec::new(), "not found".to_string()),
+        }
+    }
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[tokio::test]
+    async fn urls_map_to_gateway_paths() {
+        let driver = IpfsDriver::new(&["ipfs1:8080".to_string()], 1, 0, 5, 5).unwrap();
+        assert_eq!(
+            driver.to_gateway_url(&url(&format!("ipfs://{}/docs/a.txt?x=1#top", ROOT)), 0),
+            format!("http://ipfs1:8080/ipfs/{}/docs/a.txt?x=1", ROOT)
+        );
+        assert_eq!(
+            driver.to_gateway_url(&url("ipns://docs.ipfs.tech"), 0),
+            "http://ipfs1:8080/ipns/docs.ipfs.tech"
+        );
+    }
+
+    #[tokio::test]
+    async fn ipns_snapshot_is_an_alternate_address() {
+        let driver = IpfsDriver::new(&["ipfs1:8080".to_string()], 1, 0, 5, 5).unwrap();
+        let headers = HashMap::from([("x-ipfs-roots".to_string(), format!("{}, {}", ROOT_V0, DOCS))]);
+        assert_eq!(
+            driver.alternate_addresses(&url("ipns://docs.ipfs.tech/blog/"), &headers, ""),
+            vec![format!("ipfs://{}/blog/", ROOT)]
+        );
+        // Content-addressed URLs are the snapshot already; names without roots reveal nothing
+        assert!(driver.alternate_addresses(&url(&format!("ipfs://{}/", ROOT)), &headers, "").is_empty());
+        assert!(driver.alternate_addresses(&url("ipns://docs.ipfs.tech/"), &HashMap::new(), "").is_empty());
+    }
+
+    #[tokio::test]
+    async fn directory_listing_is_rendered_from_dag_json() {
+        let (driver, requests) = gateway(|target| directory(target, "CAE")).await;
+        let resp = driver
+            .fetch(&url(&format!("ipfs://{}/docs", ROOT)), &FetchConfig::default(), 0)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.final_url.as_str(), format!("ipfs://{}/docs/", ROOT));
+        assert_eq!(resp.content_type.as_deref(), Some("text/html; charset=utf-8"));
+        let body = String::from_utf8(resp.body).unwrap();
+        assert!(body.contains(&format!("<title>Index of {}/docs/</title>", ROOT)), "{}", body);
+        assert!(
+            body.contains(&format!("<li><a href=\"ipfs://{}/docs/a%20b.txt\">a b.txt</a> (12 bytes)</li>", ROOT)),
+            "{}",
+            body
+        );
+        assert!(
+            body.contains(&format!("<li><a href=\"ipfs://{}/docs/%3Csub%3E\">&lt;sub&gt;</a> (0 bytes)</li>", ROOT)),
+            "{}",
+            body
+        );
+        // Unnamed links aren't entries
+        assert_eq!(body.matches("<li>").count(), 2);
+        // The node is looked up by the CID the path resolved to
+        assert_eq!(requests.lock().unwrap().last().unwrap(), &format!("/ipfs/{}?format=dag-json", DOCS));
+    }
+
+    #[tokio::test]
+    async fn sharded_directories_keep_the_gateway_listing() {
+        // UnixFS Type 5: HAMTShard
+        let (driver, _) = gateway(|target| directory(target, "CAU")).await;
+        let resp = driver
+            .fetch(&url(&format!("ipfs://{}/docs", ROOT)), &FetchConfig::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(resp.body, b"gateway listing");
+        assert_eq!(resp.content_type.as_deref(), Some("text/html"));
+    }
+
+    #[tokio::test]
+    async fn ipns_fetches_resolve_through_the_gateway() {
+        let (driver, requests) = gateway(|target| match target {
+            "/ipns/docs.ipfs.tech/blog/" => (
+                200,
+                vec![("X-Ipfs-Roots", format!("{},{}", ROOT_V0, DOCS))],
+                "<html>blog</html>".to_string(),
+            ),
+            _ => (404, Vec::new(), String::new()),
+        })
+        .await;
+        let blog = url("ipns://docs.ipfs.tech/blog/");
+        let resp = driver.fetch(&blog, &FetchConfig::default(), 0).await.unwrap();
+
+        assert_eq!(resp.final_url, blog);
+        assert_eq!(resp.domain, "docs.ipfs.tech");
+        assert_eq!(
+            driver.alternate_addresses(&blog, &resp.headers, ""),
+            vec![format!("ipfs://{}/blog/", ROOT)]
+        );
+        assert_eq!(*requests.lock().unwrap(), vec!["/ipns/docs.ipfs.tech/blog/".to_string()]);
+        assert!(!driver.is_immutable(&blog));
+        assert!(driver.is_immutable(&url(&format!("ipfs://{}/", ROOT))));
+    }
+}
@@ -1,11 +1,23 @@
+pub mod gemini;
+pub mod gopher;
+mod health;
 pub mod hyphanet;
 pub mod i2p;
+pub mod ipfs;
 pub mod lokinet;
+pub mod registry;
+mod socks;
 pub mod tor;
+pub mod yggdrasil;
 pub mod zeronet;
 
+pub use self::gemini::GeminiDriver;
+pub use self::gopher::GopherDriver;
 pub use self::hyphanet::HyphanetDriver;
 pub use self::i2p::I2pDriver;
+pub use self::ipfs::IpfsDriver;
 pub use self::lokinet::LokinetDriver;
-pub use self::tor::TorDriver;
+pub use self::registry::{route, BuiltinDriver, DriverFactory, DriverRegistry};
+pub use self::tor::{is_v3_onion, TorDriver};
+pub use self::yggdrasil::YggdrasilDriver;
 pub use self::zeronet::ZeronetDriver;
@@ -6,14 +6,21 @@ use async_trait::async_trait;
 use tracing::{debug, warn};
 use url::Url;
 
-use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
 
 /// Lokinet driver for .loki sites (SNApps).
 ///
 /// Uses a SOCKS5 proxy (e.g. lokinet-socks image exposing port 1080)
 /// to route traffic through the Lokinet overlay network.
 pub struct LokinetDriver {
+    name: String,
     clients: Vec<reqwest::Client>,
+    /// SOCKS addresses, parallel to `clients`
+    proxy_addrs: Vec<String>,
     counter: AtomicUsize,
     max_concurrency: usize,
     min_delay: Duration,
@@ -52,7 +59,9 @@ impl LokinetDriver {
         }
 
         Ok(Self {
+            name: "lokinet".to_string(),
             clients,
+            proxy_addrs: socks_addrs.to_vec(),
             counter: AtomicUsize::new(rand::random::<usize>()),
             max_concurrency,
             min_delay: Duration::from_secs(min_delay_seconds),
@@ -65,16 +74,39 @@ impl LokinetDriver {
     }
 }
 
+impl BuiltinDriver for LokinetDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
 #[async_trait]
 impl NetworkDriver for LokinetDriver {
     fn name(&self) -> &str {
-        "lokinet"
+        &self.name
     }
 
     fn can_handle(&self, url: &Url) -> bool {
-        url.host_str()
-            .map(|h| h.ends_with(".loki"))
-            .unwrap_or(false)
+        matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|h| h.ends_with(".loki"))
+    }
+
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_addrs.len());
+        for addr in &self.proxy_addrs {
+            health.push(tcp_probe(addr).await);
+        }
+        health
     }
 
     async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
@@ -87,8 +119,11 @@ impl NetworkDriver for LokinetDriver {
 
         debug!(url = %url, timeout_secs, retry_count, "fetching via lokinet");
 
-        let resp = client.get(url.as_str())
-            .timeout(timeout)
+        let mut request = client.get(url.as_str()).timeout(timeout);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| {
@@ -131,7 +166,7 @@ impl NetworkDriver for LokinetDriver {
             body: body.to_vec(),
             content_type,
             fetched_at: chrono::Utc::now(),
-            network: "lokinet".to_string(),
+            network: self.name.clone(),
             response_time_ms: elapsed.as_millis() as u64,
             domain,
         })
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use tracing::{error, info};
+use url::Url;
+
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{AppConfig, CrawlError, NetworkDriver};
+
+use crate::{
+    GeminiDriver, GopherDriver, HyphanetDriver, I2pDriver, IpfsDriver, LokinetDriver, TorDriver, YggdrasilDriver,
+    ZeronetDriver,
+};
+
+/// Builds drivers of one `kind` from their `[networks.<name>]` section.
+pub trait DriverFactory: Send + Sync {
+    fn kind(&self) -> &str;
+    /// `name` is the table name; the driver must report it from `NetworkDriver::name`.
+    fn build(&self, name: &str, config: &NetworkConfig) -> Result<Box<dyn NetworkDriver>, CrawlError>;
+}
+
+/// A built-in driver, constructed from its network section.
+pub trait BuiltinDriver: NetworkDriver + Sized {
+    fn from_config(config: &NetworkConfig) -> Result<Self, CrawlError>;
+    /// Report as `name` (the network's table name) instead of the kind.
+    fn with_name(self, name: &str) -> Self;
+}
+
+/// (network name, section) → driver
+type BuildFn = fn(&str, &NetworkConfig) -> Result<Box<dyn NetworkDriver>, CrawlError>;
+
+/// Factory for the built-in drivers: a kind and a constructor.
+struct BuiltinFactory {
+    kind: &'static str,
+    build: BuildFn,
+}
+
+impl DriverFactory for BuiltinFactory {
+    fn kind(&self) -> &str {
+        self.kind
+    }
+
+    fn build(&self, name: &str, config: &NetworkConfig) -> Result<Box<dyn NetworkDriver>, CrawlError> {
+        (self.build)(name, config)
+    }
+}
+
+fn build_builtin<D: BuiltinDriver>(name: &str, config: &NetworkConfig) -> Result<Box<dyn NetworkDriver>, CrawlError> {
+    Ok(Box::new(D::from_config(config)?.with_name(name)))
+}
+
+/// Driver factories by kind. `Default` registers every built-in driver.
+pub struct DriverRegistry {
+    factories: BTreeMap<String, Box<dyn DriverFactory>>,
+}
+
+impl Default for DriverRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register_builtin::<TorDriver>("tor");
+        registry.register_builtin::<I2pDriver>("i2p");
+        registry.register_builtin::<ZeronetDriver>("zeronet");
+        registry.register_builtin::<HyphanetDriver>("hyphanet");
+        registry.register_builtin::<LokinetDriver>("lokinet");
+        registry.register_builtin::<GeminiDriver>("gemini");
+        registry.register_builtin::<GopherDriver>("gopher");
+        registry.register_builtin::<IpfsDriver>("ipfs");
+        registry.register_builtin::<YggdrasilDriver>("yggdrasil");
+        registry
+    }
+}
+
+impl DriverRegistry {
+    /// A registry with no factories.
+    pub fn empty() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// Add a factory, replacing any registered for the same kind.
+    pub fn register(&mut self, factory: impl DriverFactory + 'static) {
+        self.factories.insert(factory.kind().to_string(), Box::new(factory));
+    }
+
+    /// Register a built-in driver type under `kind`.
+    pub fn register_builtin<D: BuiltinDriver>(&mut self, kind: &'static str) {
+        self.register(BuiltinFactory {
+            kind,
+            build: build_builtin::<D>,
+        });
+    }
+
+    pub fn kinds(&self) -> Vec<&str> {
+        self.factories.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Build the driver for one network section.
+    pub fn build(&self, name: &str, config: &NetworkConfig) -> Result<Box<dyn NetworkDriver>, CrawlError> {
+        let factory = self.factories.get(&config.kind).ok_or_else(|| {
+            CrawlError::Config(format!(
+                "network {}: unknown kind \"{}\" (known: {})",
+                name,
+                config.kind,
+                self.kinds().join(", ")
+            ))
+        })?;
+        factory.build(name, config)
+    }
+
+    /// Build a driver for every enabled network.
+    /// Networks whose driver fails to build are logged and left out.
+    pub fn build_enabled(&self, config: &AppConfig) -> Vec<Box<dyn NetworkDriver>> {
+        let mut drivers = Vec::new();
+        for (name, network) in config.enabled_networks() {
+            match self.build(name, network) {
+                Ok(driver) => {
+                    info!(network = name, kind = %network.kind, proxies = ?network.proxies, max_concurrency = network.max_concurrency, "driver ready");
+                    drivers.push(driver);
+                }
+                Err(e) => error!(network = name, "failed to create driver: {}", e),
+            }
+        }
+        drivers
+    }
+}
+
+/// The driver that handles `url`: the first whose `can_handle` accepts it. Built-in
+/// drivers claim disjoint URLs (by scheme as well as host), so the result doesn't
+/// depend on the order networks are configured or named in.
+pub fn route<'a>(drivers: &'a [Box<dyn NetworkDriver>], url: &Url) -> Option<&'a dyn NetworkDriver> {
+    drivers.iter().find(|d| d.can_handle(url)).map(|d| d.as_ref())
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+
+use darkscraper_core::CrawlError;
+
+/// Open a TCP stream to `host:port` through a SOCKS5 proxy. The proxy resolves
+/// the host name (required for .onion).
+pub(crate) async fn connect(
+    proxy: &str,
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<Socks5Stream<TcpStream>, CrawlError> {
+    match tokio::time::timeout(timeout, Socks5Stream::connect(proxy, (host, port))).await {
+        Ok(Ok(stream)) => Ok(stream),
+        Ok(Err(e)) => Err(CrawlError::Network(format!("socks connect to {}:{} via {}: {}", host, port, proxy, e))),
+        Err(_) => Err(CrawlError::Timeout(timeout.as_secs())),
+    }
+}
+
+/// Read until the server closes the stream. More than `max` bytes is an error.
+/// A TLS close without close_notify counts as a normal end (many servers do it).
+pub(crate) async fn read_limited<R: AsyncRead + Unpin>(reader: R, max: usize) -> Result<Vec<u8>, CrawlError> {
+    let mut body = Vec::new();
+    match reader.take(max as u64 + 1).read_to_end(&mut body).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {}
+        Err(e) => return Err(CrawlError::Network(e.to_string())),
+    }
+    if body.len() > max {
+        return Err(CrawlError::BodyTooLarge { size: body.len(), max });
+    }
+    Ok(body)
+}
+
+/// A SOCKS5 proxy for tests that answers every CONNECT itself: instead of being
+/// forwarded, the connection is handed to `serve` as if it reached the target.
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::future::Future;
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Start the proxy on a free local port; returns its address.
+    pub(crate) async fn proxy<F, Fut>(serve: F) -> String
+    where
+        F: Fn(TcpStream) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let serve = Arc::new(serve);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let serve = Arc::clone(&serve);
+                tokio::spawn(async move {
+                    if handshake(&mut stream).await.is_ok() {
+                        serve(stream).await;
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    /// No-auth greeting and a CONNECT request, answered with success.
+    async fn handshake(stream: &mut TcpStream) -> std::io::Result<()> {
+        // Version, number of auth methods, methods
+        let mut greeting = [0u8; 2];
+        stream.read_exact(&mut greeting).await?;
+        let mut methods = vec![0u8; greeting[1] as usize];
+        stream.read_exact(&mut methods).await?;
+        stream.write_all(&[5, 0]).await?;
+
+        // Version, CONNECT, reserved, address type; then address and port
+        let mut request = [0u8; 4];
+        stream.read_exact(&mut request).await?;
+        let addr_len = match request[3] {
+            1 => 4,
+            4 => 16,
+            3 => stream.read_u8().await? as usize,
+            _ => return Err(std::io::ErrorKind::InvalidData.into()),
+        };
+        let mut target = vec![0u8; addr_len + 2];
+        stream.read_exact(&mut target).await?;
+        stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await
+    }
+}
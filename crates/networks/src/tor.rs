@@ -7,10 +7,17 @@ use rquest_util::Emulation;
 use tracing::{debug, warn};
 use url::Url;
 
-use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
 
 pub struct TorDriver {
+    name: String,
     clients: Vec<rquest::Client>,
+    /// SOCKS addresses, parallel to `clients`
+    proxy_addrs: Vec<String>,
     counter: AtomicUsize,
     max_concurrency: usize,
     min_delay: Duration,
@@ -67,7 +74,9 @@ impl TorDriver {
         eprintln!("✅ Tor: Using {} of {} configured proxies", clients.len(), socks_addrs.len());
 
         Ok(Self {
+            name: "tor".to_string(),
             clients,
+            proxy_addrs: working_addrs,
             counter: AtomicUsize::new(rand::random::<usize>()),
             max_concurrency,
             min_delay: Duration::from_secs(min_delay_seconds),
@@ -80,16 +89,42 @@ impl TorDriver {
     }
 }
 
+impl BuiltinDriver for TorDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
 #[async_trait]
 impl NetworkDriver for TorDriver {
     fn name(&self) -> &str {
-        "tor"
+        &self.name
     }
 
     fn can_handle(&self, url: &Url) -> bool {
-        url.host_str()
-            .map(|h| h.ends_with(".onion"))
-            .unwrap_or(false)
+        // v2 onion addresses are dead; only route v3. Other schemes on onion hosts
+        // (gemini, gopher, ...) belong to their own drivers or none.
+        matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(is_v3_onion)
+    }
+
+    /// SOCKS port open = ready (tor only opens it once bootstrapped enough to build circuits)
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_addrs.len());
+        for addr in &self.proxy_addrs {
+            health.push(tcp_probe(addr).await);
+        }
+        health
     }
 
     async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
@@ -107,8 +142,11 @@ impl NetworkDriver for TorDriver {
 
         debug!(url = %url, timeout_secs, retry_count, "fetching via tor");
 
-        let resp = client.get(url.as_str())
-            .timeout(timeout)
+        let mut request = client.get(url.as_str()).timeout(timeout);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| {
@@ -151,7 +189,7 @@ impl NetworkDriver for TorDriver {
             body: body.to_vec(),
             content_type,
             fetched_at: chrono::Utc::now(),
-            network: "tor".to_string(),
+            network: self.name.clone(),
             response_time_ms: elapsed.as_millis() as u64,
             domain,
         })
@@ -200,3 +238,14 @@ impl NetworkDriver for TorDriver {
         "dead"
     }
 }
+
+/// Check if an .onion host is a valid v3 address (56 base32 chars).
+pub fn is_v3_onion(host: &str) -> bool {
+    let Some(name) = host.strip_suffix(".onion") else {
+        return false;
+    };
+    name.len() == 56
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c))
+}
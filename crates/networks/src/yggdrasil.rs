@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tracing::{debug, warn};
+use url::Url;
+
+use darkscraper_core::ip_range::{host_ip, IpRange, YGGDRASIL, YGGDRASIL_SUFFIX};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Yggdrasil and similar IPv6 mesh networks. Routes by address range rather than
+/// by host suffix: http://[200:...]/ literals inside the configured ranges (plus
+/// names with the configured suffixes, e.g. Alfis `.ygg` names).
+///
+/// With SOCKS proxies configured (e.g. yggstack) traffic goes through them; without,
+/// requests connect directly and the host's mesh interface routes them.
+pub struct YggdrasilDriver {
+    name: String,
+    clients: Vec<reqwest::Client>,
+    /// SOCKS addresses, parallel to `clients`; empty for direct connections
+    proxy_addrs: Vec<String>,
+    routes: Arc<MeshRoutes>,
+    counter: AtomicUsize,
+    max_concurrency: usize,
+    min_delay: Duration,
+}
+
+/// Address ranges and host suffixes a mesh driver handles.
+struct MeshRoutes {
+    ranges: Vec<IpRange>,
+    suffixes: Vec<String>,
+}
+
+impl MeshRoutes {
+    fn contains(&self, url: &Url) -> bool {
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        match host_ip(url) {
+            Some(ip) => self.ranges.iter().any(|range| range.contains(ip)),
+            None => url
+                .host_str()
+                .is_some_and(|host| self.suffixes.iter().any(|suffix| host.ends_with(suffix.as_str()))),
+        }
+    }
+}
+
+impl YggdrasilDriver {
+    /// `ranges` / `suffixes` replace Yggdrasil's 200::/7 and `.ygg` (e.g. fc00::/8 for
+    /// cjdns); empty lists keep the defaults.
+    pub fn new(
+        socks_addrs: &[String],
+        ranges: &[IpRange],
+        suffixes: &[String],
+        max_concurrency: usize,
+        min_delay_seconds: u64,
+        connect_timeout_seconds: u64,
+        request_timeout_seconds: u64,
+    ) -> Result<Self, CrawlError> {
+        let routes = Arc::new(MeshRoutes {
+            ranges: if ranges.is_empty() { vec![YGGDRASIL] } else { ranges.to_vec() },
+            suffixes: if suffixes.is_empty() {
+                vec![YGGDRASIL_SUFFIX.to_string()]
+            } else {
+                suffixes.to_vec()
+            },
+        });
+        let builder = || {
+            // Only follow redirects that stay on the mesh: a redirect to a clearnet host
+            // would be fetched directly, leaking the crawler's address (and reaching
+            // internal services). Others come back as the 3xx response.
+            let mesh = Arc::clone(&routes);
+            let policy = reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if mesh.contains(attempt.url()) {
+                    attempt.follow()
+                } else {
+                    attempt.stop()
+                }
+            });
+            reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(connect_timeout_seconds))
+                .timeout(Duration::from_secs(request_timeout_seconds))
+                .pool_max_idle_per_host(4)
+                .redirect(policy)
+        };
+
+        let mut clients = Vec::with_capacity(socks_addrs.len().max(1));
+        for addr in socks_addrs {
+            let proxy = reqwest::Proxy::all(format!("socks5h://{}", addr))
+                .map_err(|e| CrawlError::Proxy(e.to_string()))?;
+            clients.push(builder().proxy(proxy).build().map_err(|e| CrawlError::Network(e.to_string()))?);
+        }
+        if clients.is_empty() {
+            clients.push(builder().no_proxy().build().map_err(|e| CrawlError::Network(e.to_string()))?);
+        }
+
+        Ok(Self {
+            name: "yggdrasil".to_string(),
+            clients,
+            proxy_addrs: socks_addrs.to_vec(),
+            routes,
+            counter: AtomicUsize::new(rand::random::<usize>()),
+            max_concurrency,
+            min_delay: Duration::from_secs(min_delay_seconds),
+        })
+    }
+
+    fn next_client(&self) -> &reqwest::Client {
+        let idx = self.counter.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[idx]
+    }
+}
+
+impl BuiltinDriver for YggdrasilDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            &c.ip_ranges,
+            &c.host_suffixes,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl NetworkDriver for YggdrasilDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn can_handle(&self, url: &Url) -> bool {
+        self.routes.contains(url)
+    }
+
+    /// Mesh addresses inside the configured ranges are derived from the node's key
+    fn is_key_address(&self, url: &Url) -> bool {
+        host_ip(url).is_some_and(|ip| self.routes.ranges.iter().any(|range| range.contains(ip)))
+    }
+
+    /// Direct connections have nothing to probe: the mesh interface is the host's
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_addrs.len());
+        for addr in &self.proxy_addrs {
+            health.push(tcp_probe(addr).await);
+        }
+        health
+    }
+
+    async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
+        let start = Instant::now();
+        let client = self.next_client();
+
+        // Progressive timeout: 10s base + 10s per retry
+        let timeout_secs = 10 + (retry_count * 10);
+        let timeout = Duration::from_secs(timeout_secs as u64);
+
+        debug!(url = %url, timeout_secs, retry_count, "fetching via yggdrasil");
+
+        let mut request = client
+            .get(url.as_str())
+            .header("User-Agent", &config.user_agent)
+            .timeout(timeout);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| {
+                warn!(url = %url, error = %e, timeout_secs, "yggdrasil fetch failed");
+                CrawlError::Network(e.to_string())
+            })?;
+
+        let status = resp.status().as_u16();
+        let final_url = Url::parse(resp.url().as_str()).unwrap_or_else(|_| url.clone());
+
+        let mut headers = HashMap::new();
+        for (k, v) in resp.headers() {
+            if let Ok(val) = v.to_str() {
+                headers.insert(k.as_str().to_string(), val.to_string());
+            }
+        }
+
+        let content_type = headers.get("content-type").cloned();
+
+        let body = resp
+            .bytes()
+            .await
+            .map_err(|e| CrawlError::Network(e.to_string()))?;
+
+        if body.len() > config.max_body_size {
+            return Err(CrawlError::BodyTooLarge {
+                size: body.len(),
+                max: config.max_body_size,
+            });
+        }
+
+        let domain = url.host_str().unwrap_or("unknown").to_string();
+
+        Ok(FetchResponse {
+            url: url.clone(),
+            final_url,
+            status,
+            headers,
+            body: body.to_vec(),
+            content_type,
+            fetched_at: chrono::Utc::now(),
+            network: self.name.clone(),
+            response_time_ms: start.elapsed().as_millis() as u64,
+            domain,
+        })
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    fn default_delay(&self) -> Duration {
+        self.min_delay
+    }
+}
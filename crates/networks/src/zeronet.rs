@@ -6,9 +6,14 @@ use async_trait::async_trait;
 use tracing::{debug, warn};
 use url::Url;
 
-use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver};
+use darkscraper_core::config::NetworkConfig;
+use darkscraper_core::{CrawlError, FetchConfig, FetchResponse, NetworkDriver, ProxyHealth};
+
+use crate::health::tcp_probe;
+use crate::registry::BuiltinDriver;
 
 pub struct ZeronetDriver {
+    name: String,
     clients: Vec<reqwest::Client>,
     proxy_bases: Vec<String>,
     counter: AtomicUsize,
@@ -47,6 +52,7 @@ impl ZeronetDriver {
         }
 
         Ok(Self {
+            name: "zeronet".to_string(),
             clients,
             proxy_bases,
             counter: AtomicUsize::new(rand::random::<usize>()),
@@ -71,14 +77,39 @@ impl ZeronetDriver {
     }
 }
 
+impl BuiltinDriver for ZeronetDriver {
+    fn from_config(c: &NetworkConfig) -> Result<Self, CrawlError> {
+        Self::new(
+            &c.proxies,
+            c.max_concurrency,
+            c.min_delay_seconds,
+            c.connect_timeout_seconds,
+            c.request_timeout_seconds,
+        )
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
 #[async_trait]
 impl NetworkDriver for ZeronetDriver {
     fn name(&self) -> &str {
-        "zeronet"
+        &self.name
     }
 
     fn can_handle(&self, url: &Url) -> bool {
-        url.host_str().map(|h| h.ends_with(".bit")).unwrap_or(false)
+        matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|h| h.ends_with(".bit"))
+    }
+
+    async fn health_check(&self) -> Vec<ProxyHealth> {
+        let mut health = Vec::with_capacity(self.proxy_bases.len());
+        for base in &self.proxy_bases {
+            health.push(tcp_probe(base.trim_start_matches("http://")).await);
+        }
+        health
     }
 
     async fn fetch(&self, url: &Url, config: &FetchConfig, retry_count: u32) -> Result<FetchResponse, CrawlError> {
@@ -93,13 +124,17 @@ impl NetworkDriver for ZeronetDriver {
 
         debug!(url = %url, proxy_url = %proxy_url, timeout_secs, retry_count, "fetching via zeronet");
 
-        let resp = client
+        let mut request = client
             .get(&proxy_url)
             .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8")
             .header("Accept-Encoding", "gzip, deflate")
             .header("Accept-Language", "en-US,en;q=0.9")
             .header("User-Agent", "Mozilla/5.0 (DarkScraper/1.0; +https://github.com/yourusername/darkscraper)")
-            .timeout(timeout)
+            .timeout(timeout);
+        for (name, value) in config.validators.request_headers() {
+            request = request.header(name, value);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| {
@@ -142,7 +177,7 @@ impl NetworkDriver for ZeronetDriver {
             body: body.to_vec(),
             content_type,
             fetched_at: chrono::Utc::now(),
-            network: "zeronet".to_string(),
+            network: self.name.clone(),
             response_time_ms: elapsed.as_millis() as u64,
             domain,
         })
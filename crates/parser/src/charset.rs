@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use once_cell::sync::Lazy;
+use regex::bytes::Regex;
+
+use crate::content_type_param;
+
+/// How far into the body to look for a `<meta>` charset. The HTML spec prescans
+/// 1024 bytes; forum templates often put the declaration after long `<head>` blocks.
+const META_PRESCAN: usize = 4096;
+
+/// Bytes fed to the statistical detector; enough text to tell the encodings apart.
+const SNIFF_LIMIT: usize = 64 * 1024;
+
+/// `<meta charset="...">` and `<meta http-equiv="Content-Type" content="...; charset=...">`
+static META_CHARSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<meta[^>]+?charset\s*=\s*["']?\s*([a-z0-9_.:\-]+)"#).unwrap()
+});
+
+/// Decode a response body to UTF-8. Returns the text and the WHATWG name of the
+/// encoding it was decoded from ("UTF-8", "windows-1251", "GBK", ...).
+pub fn decode_body<'a>(body: &'a [u8], content_type: Option<&str>) -> (Cow<'a, str>, &'static str) {
+    let encoding = detect_encoding(body, content_type);
+    // decode() also strips a BOM
+    let (text, _, _) = encoding.decode(body);
+    (text, encoding.name())
+}
+
+/// The body's encoding, from (in order) a byte-order mark, the Content-Type charset,
+/// a `<meta>` declaration, and statistical detection. A declared UTF-8 that doesn't
+/// validate falls through to detection: servers and templates routinely claim UTF-8
+/// for windows-1251 / KOI8-R / GBK pages.
+pub fn detect_encoding(body: &[u8], content_type: Option<&str>) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(body) {
+        return encoding;
+    }
+
+    let declared = content_type
+        .and_then(|ct| content_type_param(ct, "charset"))
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .or_else(|| meta_charset(body));
+
+    match declared {
+        Some(encoding) if encoding != UTF_8 || is_utf8(body) => encoding,
+        _ => sniff(body),
+    }
+}
+
+/// Charset declared by a `<meta>` tag near the top of the document.
+fn meta_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let head = &body[..body.len().min(META_PRESCAN)];
+    let label = META_CHARSET.captures(head)?.get(1)?.as_bytes();
+    // A meta tag can't declare UTF-16 (the bytes it was read from are ASCII-compatible)
+    Encoding::for_label(label).map(|e| e.output_encoding())
+}
+
+/// Valid UTF-8, allowing a sequence cut off at the end (bodies are truncated before parsing).
+fn is_utf8(body: &[u8]) -> bool {
+    match std::str::from_utf8(body) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Statistical guess for undeclared or mis-declared bodies.
+fn sniff(body: &[u8]) -> &'static Encoding {
+    let mut detector = EncodingDetector::new();
+    detector.feed(&body[..body.len().min(SNIFF_LIMIT)], true);
+    detector.guess(None, true)
+}
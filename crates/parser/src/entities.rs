@@ -60,6 +60,9 @@ pub fn extract_entities(text: &str) -> ExtractedEntities {
         phone_numbers,
         pgp_fingerprints,
         usernames,
+        // Only image metadata yields these
+        gps_coordinates: Vec::new(),
+        camera_serials: Vec::new(),
     }
 }
 
@@ -0,0 +1,124 @@
+use url::Url;
+
+use crate::html::{resolved_link, HtmlResult};
+
+/// Parse a text/gemini document. Headings map to h1-h3 (the first `#` is the title),
+/// `=>` lines are links, everything else (preformatted blocks included) is body text.
+/// `language` comes from the response's `lang=` parameter.
+pub fn parse_gemtext(text: &str, base_url: &Url, language: Option<String>) -> HtmlResult {
+    let base_domain = base_url.host_str().unwrap_or("");
+    let mut title = None;
+    let (mut h1, mut h2, mut h3) = (Vec::new(), Vec::new(), Vec::new());
+    let mut links = Vec::new();
+    let mut body: Vec<&str> = Vec::new();
+    let mut preformatted = false;
+
+    for line in text.lines() {
+        if line.starts_with("```") {
+            preformatted = !preformatted;
+            continue;
+        }
+        if preformatted {
+            body.push(line);
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("=>") {
+            let rest = rest.trim();
+            let (target, label) = match rest.split_once(char::is_whitespace) {
+                Some((target, label)) => (target, Some(label.trim())),
+                None => (rest, None),
+            };
+            if target.is_empty() || target.starts_with("mailto:") || target.starts_with("tel:") {
+                continue;
+            }
+            if let Ok(resolved) = base_url.join(target) {
+                links.push(resolved_link(&resolved, label, base_domain));
+            }
+            body.extend(label);
+        } else if line.starts_with('#') {
+            let level = line.chars().take_while(|&c| c == '#').count();
+            let heading = line[level..].trim();
+            body.push(heading);
+            if heading.is_empty() {
+                continue;
+            }
+            match level {
+                1 => {
+                    title.get_or_insert_with(|| heading.to_string());
+                    h1.push(heading.to_string());
+                }
+                2 => h2.push(heading.to_string()),
+                _ => h3.push(heading.to_string()),
+            }
+        } else {
+            // List items and quotes: drop the marker
+            body.push(line.trim_start_matches(['*', '>']));
+        }
+    }
+
+    HtmlResult {
+        title,
+        h1,
+        h2,
+        h3,
+        body_text: body.join(" ").split_whitespace().collect::<Vec<_>>().join(" "),
+        links,
+        meta_description: None,
+        meta_keywords: Vec::new(),
+        language,
+        has_login_form: false,
+        has_search_form: false,
+        open_graph: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPSULE: &str = "gemini://aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion/dir/index.gmi";
+
+    #[test]
+    fn links_resolve_against_the_page() {
+        let text = "# Capsule\n=> other.gmi Other page\n=> /top.gmi\n=>\tgemini://example.org/ Elsewhere\n";
+        let parsed = parse_gemtext(text, &Url::parse(CAPSULE).unwrap(), None);
+        let urls: Vec<&str> = parsed.links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "gemini://aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion/dir/other.gmi",
+                "gemini://aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion/top.gmi",
+                "gemini://example.org/",
+            ]
+        );
+        assert_eq!(parsed.links[0].anchor_text.as_deref(), Some("Other page"));
+        assert_eq!(parsed.links[1].anchor_text, None);
+        assert!(parsed.links[0].is_onion && !parsed.links[0].is_external);
+        assert!(parsed.links[2].is_external);
+    }
+
+    #[test]
+    fn mail_and_phone_links_are_skipped() {
+        let text = "=> mailto:op@example.org Mail\n=> tel:+100 Call\n=> next.gmi Next\n";
+        let parsed = parse_gemtext(text, &Url::parse(CAPSULE).unwrap(), None);
+        assert_eq!(parsed.links.len(), 1);
+        assert!(parsed.links[0].url.ends_with("/dir/next.gmi"));
+    }
+
+    #[test]
+    fn headings_and_preformatted_text() {
+        let text = "# Title\n## Section\n### Sub\n# Second\n```\n=> not-a-link.gmi\n# not a heading\n```\n* item\n> quote\n";
+        let parsed = parse_gemtext(text, &Url::parse(CAPSULE).unwrap(), Some("en".into()));
+        assert_eq!(parsed.title.as_deref(), Some("Title"));
+        assert_eq!(parsed.h1, ["Title", "Second"]);
+        assert_eq!(parsed.h2, ["Section"]);
+        assert_eq!(parsed.h3, ["Sub"]);
+        assert!(parsed.links.is_empty());
+        assert_eq!(
+            parsed.body_text,
+            "Title Section Sub Second => not-a-link.gmi # not a heading item quote"
+        );
+        assert_eq!(parsed.language.as_deref(), Some("en"));
+    }
+}
@@ -0,0 +1,131 @@
+use url::Url;
+
+use crate::html::{resolved_link, HtmlResult};
+
+/// Parse a gopher menu (RFC 1436): one item per line,
+/// `<type><display>\t<selector>\t<host>\t<port>`. Info lines (`i`) are body text
+/// and the first one is the title; every other item with a host is a link.
+pub fn parse_menu(text: &str, base_url: &Url) -> HtmlResult {
+    let base_domain = base_url.host_str().unwrap_or("");
+    let mut title = None;
+    let mut links = Vec::new();
+    let mut body: Vec<&str> = Vec::new();
+    let mut has_search_form = false;
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line == "." {
+            break;
+        }
+        let mut chars = line.chars();
+        let Some(item_type) = chars.next() else {
+            continue;
+        };
+        let mut fields = chars.as_str().split('\t');
+        let display = fields.next().unwrap_or("");
+        body.push(display);
+
+        match item_type {
+            'i' => {
+                if title.is_none() && !display.trim().is_empty() {
+                    title = Some(display.trim().to_string());
+                }
+                continue;
+            }
+            // Errors, telnet sessions and CSO phone books aren't crawlable
+            '3' | '8' | 'T' | '2' => continue,
+            '7' => has_search_form = true,
+            _ => {}
+        }
+
+        let selector = fields.next().unwrap_or("");
+        let host = fields.next().unwrap_or("").trim();
+        let port = fields.next().unwrap_or("70").trim();
+        // `h` items point at other protocols with a URL: selector
+        let target = match selector.strip_prefix("URL:") {
+            Some(url) => url.to_string(),
+            None if host.is_empty() => continue,
+            None => {
+                let selector = selector.replace('%', "%25").replace('?', "%3F").replace('#', "%23");
+                match port {
+                    "70" | "" => format!("gopher://{}/{}{}", host, item_type, selector),
+                    port => format!("gopher://{}:{}/{}{}", host, port, item_type, selector),
+                }
+            }
+        };
+        if let Ok(url) = Url::parse(&target) {
+            links.push(resolved_link(&url, Some(display), base_domain));
+        }
+    }
+
+    HtmlResult {
+        title,
+        h1: Vec::new(),
+        h2: Vec::new(),
+        h3: Vec::new(),
+        body_text: body.join(" ").split_whitespace().collect::<Vec<_>>().join(" "),
+        links,
+        meta_description: None,
+        meta_keywords: Vec::new(),
+        language: None,
+        has_login_form: false,
+        has_search_form,
+        open_graph: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOLE: &str = "gopher://aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion/1/";
+    const HOST: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.onion";
+
+    fn menu(lines: &[&str]) -> String {
+        lines.iter().map(|l| format!("{}\r\n", l)).collect()
+    }
+
+    #[test]
+    fn items_become_links_by_type() {
+        let text = menu(&[
+            "iWelcome to the hole\t\terror.host\t1",
+            &format!("1Phlog\t/phlog\t{HOST}\t70"),
+            &format!("0About\t/about.txt\t{HOST}\t7070"),
+            &format!("9Odd name\t/files/50%?.bin#1\t{HOST}\t70"),
+            "hWeb\tURL:http://example.org/\t\t",
+            &format!("7Search\t/search\t{HOST}\t70"),
+            ".",
+            &format!("1After the end\t/x\t{HOST}\t70"),
+        ]);
+        let parsed = parse_menu(&text, &Url::parse(HOLE).unwrap());
+        let urls: Vec<&str> = parsed.links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                format!("gopher://{HOST}/1/phlog"),
+                format!("gopher://{HOST}:7070/0/about.txt"),
+                format!("gopher://{HOST}/9/files/50%25%3F.bin%231"),
+                "http://example.org/".to_string(),
+                format!("gopher://{HOST}/7/search"),
+            ]
+        );
+        assert_eq!(parsed.links[0].anchor_text.as_deref(), Some("Phlog"));
+        assert!(!parsed.links[0].is_external && parsed.links[3].is_external);
+        assert_eq!(parsed.title.as_deref(), Some("Welcome to the hole"));
+        assert!(parsed.has_search_form);
+    }
+
+    #[test]
+    fn uncrawlable_items_are_text_only() {
+        let text = menu(&[
+            "3Selector not found\t\terror.host\t1",
+            &format!("8Telnet\t\t{HOST}\t23"),
+            &format!("2Phone book\t\t{HOST}\t105"),
+            "1No host\t/nowhere\t\t70",
+        ]);
+        let parsed = parse_menu(&text, &Url::parse(HOLE).unwrap());
+        assert!(parsed.links.is_empty());
+        assert!(parsed.title.is_none());
+        assert_eq!(parsed.body_text, "Selector not found Telnet Phone book No host");
+    }
+}
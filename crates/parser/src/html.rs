@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use scraper::{Html, Selector};
 use url::Url;
 
-use darkscraper_core::ExtractedLink;
+use darkscraper_core::{ipfs, ExtractedLink};
 
 pub struct HtmlResult {
     pub title: Option<String>,
@@ -153,10 +153,12 @@ fn extract_links(document: &Html, base_url: &Url, base_domain: &str) -> Vec<Extr
                     is_hyphanet: true,
                     is_lokinet: false,
                     is_external: true,
+                    network: None,
                 });
             }
 
             let resolved = base_url.join(href).ok()?;
+            let resolved = ipfs_link(&resolved).unwrap_or(resolved);
             let host = resolved.host_str().unwrap_or("");
 
             // Convert FProxy gateway URLs back to hyphanet: scheme
@@ -194,11 +196,52 @@ fn extract_links(document: &Html, base_url: &Url, base_domain: &str) -> Vec<Extr
                 is_hyphanet: is_hyphanet_link,
                 is_lokinet: host.ends_with(".loki"),
                 is_external: host != base_domain,
+                network: None,
             })
         })
         .collect()
 }
 
+/// IPFS address behind a link: clearnet gateway URLs (`https://ipfs.io/ipfs/<cid>`,
+/// `https://<cid>.ipfs.dweb.link`) and gateway-absolute `/ipfs/<cid>` links on pages
+/// fetched through a gateway. Overlay hosts keep their URL; they're crawled as sites.
+fn ipfs_link(url: &Url) -> Option<Url> {
+    match url.scheme() {
+        "ipfs" | "ipns" => {
+            let path = url.path();
+            if !path.starts_with("/ipfs/") && !path.starts_with("/ipns/") {
+                return None;
+            }
+            let query = url.query().map(|q| format!("?{}", q)).unwrap_or_default();
+            ipfs::parse_address(&format!("{}{}", path, query))
+        }
+        "http" | "https" => {
+            let host = url.host_str()?;
+            if [".onion", ".i2p", ".bit", ".loki"].iter().any(|tld| host.ends_with(tld)) {
+                return None;
+            }
+            ipfs::canonical_url(url)
+        }
+        _ => None,
+    }
+}
+
+/// Link record for an already-resolved URL (gemtext and gopher links).
+pub(crate) fn resolved_link(url: &Url, anchor_text: Option<&str>, base_domain: &str) -> ExtractedLink {
+    let host = url.host_str().unwrap_or("");
+    ExtractedLink {
+        url: url.to_string(),
+        anchor_text: anchor_text.map(str::trim).filter(|t| !t.is_empty()).map(str::to_string),
+        is_onion: host.ends_with(".onion"),
+        is_i2p: host.ends_with(".i2p"),
+        is_zeronet: host.ends_with(".bit"),
+        is_hyphanet: url.scheme() == "hyphanet" || url.scheme() == "freenet",
+        is_lokinet: host.ends_with(".loki"),
+        is_external: host != base_domain,
+        network: None,
+    }
+}
+
 fn extract_meta_content(document: &Html, name: &str) -> Option<String> {
     let sel_str = format!(
         "meta[name='{}'], meta[name='{}']",
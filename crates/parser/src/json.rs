@@ -0,0 +1,94 @@
+use serde_json::Value;
+use url::Url;
+
+use crate::html::{resolved_link, HtmlResult};
+use crate::text::{bare_urls, parse_text};
+
+/// Keys whose string value is taken as the document title.
+const TITLE_KEYS: &[&str] = &["title", "name", "headline", "subject"];
+/// Keys whose relative path value ("/api/items?page=2") is a link.
+const LINK_KEYS: &[&str] = &["url", "href", "link", "uri", "src", "next", "prev", "previous"];
+/// Nesting below this depth is not walked.
+const MAX_DEPTH: usize = 64;
+
+/// Parse a JSON body (APIs, market listings, JSON-LD). Every string is walked:
+/// strings become body text, URLs in them (and relative values of url/href/link
+/// keys) become links. Bodies that aren't valid JSON are parsed as plain text.
+pub fn parse_json(text: &str, base_url: &Url) -> HtmlResult {
+    let Ok(value) = serde_json::from_str::<Value>(text) else {
+        return parse_text(text, base_url);
+    };
+
+    let mut walk = Walk::default();
+    walk.visit(&value, None, base_url, 0);
+
+    let base_domain = base_url.host_str().unwrap_or("");
+    let mut seen = std::collections::HashSet::new();
+    let links = walk
+        .links
+        .into_iter()
+        .filter(|url| seen.insert(url.to_string()))
+        .map(|url| resolved_link(&url, None, base_domain))
+        .collect();
+
+    HtmlResult {
+        title: walk.title,
+        h1: Vec::new(),
+        h2: Vec::new(),
+        h3: Vec::new(),
+        body_text: walk.strings.join(" ").split_whitespace().collect::<Vec<_>>().join(" "),
+        links,
+        meta_description: None,
+        meta_keywords: Vec::new(),
+        language: None,
+        has_login_form: false,
+        has_search_form: false,
+        open_graph: Default::default(),
+    }
+}
+
+#[derive(Default)]
+struct Walk {
+    title: Option<String>,
+    strings: Vec<String>,
+    links: Vec<Url>,
+}
+
+impl Walk {
+    fn visit(&mut self, value: &Value, key: Option<&str>, base_url: &Url, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        match value {
+            Value::String(s) => self.visit_string(s, key, base_url),
+            Value::Array(items) => {
+                for item in items {
+                    self.visit(item, key, base_url, depth + 1);
+                }
+            }
+            Value::Object(map) => {
+                for (k, v) in map {
+                    self.visit(v, Some(k), base_url, depth + 1);
+                }
+            }
+            Value::Number(_) | Value::Bool(_) | Value::Null => {}
+        }
+    }
+
+    fn visit_string(&mut self, s: &str, key: Option<&str>, base_url: &Url) {
+        let key_lower = key.map(str::to_lowercase);
+        if self.title.is_none() && key_lower.as_deref().is_some_and(|k| TITLE_KEYS.contains(&k)) {
+            self.title = Some(s.trim().to_string()).filter(|t| !t.is_empty());
+        }
+
+        let found = bare_urls(s);
+        let relative = s.starts_with(['/', '?', '.']) && !s.contains(char::is_whitespace);
+        if found.is_empty() && relative && key_lower.as_deref().is_some_and(|k| LINK_KEYS.contains(&k)) {
+            if let Ok(url) = base_url.join(s) {
+                self.links.push(url);
+            }
+        }
+        self.links.extend(found);
+        self.strings.push(s.to_string());
+    }
+}
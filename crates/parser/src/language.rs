@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use whatlang::Lang;
+
+/// Text beyond this many bytes is not analysed.
+const MAX_TEXT_BYTES: usize = 50_000;
+/// Below this many characters of text there is too little to identify.
+const MIN_TEXT_CHARS: usize = 40;
+/// Words per segment when measuring the language mix of a page.
+const SEGMENT_WORDS: usize = 40;
+/// Segments identified with less confidence don't count toward the mix.
+const MIN_SEGMENT_CONFIDENCE: f64 = 0.5;
+/// Languages covering less of the text than this are dropped from the mix.
+const MIN_SHARE: f64 = 0.1;
+/// Languages reported for a mixed-language page.
+const MAX_LANGUAGES: usize = 3;
+
+/// Statistical language identification of a page's text.
+#[derive(Debug, Clone, Default)]
+pub struct LanguageDetection {
+    /// Dominant language (ISO 639-1)
+    pub language: Option<String>,
+    pub confidence: Option<f64>,
+    /// (language, share of the text), largest first
+    pub languages: Vec<(String, f64)>,
+}
+
+/// Identify the language of `text`, plus the mix of languages across its segments
+/// (forum threads and market listings often mix Russian and English).
+pub fn detect_languages(text: &str) -> LanguageDetection {
+    let text = truncate(text, MAX_TEXT_BYTES);
+    if text.chars().filter(|c| c.is_alphabetic()).count() < MIN_TEXT_CHARS {
+        return LanguageDetection::default();
+    }
+
+    // Share of each language, weighted by segment length
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut weights: HashMap<Lang, usize> = HashMap::new();
+    for segment in words.chunks(SEGMENT_WORDS) {
+        let segment = segment.join(" ");
+        if let Some(info) = whatlang::detect(&segment) {
+            if info.confidence() >= MIN_SEGMENT_CONFIDENCE {
+                *weights.entry(info.lang()).or_default() += segment.len();
+            }
+        }
+    }
+    let total: usize = weights.values().sum();
+    let mut languages: Vec<(Lang, f64)> = weights
+        .into_iter()
+        .map(|(lang, weight)| (lang, weight as f64 / total as f64))
+        .filter(|(_, share)| *share >= MIN_SHARE)
+        .collect();
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    languages.truncate(MAX_LANGUAGES);
+
+    // Dominant language from the whole text; fall back to the largest segment share
+    let (language, confidence) = match whatlang::detect(text) {
+        Some(info) => (Some(info.lang()), Some(info.confidence())),
+        None => (languages.first().map(|(lang, _)| *lang), languages.first().map(|(_, share)| *share)),
+    };
+
+    LanguageDetection {
+        language: language.map(|lang| iso639_1(lang).to_string()),
+        confidence,
+        languages: languages
+            .into_iter()
+            .map(|(lang, share)| (iso639_1(lang).to_string(), share))
+            .collect(),
+    }
+}
+
+/// Two-letter code, the form `<html lang>` and the topic profile use.
+fn iso639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Epo => "eo",
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Yid => "yi",
+        Lang::Pol => "pl",
+        Lang::Amh => "am",
+        Lang::Jav => "jv",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Mar => "mr",
+        Lang::Kan => "kn",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Tam => "ta",
+        Lang::Vie => "vi",
+        Lang::Urd => "ur",
+        Lang::Tha => "th",
+        Lang::Guj => "gu",
+        Lang::Uzb => "uz",
+        Lang::Pan => "pa",
+        Lang::Aze => "az",
+        Lang::Ind => "id",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Sin => "si",
+        Lang::Khm => "km",
+        Lang::Tuk => "tk",
+        Lang::Aka => "ak",
+        Lang::Zul => "zu",
+        Lang::Sna => "sn",
+        Lang::Afr => "af",
+        Lang::Lat => "la",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+    }
+}
+
+/// Longest prefix of `s` of at most `max` bytes, on a char boundary.
+fn truncate(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
@@ -1,10 +1,21 @@
+pub mod charset;
 pub mod entities;
+pub mod gemtext;
+pub mod gopher;
 pub mod html;
+pub mod json;
+pub mod language;
+pub mod sniff;
+pub mod text;
+pub mod xml;
 
 use darkscraper_core::{CrawlError, FetchResponse, PageData, PageMetadata};
 use sha2::{Digest, Sha256};
 use url::Url;
 
+pub use charset::decode_body;
+pub use sniff::{content_kind, ContentKind};
+
 /// Max bytes to parse (5 MB). Larger pages are truncated before parsing.
 const MAX_PARSE_SIZE: usize = 5 * 1024 * 1024;
 
@@ -38,18 +49,81 @@ fn extract_domain(url: &Url) -> String {
     "unknown".to_string()
 }
 
+/// Media type without parameters, lowercased ("text/gemini; lang=en" → "text/gemini").
+fn media_type(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or("").trim().to_lowercase()
+}
+
+/// A content-type parameter ("text/gemini; lang=en", "lang" → "en").
+fn content_type_param(content_type: &str, name: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Whether `parse_response` has a parser for this body: HTML, gemtext, gopher menus,
+/// plain text, JSON or XML (RSS/Atom included). Missing or generic content types are
+/// sniffed from the body.
+pub fn is_parseable(content_type: Option<&str>, body: &[u8]) -> bool {
+    content_kind(content_type, body).is_some()
+}
+
 pub fn parse_response(resp: &FetchResponse) -> Result<PageData, CrawlError> {
+    let kind = content_kind(resp.content_type.as_deref(), &resp.body)
+        .ok_or_else(|| CrawlError::Parse(format!("no parser for {:?}", resp.content_type)))?;
     let body = if resp.body.len() > MAX_PARSE_SIZE {
         &resp.body[..MAX_PARSE_SIZE]
     } else {
         &resp.body
     };
-    let body_str = String::from_utf8_lossy(body);
+    // Transcode to UTF-8 before parsing; many target forums are windows-1251 / KOI8-R / GBK
+    let (body_str, encoding) = decode_body(body, resp.content_type.as_deref());
 
-    let html_result = html::parse_html(&body_str, &resp.url);
-    // Extract from both body_text AND raw HTML to catch JS-embedded data (ZeroNet SPAs)
+    let content_type = resp.content_type.as_deref().unwrap_or("");
+    let mut feed = None;
+    let html_result = match kind {
+        ContentKind::Gemtext => gemtext::parse_gemtext(
+            &body_str,
+            &resp.final_url,
+            content_type_param(content_type, "lang"),
+        ),
+        ContentKind::GopherMenu => gopher::parse_menu(&body_str, &resp.url),
+        ContentKind::PlainText => text::parse_text(&body_str, &resp.final_url),
+        ContentKind::Json => json::parse_json(&body_str, &resp.final_url),
+        ContentKind::Xml => {
+            let (result, parsed_feed) = xml::parse_xml(&body_str, &resp.final_url);
+            feed = parsed_feed;
+            result
+        }
+        ContentKind::Html => html::parse_html(&body_str, &resp.url),
+    };
+    // Sniffed bodies record what they were parsed as
+    let content_type = resp.content_type.clone().or_else(|| Some(kind.mime().to_string()));
+    let mut page = build_page(resp, html_result, &body_str, content_type);
+    page.encoding = Some(encoding.to_string());
+    page.feed = feed;
+    Ok(page)
+}
+
+/// Page for text extracted from a binary document (PDF, office files): parsed as
+/// plain text, with the extracted text stored in place of the raw body.
+pub fn parse_document_text(resp: &FetchResponse, text: &str) -> PageData {
+    let html_result = text::parse_text(text, &resp.final_url);
+    build_page(resp, html_result, text, resp.content_type.clone())
+}
+
+fn build_page(
+    resp: &FetchResponse,
+    html_result: html::HtmlResult,
+    body_str: &str,
+    content_type: Option<String>,
+) -> PageData {
+    // Extract from both body_text AND the raw body to catch JS-embedded data (ZeroNet SPAs)
     let mut entities = entities::extract_entities(&html_result.body_text);
-    let raw_entities = entities::extract_entities(&body_str);
+    let raw_entities = entities::extract_entities(body_str);
     // Merge entities from raw HTML
     entities.emails.extend(raw_entities.emails);
     entities
@@ -96,21 +170,30 @@ pub fn parse_response(resp: &FetchResponse) -> Result<PageData, CrawlError> {
 
     let domain = resp.domain.clone();
 
+    // Most sites omit <html lang> or leave the template's "en"; identify it from the text
+    let detected = language::detect_languages(&html_result.body_text);
+
     // Extract metadata from response headers
     let metadata = PageMetadata {
         server_header: resp.headers.get("server").cloned(),
         powered_by: resp.headers.get("x-powered-by").cloned(),
+        etag: resp.headers.get("etag").cloned(),
+        last_modified: resp.headers.get("last-modified").cloned(),
         meta_description: html_result.meta_description,
         meta_keywords: html_result.meta_keywords,
         language: html_result.language,
+        detected_language: detected.language,
+        language_confidence: detected.confidence,
+        languages: detected.languages,
         has_login_form: html_result.has_login_form,
         has_search_form: html_result.has_search_form,
         open_graph: html_result.open_graph,
     };
 
-    Ok(PageData {
+    PageData {
         url: resp.url.to_string(),
         final_url: resp.final_url.to_string(),
+        canonical_url: None,
         network: resp.network.clone(),
         title: html_result.title,
         h1: html_result.h1,
@@ -126,6 +209,10 @@ pub fn parse_response(resp: &FetchResponse) -> Result<PageData, CrawlError> {
         response_time_ms: resp.response_time_ms,
         status_code: resp.status,
         domain,
-        content_type: resp.content_type.clone(),
-    })
+        content_type,
+        encoding: None,
+        relevance: None,
+        feed: None,
+        body_hash: None,
+    }
 }
@@ -0,0 +1,95 @@
+use crate::media_type;
+
+/// How a response body is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Html,
+    Gemtext,
+    GopherMenu,
+    PlainText,
+    Json,
+    /// Generic XML, RSS and Atom (told apart by the root element)
+    Xml,
+}
+
+impl ContentKind {
+    /// Media type recorded for bodies that came without a content type.
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ContentKind::Html => "text/html",
+            ContentKind::Gemtext => "text/gemini",
+            ContentKind::GopherMenu => "application/gopher-menu",
+            ContentKind::PlainText => "text/plain",
+            ContentKind::Json => "application/json",
+            ContentKind::Xml => "application/xml",
+        }
+    }
+}
+
+/// Bytes looked at when sniffing.
+const SNIFF_BYTES: usize = 1024;
+
+/// Tags that mark a body as HTML when it starts with one (WHATWG MIME sniffing).
+const HTML_TAGS: &[&str] = &[
+    "<!doctype html", "<html", "<head", "<script", "<iframe", "<h1", "<div", "<font",
+    "<table", "<a", "<style", "<title", "<b", "<body", "<br", "<p", "<!--",
+];
+
+/// How to parse a body, from its content type and, when that is missing or one of
+/// the generic types servers default to, from the bytes themselves. None = binary
+/// or a type without a parser.
+pub fn content_kind(content_type: Option<&str>, body: &[u8]) -> Option<ContentKind> {
+    let declared = content_type.map(media_type);
+    match declared.as_deref() {
+        Some("text/html" | "application/xhtml+xml") => Some(ContentKind::Html),
+        Some("text/gemini") => Some(ContentKind::Gemtext),
+        Some("application/gopher-menu") => Some(ContentKind::GopherMenu),
+        Some("application/json" | "text/json") => Some(ContentKind::Json),
+        Some(t) if t.ends_with("+json") => Some(ContentKind::Json),
+        Some("application/xml" | "text/xml") => Some(ContentKind::Xml),
+        Some(t) if t.ends_with("+xml") => Some(ContentKind::Xml),
+        None | Some("" | "text/plain" | "application/octet-stream" | "binary/octet-stream" | "application/unknown") => {
+            sniff(body)
+        }
+        Some(t) if t.starts_with("text/") => Some(ContentKind::PlainText),
+        _ => None,
+    }
+}
+
+/// Guess a body's kind from its first bytes: markup, JSON, text or binary.
+pub fn sniff(body: &[u8]) -> Option<ContentKind> {
+    let head = &body[..body.len().min(SNIFF_BYTES)];
+    let has_bom = head.starts_with(&[0xEF, 0xBB, 0xBF])
+        || head.starts_with(&[0xFE, 0xFF])
+        || head.starts_with(&[0xFF, 0xFE]);
+    // WHATWG "binary data bytes": control characters that never occur in text
+    if !has_bom && head.iter().any(|&b| matches!(b, 0x00..=0x08 | 0x0B | 0x0E..=0x1A | 0x1C..=0x1F)) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(head);
+    let start = text.trim_start_matches(['\u{feff}', ' ', '\t', '\r', '\n']).to_lowercase();
+    if start.starts_with("<?xml") {
+        // XHTML documents carry an XML declaration too
+        return Some(if start.contains("<html") { ContentKind::Html } else { ContentKind::Xml });
+    }
+    if ["<rss", "<feed", "<rdf:rdf"].iter().any(|root| start.starts_with(root)) {
+        return Some(ContentKind::Xml);
+    }
+    let is_html = HTML_TAGS.iter().any(|tag| {
+        start.strip_prefix(tag).is_some_and(|rest| {
+            *tag == "<!--" || rest.starts_with([' ', '>', '\t', '\n', '\r'])
+        })
+    });
+    if is_html {
+        return Some(ContentKind::Html);
+    }
+    if start.starts_with('{') || start.starts_with('[') {
+        return Some(ContentKind::Json);
+    }
+    // Pages with junk before the markup (stray text, <meta> first) are still HTML
+    if start.contains("<html") || start.contains("<body") {
+        return Some(ContentKind::Html);
+    }
+    Some(ContentKind::PlainText)
+}
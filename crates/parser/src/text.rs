@@ -0,0 +1,75 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use url::Url;
+
+use darkscraper_core::ExtractedLink;
+
+use crate::html::{resolved_link, HtmlResult};
+
+/// Absolute URLs of the schemes we crawl, up to whitespace, quotes or brackets.
+static URL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\b(?:https?|gemini|gopher|ipfs|ipns)://[^\s<>"'`\\(){}\[\]]+|\b(?:hyphanet|freenet):(?:USK|SSK|CHK|KSK)@[^\s<>"'`\\(){}\[\]]+"#)
+        .unwrap()
+});
+
+/// Bare overlay hosts written without a scheme ("xyz...xyz.onion/forum").
+static BARE_HOST_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[^a-z0-9./:@-])((?:[a-z2-7]{56}\.onion|[a-z2-7]{52}\.b32\.i2p|[a-z0-9-]+\.i2p|[a-z0-9]{52}\.loki)(?:/[^\s<>'`\\(){}\[\]]*)?)")
+        .unwrap()
+});
+
+/// Parse a plain-text body (.txt dumps, pastes, READMEs). The first non-empty line
+/// is the title; every URL or bare overlay address in the text is a link.
+pub fn parse_text(text: &str, base_url: &Url) -> HtmlResult {
+    let title = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| line.chars().take(200).collect());
+
+    HtmlResult {
+        title,
+        h1: Vec::new(),
+        h2: Vec::new(),
+        h3: Vec::new(),
+        body_text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        links: bare_links(text, base_url),
+        meta_description: None,
+        meta_keywords: Vec::new(),
+        language: None,
+        has_login_form: false,
+        has_search_form: false,
+        open_graph: Default::default(),
+    }
+}
+
+/// Links for every URL or bare overlay address written out in `text`.
+pub(crate) fn bare_links(text: &str, base_url: &Url) -> Vec<ExtractedLink> {
+    let base_domain = base_url.host_str().unwrap_or("");
+    let mut seen = std::collections::HashSet::new();
+    bare_urls(text)
+        .into_iter()
+        .filter(|url| seen.insert(url.to_string()))
+        .map(|url| resolved_link(&url, None, base_domain))
+        .collect()
+}
+
+/// URLs written out in `text`. Bare overlay hosts get http://.
+pub(crate) fn bare_urls(text: &str) -> Vec<Url> {
+    let explicit = URL_RE
+        .find_iter(text)
+        .map(|m| trim_trailing_punctuation(m.as_str()).to_string());
+    let bare = BARE_HOST_RE
+        .captures_iter(text)
+        .filter_map(|c| c.get(1))
+        .map(|m| format!("http://{}", trim_trailing_punctuation(m.as_str())));
+    explicit
+        .chain(bare)
+        .filter_map(|s| Url::parse(&s).ok())
+        .collect()
+}
+
+/// Sentence punctuation after a URL isn't part of it ("see http://x.onion/.").
+fn trim_trailing_punctuation(url: &str) -> &str {
+    url.trim_end_matches(['.', ',', ';', ':', '!', '?'])
+}
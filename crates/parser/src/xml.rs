@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use regex::Regex;
+use url::Url;
+
+use darkscraper_core::{Feed, FeedItem};
+
+use crate::html::{resolved_link, HtmlResult};
+use crate::text::{bare_urls, parse_text};
+
+/// Attributes whose value is a link in generic XML (XLink, RDF, OPML, custom APIs).
+const LINK_ATTRIBUTES: &[&str] = &["href", "src", "url", "link", "resource", "about", "xmlurl", "htmlurl"];
+
+/// Tags inside escaped HTML (RSS descriptions, Atom content).
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Parse an XML body. RSS (0.9x/1.0/2.0) and Atom documents yield their items;
+/// any XML yields its text and every link in attributes or text. Bodies that
+/// aren't well-formed XML are parsed as plain text.
+pub fn parse_xml(text: &str, base_url: &Url) -> (HtmlResult, Option<Feed>) {
+    match walk(text, base_url) {
+        Some(parsed) => parsed,
+        None => (parse_text(text, base_url), None),
+    }
+}
+
+fn walk(text: &str, base_url: &Url) -> Option<(HtmlResult, Option<Feed>)> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    // Element path of local names, lowercased
+    let mut path: Vec<String> = Vec::new();
+    let mut format: Option<&'static str> = None;
+    let mut title: Option<String> = None;
+    let mut items: Vec<FeedItem> = Vec::new();
+    let mut item: Option<FeedItem> = None;
+    let mut body: Vec<String> = Vec::new();
+    let mut urls: Vec<Url> = Vec::new();
+    // Text of the element being read (reset on every start tag)
+    let mut current = String::new();
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                if path.is_empty() {
+                    format = match name.as_str() {
+                        "rss" | "rdf" => Some("rss"),
+                        "feed" => Some("atom"),
+                        _ => None,
+                    };
+                }
+                if format.is_some() && (name == "item" || name == "entry") {
+                    item = Some(FeedItem { title: None, link: None, guid: None, published_at: None });
+                }
+                link_attributes(&e, &name, format, item.as_mut(), base_url, &mut urls);
+                path.push(name);
+                current.clear();
+            }
+            Event::Empty(e) => {
+                let name = local_name(&e);
+                link_attributes(&e, &name, format, item.as_mut(), base_url, &mut urls);
+            }
+            Event::Text(t) => {
+                let s = t.unescape().map(|c| c.into_owned()).unwrap_or_else(|_| String::from_utf8_lossy(&t).into_owned());
+                current.push_str(&s);
+            }
+            Event::CData(c) => current.push_str(&String::from_utf8_lossy(&c)),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str).unwrap_or("");
+                let value = strip_markup(&current);
+                current.clear();
+                if name == "item" || name == "entry" {
+                    items.extend(item.take());
+                    continue;
+                }
+                if value.is_empty() {
+                    continue;
+                }
+
+                urls.extend(bare_urls(&value));
+                match (item.as_mut(), name.as_str()) {
+                    (Some(item), "title") => item.title = Some(value.clone()),
+                    // RSS <link>text</link>; Atom links are attributes
+                    (Some(item), "link") if item.link.is_none() => item.link = absolute(&value, base_url).map(String::from),
+                    (Some(item), "guid" | "id") => item.guid = Some(value.clone()),
+                    (Some(item), "pubdate" | "published" | "date" | "issued") => {
+                        item.published_at = parse_date(&value).or(item.published_at);
+                    }
+                    (Some(item), "updated" | "modified") if item.published_at.is_none() => {
+                        item.published_at = parse_date(&value);
+                    }
+                    (None, "title") if title.is_none() && (format.is_none() || matches!(parent, "channel" | "feed")) => {
+                        title = Some(value.clone());
+                    }
+                    (None, "link") if format == Some("rss") => urls.extend(absolute(&value, base_url)),
+                    _ => {}
+                }
+                body.push(value);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    // Not XML at all (no elements): let the caller treat it as text
+    if format.is_none() && body.is_empty() && urls.is_empty() {
+        return None;
+    }
+
+    let base_domain = base_url.host_str().unwrap_or("");
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    // Item links carry their titles as anchor text
+    for item in &items {
+        if let Some(url) = item.link.as_deref().and_then(|l| Url::parse(l).ok()) {
+            if seen.insert(url.to_string()) {
+                links.push(resolved_link(&url, item.title.as_deref(), base_domain));
+            }
+        }
+    }
+    for url in urls {
+        if seen.insert(url.to_string()) {
+            links.push(resolved_link(&url, None, base_domain));
+        }
+    }
+
+    let result = HtmlResult {
+        title,
+        h1: Vec::new(),
+        h2: items.iter().filter_map(|i| i.title.clone()).collect(),
+        h3: Vec::new(),
+        body_text: body.join(" ").split_whitespace().collect::<Vec<_>>().join(" "),
+        links,
+        meta_description: None,
+        meta_keywords: Vec::new(),
+        language: None,
+        has_login_form: false,
+        has_search_form: false,
+        open_graph: Default::default(),
+    };
+    let feed = format.map(|format| Feed { format: format.to_string(), items });
+    Some((result, feed))
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase()
+}
+
+/// Links in attributes. An Atom `<link href>` inside an entry is the entry's link
+/// (the alternate one when several are given).
+fn link_attributes(
+    e: &BytesStart,
+    name: &str,
+    format: Option<&str>,
+    item: Option<&mut FeedItem>,
+    base_url: &Url,
+    urls: &mut Vec<Url>,
+) {
+    let mut href = None;
+    let mut rel = None;
+    for attr in e.attributes().flatten() {
+        let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_lowercase();
+        let Ok(value) = attr.unescape_value() else {
+            continue;
+        };
+        if key == "rel" {
+            rel = Some(value.to_lowercase());
+        } else if LINK_ATTRIBUTES.contains(&key.as_str()) {
+            if let Some(url) = absolute(&value, base_url) {
+                if key == "href" {
+                    href = Some(url.clone());
+                }
+                urls.push(url);
+            }
+        }
+    }
+    if let (Some(item), Some(href)) = (item, href) {
+        let alternate = rel.as_deref().is_none_or(|r| r == "alternate");
+        if format == Some("atom") && name == "link" && alternate && item.link.is_none() {
+            item.link = Some(href.to_string());
+        }
+    }
+}
+
+/// Resolve a link value against the document URL (skipping anything that isn't one).
+fn absolute(value: &str, base_url: &Url) -> Option<Url> {
+    let value = value.trim();
+    if value.is_empty() || value.contains(char::is_whitespace) || value.starts_with(['#', '{']) {
+        return None;
+    }
+    base_url.join(value).ok()
+}
+
+/// Text with escaped HTML markup (descriptions, content) reduced to its words.
+fn strip_markup(s: &str) -> String {
+    let s = if s.contains('<') { TAG_RE.replace_all(s, " ") } else { s.into() };
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// RSS dates are RFC 2822, Atom and Dublin Core dates RFC 3339.
+fn parse_date(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc2822(s)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(s))
+        .ok()
+        .map(|d| d.with_timezone(&chrono::Utc))
+}
@@ -2,6 +2,8 @@ use anyhow::Result;
 use serde::Serialize;
 use sqlx::PgPool;
 
+use darkscraper_core::language::{primary_subtag, text_search_config};
+
 #[derive(Debug, Serialize)]
 pub struct SearchResult {
     pub page_id: i64,
@@ -23,6 +25,28 @@ pub struct EntityResult {
     pub page_title: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct CloneResult {
+    pub clone_domain: String,
+    pub canonical_domain: String,
+    pub content_distance: i32,
+    pub dom_distance: i32,
+    pub vanity_prefix_len: i32,
+    pub reason: String,
+    pub canonical_basis: String,
+    pub detected_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloneEntityDiffResult {
+    pub clone_domain: String,
+    pub canonical_domain: String,
+    pub entity_type: String,
+    pub value: String,
+    pub side: String,
+    pub substituted: bool,
+}
+
 pub struct SearchEngine {
     pool: PgPool,
 }
@@ -32,31 +56,53 @@ impl SearchEngine {
         Self { pool }
     }
 
-    /// Search pages by body text, raw HTML, and title (ILIKE with trigram index)
-    pub async fn search_text(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>> {
-        let pattern = format!("%{}%", query);
-        let rows = sqlx::query_as::<
-            _,
-            (
-                i64,
-                String,
-                Option<String>,
-                String,
-                String,
-                Option<String>,
-                chrono::DateTime<chrono::Utc>,
-            ),
-        >(
-            r#"SELECT id, url, title, network, domain, LEFT(body_text, 200), fetched_at
-               FROM pages
-               WHERE body_text ILIKE $1 OR title ILIKE $1 OR raw_html ILIKE $1
-               ORDER BY fetched_at DESC
-               LIMIT $2"#,
-        )
-        .bind(&pattern)
-        .bind(limit)
-        .fetch_all(&self.pool)
-        .await?;
+    /// Search pages by body text and title (ILIKE with trigram index). Markup is not
+    /// searched: pages whose body lives in the blob store have no `raw_html`, and
+    /// matching it for the rest would make results depend on where a body is kept.
+    /// With `language`, only pages whose effective language (detected when confident,
+    /// else declared) is that language, matched with that language's Postgres
+    /// text-search configuration, the one their search vector was built with, so
+    /// stems match ("покупка" finds "покупки").
+    pub async fn search_text(&self, query: &str, language: Option<&str>, limit: i64) -> Result<Vec<SearchResult>> {
+        type Row = (
+            i64,
+            String,
+            Option<String>,
+            String,
+            String,
+            Option<String>,
+            chrono::DateTime<chrono::Utc>,
+        );
+        let rows = if let Some(language) = language {
+            let language = primary_subtag(language);
+            sqlx::query_as::<_, Row>(
+                r#"SELECT id, url, title, network, domain, LEFT(body_text, 200), fetched_at
+                   FROM pages
+                   WHERE effective_language = $2
+                     AND search_vector @@ websearch_to_tsquery($3::regconfig, $1)
+                   ORDER BY ts_rank(search_vector, websearch_to_tsquery($3::regconfig, $1)) DESC, fetched_at DESC
+                   LIMIT $4"#,
+            )
+            .bind(query)
+            .bind(&language)
+            .bind(text_search_config(&language))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?
+        } else {
+            let pattern = format!("%{}%", query);
+            sqlx::query_as::<_, Row>(
+                r#"SELECT id, url, title, network, domain, LEFT(body_text, 200), fetched_at
+                   FROM pages
+                   WHERE body_text ILIKE $1 OR title ILIKE $1
+                   ORDER BY fetched_at DESC
+                   LIMIT $2"#,
+            )
+            .bind(&pattern)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?
+        };
 
         Ok(rows
             .into_iter()
@@ -122,4 +168,73 @@ impl SearchEngine {
             })
             .collect())
     }
+
+    /// List suspected phishing clones. With `domain`, only clusters where it is
+    /// either the clone or the canonical site.
+    pub async fn search_clones(&self, domain: Option<&str>, limit: i64) -> Result<Vec<CloneResult>> {
+        let rows = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                i32,
+                i32,
+                i32,
+                String,
+                String,
+                chrono::DateTime<chrono::Utc>,
+            ),
+        >(
+            r#"SELECT clone_domain, canonical_domain, content_distance, dom_distance,
+                      vanity_prefix_len, reason, canonical_basis, detected_at
+               FROM suspected_clones
+               WHERE $1::text IS NULL OR clone_domain = $1 OR canonical_domain = $1
+               ORDER BY canonical_domain, content_distance
+               LIMIT $2"#,
+        )
+        .bind(domain)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| CloneResult {
+                clone_domain: r.0,
+                canonical_domain: r.1,
+                content_distance: r.2,
+                dom_distance: r.3,
+                vanity_prefix_len: r.4,
+                reason: r.5,
+                canonical_basis: r.6,
+                detected_at: r.7,
+            })
+            .collect())
+    }
+
+    /// Entities that differ between a suspected clone and its canonical site
+    /// (substituted payment addresses first).
+    pub async fn clone_entity_diffs(&self, clone_domain: &str) -> Result<Vec<CloneEntityDiffResult>> {
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, bool)>(
+            r#"SELECT clone_domain, canonical_domain, entity_type, value, side, substituted
+               FROM clone_entity_diffs
+               WHERE clone_domain = $1
+               ORDER BY substituted DESC, entity_type, side"#,
+        )
+        .bind(clone_domain)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| CloneEntityDiffResult {
+                clone_domain: r.0,
+                canonical_domain: r.1,
+                entity_type: r.2,
+                value: r.3,
+                side: r.4,
+                substituted: r.5,
+            })
+            .collect())
+    }
 }
@@ -134,3 +134,318 @@ CREATE TABLE IF NOT EXISTS crawl_queue (
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW()
 );
+
+-- Declared mirror relationships (site A declares mirror B) harvested from
+-- Onion-Location headers/meta, Alt-Svc and <link rel="alternate">
+CREATE TABLE IF NOT EXISTS mirrors (
+    id BIGSERIAL PRIMARY KEY,
+    source_domain TEXT NOT NULL,
+    mirror_domain TEXT NOT NULL,
+    mirror_url TEXT NOT NULL,
+    signal VARCHAR(30) NOT NULL, -- onion_location_header, onion_location_meta, alt_svc, link_alternate
+    first_seen_at TIMESTAMPTZ DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_mirrors_unique ON mirrors(source_domain, mirror_domain, signal);
+CREATE INDEX IF NOT EXISTS idx_mirrors_mirror_domain ON mirrors(mirror_domain);
+
+-- PGP-signed mirror lists: mirrors table also records verification outcome
+ALTER TABLE mirrors ADD COLUMN IF NOT EXISTS verified BOOLEAN;
+ALTER TABLE mirrors ADD COLUMN IF NOT EXISTS signer_fingerprint TEXT;
+
+-- Public keys seen per site (key_hash matches the pgp_key_hash correlation)
+CREATE TABLE IF NOT EXISTS pgp_keys (
+    id BIGSERIAL PRIMARY KEY,
+    domain TEXT NOT NULL,
+    key_hash VARCHAR(64) NOT NULL,
+    fingerprint VARCHAR(64) NOT NULL,
+    armored TEXT NOT NULL,
+    first_seen_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(domain, key_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pgp_keys_fingerprint ON pgp_keys(fingerprint);
+
+-- Warrant canary observations, one row per distinct signed text per site
+CREATE TABLE IF NOT EXISTS canaries (
+    id BIGSERIAL PRIMARY KEY,
+    domain TEXT NOT NULL,
+    url TEXT NOT NULL,
+    content_hash VARCHAR(64) NOT NULL,
+    canary_date DATE,
+    verified BOOLEAN NOT NULL DEFAULT FALSE,
+    signer_fingerprint TEXT,
+    first_seen_at TIMESTAMPTZ DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(domain, content_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_canaries_domain ON canaries(domain, canary_date DESC);
+
+-- Latest canary per site, for the dashboards
+CREATE OR REPLACE VIEW canary_latest AS
+SELECT DISTINCT ON (domain)
+    domain, url, canary_date, verified, signer_fingerprint, last_seen_at,
+    CURRENT_DATE - canary_date AS age_days
+FROM canaries
+WHERE canary_date IS NOT NULL
+ORDER BY domain, canary_date DESC;
+
+-- Front-page fingerprints for phishing-clone detection
+CREATE TABLE IF NOT EXISTS site_fingerprints (
+    domain TEXT PRIMARY KEY,
+    network VARCHAR(20) NOT NULL,
+    url TEXT NOT NULL,
+    content_simhash BIGINT NOT NULL,
+    dom_simhash BIGINT NOT NULL,
+    first_seen_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Output of the clone analyzer (rebuilt on every run)
+CREATE TABLE IF NOT EXISTS suspected_clones (
+    id BIGSERIAL PRIMARY KEY,
+    clone_domain TEXT NOT NULL UNIQUE,
+    canonical_domain TEXT NOT NULL,
+    content_distance INT NOT NULL,
+    dom_distance INT NOT NULL,
+    vanity_prefix_len INT NOT NULL,
+    reason VARCHAR(30) NOT NULL,          -- near_identical_content, vanity_prefix_dom, transitive
+    canonical_basis VARCHAR(30) NOT NULL, -- trusted_provenance, earliest_seen
+    detected_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_suspected_clones_canonical ON suspected_clones(canonical_domain);
+
+CREATE TABLE IF NOT EXISTS clone_entity_diffs (
+    id BIGSERIAL PRIMARY KEY,
+    clone_domain TEXT NOT NULL,
+    canonical_domain TEXT NOT NULL,
+    entity_type VARCHAR(30) NOT NULL,
+    value TEXT NOT NULL,
+    side VARCHAR(20) NOT NULL, -- clone_only, canonical_only
+    substituted BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS idx_clone_diffs_clone ON clone_entity_diffs(clone_domain);
+
+-- URLs held back by the frontier's crawler-trap detection
+CREATE TABLE IF NOT EXISTS quarantined_urls (
+    id BIGSERIAL PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,
+    network VARCHAR(20) NOT NULL,
+    domain TEXT NOT NULL,
+    reason VARCHAR(30) NOT NULL, -- repeated_path_segments, growing_query, calendar, param_explosion, low_novelty
+    quarantined_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_urls_domain ON quarantined_urls(domain);
+CREATE INDEX IF NOT EXISTS idx_quarantined_urls_reason ON quarantined_urls(reason);
+
+-- Learned crawl yield per source page, domain and URL pattern (focused crawling)
+CREATE TABLE IF NOT EXISTS yield_scores (
+    kind VARCHAR(20) NOT NULL, -- source_page, domain, pattern
+    key TEXT NOT NULL,
+    pages BIGINT NOT NULL DEFAULT 0,
+    new_domains BIGINT NOT NULL DEFAULT 0,
+    new_entities BIGINT NOT NULL DEFAULT 0,
+    new_correlations BIGINT NOT NULL DEFAULT 0,
+    errors BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (kind, key)
+);
+
+-- Topic relevance of each page (focused crawling); NULL when no topic profile is configured
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS relevance DOUBLE PRECISION;
+CREATE INDEX IF NOT EXISTS idx_pages_relevance ON pages(relevance) WHERE relevance IS NOT NULL;
+
+-- Revisit schedule: per-URL change history and when to recrawl it next
+CREATE TABLE IF NOT EXISTS page_revisits (
+    url TEXT PRIMARY KEY,
+    network VARCHAR(20) NOT NULL,
+    domain TEXT NOT NULL,
+    content_hash VARCHAR(64) NOT NULL, -- raw_html_hash of the latest visit
+    first_visit_at TIMESTAMPTZ NOT NULL,
+    last_visit_at TIMESTAMPTZ NOT NULL,
+    next_visit_at TIMESTAMPTZ NOT NULL,
+    visits INTEGER NOT NULL DEFAULT 1,
+    changes INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_page_revisits_due ON page_revisits(network, next_visit_at);
+
+-- Lookup by URL: content-addressed (immutable) pages are fetched only once
+CREATE INDEX IF NOT EXISTS idx_pages_url ON pages(url);
+
+-- Conditional re-fetch: validators of each stored copy, and when a 304 last confirmed it
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS etag TEXT;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS last_modified TEXT;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS last_seen_at TIMESTAMPTZ;
+
+-- Encoding each page was decoded from before parsing (raw_html is stored as UTF-8)
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS encoding VARCHAR(40);
+
+-- Statistical language identification (language = declared <html lang>), the language
+-- mix of the page as {"ru": 0.7, "en": 0.3}, and full-text search in the page's language
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS detected_language VARCHAR(10);
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS language_confidence DOUBLE PRECISION;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS languages JSONB;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS text_search_config VARCHAR(20);
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS search_vector TSVECTOR;
+CREATE INDEX IF NOT EXISTS idx_pages_detected_language ON pages(detected_language);
+CREATE INDEX IF NOT EXISTS idx_pages_languages ON pages USING gin(languages);
+CREATE INDEX IF NOT EXISTS idx_pages_search_vector ON pages USING gin(search_vector);
+
+-- RSS/Atom feeds found while crawling, polled for new items on their own schedule
+CREATE TABLE IF NOT EXISTS feeds (
+    url TEXT PRIMARY KEY,
+    network VARCHAR(20) NOT NULL,
+    domain TEXT NOT NULL,
+    format VARCHAR(10) NOT NULL, -- rss | atom
+    title TEXT,
+    item_count INTEGER NOT NULL DEFAULT 0,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_polled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    next_poll_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_feeds_due ON feeds(network, next_poll_at);
+
+CREATE TABLE IF NOT EXISTS feed_items (
+    id BIGSERIAL PRIMARY KEY,
+    feed_url TEXT NOT NULL REFERENCES feeds(url) ON DELETE CASCADE,
+    link TEXT NOT NULL,
+    title TEXT,
+    guid TEXT,
+    published_at TIMESTAMPTZ,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(feed_url, link)
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_items_published ON feed_items(published_at DESC);
+
+-- Metadata of documents (PDFs, images, office files) found while crawling; fields holds
+-- {"author": ["..."], "creator_tool": ["..."], ...} (a key can repeat: Info and XMP disagree)
+CREATE TABLE IF NOT EXISTS document_metadata (
+    id BIGSERIAL PRIMARY KEY,
+    page_id BIGINT REFERENCES pages(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    metadata_type VARCHAR(20) NOT NULL, -- pdf | image | office | archive
+    size_bytes BIGINT NOT NULL,
+    fields JSONB NOT NULL DEFAULT '{}',
+    found_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(page_id, url)
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_metadata_domain ON document_metadata(domain);
+CREATE INDEX IF NOT EXISTS idx_document_metadata_fields ON document_metadata USING gin(fields);
+
+-- Archive file listings: [{"path", "size", "compressed_size", "modified_at", "is_dir", "encrypted"}]
+ALTER TABLE document_metadata ADD COLUMN IF NOT EXISTS entries JSONB NOT NULL DEFAULT '[]';
+
+-- Raw response bodies kept in the blob store (<data_dir>/blobs/ab/cd/<hash>.zst), one row
+-- per distinct body; identical bodies served by mirrors or re-fetches share a row
+CREATE TABLE IF NOT EXISTS blobs (
+    hash VARCHAR(64) PRIMARY KEY, -- SHA-256 of the uncompressed body
+    size_bytes BIGINT NOT NULL,
+    stored_bytes BIGINT NOT NULL, -- zstd-compressed size on disk
+    content_type TEXT,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Every URL a blob was fetched from (pages and non-page files alike)
+CREATE TABLE IF NOT EXISTS blob_urls (
+    url TEXT NOT NULL,
+    hash VARCHAR(64) NOT NULL REFERENCES blobs(hash) ON DELETE CASCADE,
+    network VARCHAR(20) NOT NULL,
+    domain TEXT NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (url, hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_blob_urls_hash ON blob_urls(hash);
+
+-- Pages whose body is in the blob store reference it here instead of keeping raw_html
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS body_hash VARCHAR(64);
+CREATE INDEX IF NOT EXISTS idx_pages_body_hash ON pages(body_hash) WHERE body_hash IS NOT NULL;
+
+-- Canonical form of pages.url (the frontier's dedup key); pages.url keeps the URL as fetched.
+-- Rows stored before this column held the canonical form in url
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS canonical_url TEXT;
+UPDATE pages SET canonical_url = url WHERE canonical_url IS NULL;
+CREATE INDEX IF NOT EXISTS idx_pages_canonical_url ON pages(canonical_url);
+
+-- Revisits re-enter the crawl as the job that first fetched the page: the URL as fetched
+-- (page_revisits.url is the canonical key), its depth, domain hops and referrer.
+-- Rows scheduled before these columns fall back to url at depth 0
+ALTER TABLE page_revisits ADD COLUMN IF NOT EXISTS fetch_url TEXT;
+ALTER TABLE page_revisits ADD COLUMN IF NOT EXISTS depth INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE page_revisits ADD COLUMN IF NOT EXISTS domain_hops INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE page_revisits ADD COLUMN IF NOT EXISTS source_url TEXT;
+
+-- Feeds are polled as the job that first fetched them (see page_revisits.fetch_url)
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS fetch_url TEXT;
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS depth INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS domain_hops INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS source_url TEXT;
+
+-- IPNS name history: every root CID a mutable ipns:// name resolved to, so past
+-- snapshots of a republished site stay addressable as ipfs://<root_cid>
+CREATE TABLE IF NOT EXISTS ipns_resolutions (
+    name TEXT NOT NULL, -- canonical IPNS name (CIDv1 base36 key or DNSLink domain)
+    root_cid TEXT NOT NULL, -- CIDv1 base32
+    network VARCHAR(20) NOT NULL,
+    times_seen INTEGER NOT NULL DEFAULT 1,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (name, root_cid)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ipns_resolutions_last_seen ON ipns_resolutions(name, last_seen_at DESC);
+
+-- Network of the driver that routes a link's target (NULL: no enabled driver handles it).
+-- Links stored before this column existed are labelled once from their per-network flags
+-- when the column is added; later runs leave the column alone
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'links' AND column_name = 'network'
+    ) THEN
+        ALTER TABLE links ADD COLUMN network VARCHAR(20);
+        UPDATE links SET network = CASE
+                WHEN is_onion THEN 'tor'
+                WHEN is_i2p THEN 'i2p'
+                WHEN is_zeronet THEN 'zeronet'
+                WHEN is_hyphanet THEN 'hyphanet'
+                WHEN is_lokinet THEN 'lokinet'
+            END
+        WHERE is_onion OR is_i2p OR is_zeronet OR is_hyphanet OR is_lokinet;
+    END IF;
+END $$;
+CREATE INDEX IF NOT EXISTS idx_links_network ON links(network);
+
+-- Language the page's search_vector was built in (PageMetadata::effective_language:
+-- detected when confident, else declared), so language filters follow the same rule.
+-- Pages stored before this column existed are labelled once when the column is added
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'pages' AND column_name = 'effective_language'
+    ) THEN
+        ALTER TABLE pages ADD COLUMN effective_language VARCHAR(10);
+        UPDATE pages SET effective_language = CASE
+                WHEN detected_language IS NOT NULL AND language_confidence >= 0.5 THEN detected_language
+                WHEN language IS NOT NULL THEN lower(trim(split_part(replace(language, '_', '-'), '-', 1)))
+                ELSE detected_language
+            END
+        WHERE detected_language IS NOT NULL OR language IS NOT NULL;
+    END IF;
+END $$;
+CREATE INDEX IF NOT EXISTS idx_pages_effective_language ON pages(effective_language);
@@ -0,0 +1,123 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+/// Distinguishes temp files of concurrent writes within this process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A body written to (or already in) the blob store.
+#[derive(Debug, Clone)]
+pub struct StoredBlob {
+    /// SHA-256 of the uncompressed body, lowercase hex
+    pub hash: String,
+    pub size_bytes: usize,
+    /// Size of the compressed file on disk
+    pub stored_bytes: usize,
+    /// False when an identical body was already stored
+    pub created: bool,
+}
+
+/// Content-addressed store of raw response bodies: `<root>/ab/cd/<sha256>.zst`, one
+/// zstd-compressed file per distinct body. Blocking; call from `spawn_blocking`.
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+    compression_level: i32,
+}
+
+impl BlobStore {
+    /// Open (and create) the store at `<data_dir>/blobs`.
+    pub fn open(data_dir: impl AsRef<Path>, compression_level: i32) -> Result<Self> {
+        let root = data_dir.as_ref().join("blobs");
+        fs::create_dir_all(&root).with_context(|| format!("creating blob store {}", root.display()))?;
+        Ok(Self { root, compression_level })
+    }
+
+    pub fn hash(body: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(body))
+    }
+
+    /// Store a body unless an identical one is already there.
+    pub fn put(&self, body: &[u8]) -> Result<StoredBlob> {
+        let hash = Self::hash(body);
+        let path = self.path(&hash)?;
+        if let Ok(existing) = fs::metadata(&path) {
+            return Ok(StoredBlob {
+                hash,
+                size_bytes: body.len(),
+                stored_bytes: existing.len() as usize,
+                created: false,
+            });
+        }
+
+        let compressed = zstd::encode_all(body, self.compression_level)?;
+        let dir = path.parent().expect("blob path has a parent");
+        fs::create_dir_all(dir)?;
+        // Write then rename, so a crash never leaves a truncated blob under its final name.
+        // The temp name is unique per write: workers storing the same body concurrently
+        // must not write into each other's file
+        let tmp = dir.join(format!(
+            ".{}.{}.{}.tmp",
+            hash,
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = fs::File::create(&tmp).and_then(|mut file| {
+            file.write_all(&compressed)?;
+            file.sync_all()
+        });
+        if let Err(e) = written {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        if let Err(e) = fs::rename(&tmp, &path) {
+            let _ = fs::remove_file(&tmp);
+            // Another writer stored the same body first: identical content, same result
+            if let Ok(existing) = fs::metadata(&path) {
+                return Ok(StoredBlob {
+                    hash,
+                    size_bytes: body.len(),
+                    stored_bytes: existing.len() as usize,
+                    created: false,
+                });
+            }
+            return Err(e.into());
+        }
+
+        Ok(StoredBlob {
+            hash,
+            size_bytes: body.len(),
+            stored_bytes: compressed.len(),
+            created: true,
+        })
+    }
+
+    /// The body stored under a hash, or None if there is none. The content is checked
+    /// against its hash.
+    pub fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(hash)?;
+        let compressed = match fs::read(&path) {
+            Ok(compressed) => compressed,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let body = zstd::decode_all(compressed.as_slice())
+            .with_context(|| format!("decompressing blob {}", path.display()))?;
+        if Self::hash(&body) != hash.to_ascii_lowercase() {
+            bail!("blob {} is corrupt: content does not match its hash", hash);
+        }
+        Ok(Some(body))
+    }
+
+    fn path(&self, hash: &str) -> Result<PathBuf> {
+        if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!("not a SHA-256 hash: {}", hash);
+        }
+        let hash = hash.to_ascii_lowercase();
+        Ok(self.root.join(&hash[..2]).join(&hash[2..4]).join(format!("{}.zst", hash)))
+    }
+}
@@ -1,9 +1,14 @@
 use anyhow::Result;
 use sqlx::postgres::PgPoolOptions;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use tracing::info;
 
-use darkscraper_core::PageData;
+use darkscraper_core::language::text_search_config;
+use darkscraper_core::{Feed, PageData, RevisitTarget, Validators};
+
+pub mod blob;
+
+pub use blob::{BlobStore, StoredBlob};
 
 pub struct Storage {
     pool: PgPool,
@@ -36,10 +41,23 @@ impl Storage {
     }
 
     pub async fn store_page(&self, page: &PageData) -> Result<i64> {
+        // Language mix as {"ru": 0.7, "en": 0.3}; the text-search configuration follows the page language
+        let languages: serde_json::Map<String, serde_json::Value> = page
+            .metadata
+            .languages
+            .iter()
+            .map(|(lang, share)| (lang.clone(), serde_json::Value::from(*share)))
+            .collect();
+        let effective_language = page.metadata.effective_language();
+        let search_config = effective_language
+            .as_deref()
+            .map_or("simple", text_search_config);
+
         let row: (i64,) = sqlx::query_as(
-            r#"INSERT INTO pages (url, final_url, network, domain, title, body_text, raw_html, raw_html_hash, status_code, content_type, server_header, language, has_login_form, response_time_ms, fetched_at)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-               ON CONFLICT (url, fetched_at) DO UPDATE SET body_text = EXCLUDED.body_text, raw_html = EXCLUDED.raw_html
+            r#"INSERT INTO pages (url, final_url, network, domain, title, body_text, raw_html, raw_html_hash, status_code, content_type, server_header, language, has_login_form, response_time_ms, fetched_at, relevance, etag, last_modified, last_seen_at, encoding, detected_language, language_confidence, languages, text_search_config, search_vector, body_hash, canonical_url, effective_language)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $15, $19, $20, $21, $22::jsonb, $23,
+                       to_tsvector($23::regconfig, COALESCE($5, '') || ' ' || LEFT(COALESCE($6, ''), 200000)), $24, $25, $26)
+               ON CONFLICT (url, fetched_at) DO UPDATE SET body_text = EXCLUDED.body_text, raw_html = EXCLUDED.raw_html, relevance = EXCLUDED.relevance, search_vector = EXCLUDED.search_vector, text_search_config = EXCLUDED.text_search_config, effective_language = EXCLUDED.effective_language, body_hash = EXCLUDED.body_hash
                RETURNING id"#,
         )
         .bind(&page.url)
@@ -48,7 +66,8 @@ impl Storage {
         .bind(&page.domain)
         .bind(&page.title)
         .bind(&page.body_text)
-        .bind(&page.raw_html)
+        // A body in the blob store is referenced by hash, not duplicated inline
+        .bind(page.body_hash.is_none().then_some(&page.raw_html))
         .bind(&page.raw_html_hash)
         .bind(page.status_code as i32)
         .bind(&page.content_type)
@@ -57,6 +76,17 @@ impl Storage {
         .bind(page.metadata.has_login_form)
         .bind(page.response_time_ms as i32)
         .bind(page.fetched_at)
+        .bind(page.relevance)
+        .bind(&page.metadata.etag)
+        .bind(&page.metadata.last_modified)
+        .bind(&page.encoding)
+        .bind(&page.metadata.detected_language)
+        .bind(page.metadata.language_confidence)
+        .bind(serde_json::Value::Object(languages).to_string())
+        .bind(search_config)
+        .bind(&page.body_hash)
+        .bind(page.key())
+        .bind(&effective_language)
         .fetch_one(&self.pool)
         .await?;
 
@@ -92,28 +122,8 @@ impl Storage {
         // Store entities (batch UNNEST insert)
         {
             let now = chrono::Utc::now();
-            let entity_groups: Vec<(&str, &[String])> = vec![
-                ("email", &page.entities.emails),
-                ("onion_address", &page.entities.onion_addresses),
-                ("i2p_address", &page.entities.i2p_addresses),
-                ("bitcoin", &page.entities.bitcoin_addresses),
-                ("monero", &page.entities.monero_addresses),
-                ("ethereum", &page.entities.ethereum_addresses),
-                ("phone", &page.entities.phone_numbers),
-                ("pgp_fingerprint", &page.entities.pgp_fingerprints),
-                ("username", &page.entities.usernames),
-            ];
-
-            let mut all_types: Vec<String> = Vec::new();
-            let mut all_values: Vec<String> = Vec::new();
-            let mut all_times: Vec<chrono::DateTime<chrono::Utc>> = Vec::new();
-            for (entity_type, values) in entity_groups {
-                for value in values.iter() {
-                    all_types.push(entity_type.to_string());
-                    all_values.push(value.clone());
-                    all_times.push(now);
-                }
-            }
+            let (all_types, all_values) = entity_rows(page);
+            let all_times: Vec<chrono::DateTime<chrono::Utc>> = vec![now; all_types.len()];
             if !all_types.is_empty() {
                 sqlx::query(
                     "INSERT INTO entities (page_id, entity_type, value, found_at)
@@ -137,6 +147,7 @@ impl Storage {
             let mut all_zeronet: Vec<bool> = Vec::new();
             let mut all_hyphanet: Vec<bool> = Vec::new();
             let mut all_lokinet: Vec<bool> = Vec::new();
+            let mut all_networks: Vec<Option<String>> = Vec::new();
             for link in &page.links {
                 all_targets.push(link.url.clone());
                 all_anchors.push(link.anchor_text.clone().unwrap_or_default());
@@ -145,10 +156,11 @@ impl Storage {
                 all_zeronet.push(link.is_zeronet);
                 all_hyphanet.push(link.is_hyphanet);
                 all_lokinet.push(link.is_lokinet);
+                all_networks.push(link.network.clone());
             }
             sqlx::query(
-                "INSERT INTO links (source_page_id, target_url, anchor_text, is_onion, is_i2p, is_zeronet, is_hyphanet, is_lokinet)
-                 SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::bool[], $5::bool[], $6::bool[], $7::bool[], $8::bool[])"
+                "INSERT INTO links (source_page_id, target_url, anchor_text, is_onion, is_i2p, is_zeronet, is_hyphanet, is_lokinet, network)
+                 SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::bool[], $5::bool[], $6::bool[], $7::bool[], $8::bool[], $9::varchar[])"
             )
             .bind(page_id)
             .bind(&all_targets)
@@ -158,6 +170,7 @@ impl Storage {
             .bind(&all_zeronet)
             .bind(&all_hyphanet)
             .bind(&all_lokinet)
+            .bind(&all_networks)
             .execute(&self.pool)
             .await?;
         }
@@ -165,6 +178,27 @@ impl Storage {
         Ok(page_id)
     }
 
+    /// Count the page's entities (type + value) not yet stored for any page.
+    /// Call before `store_page` to measure what the page newly contributed.
+    pub async fn count_new_entities(&self, page: &PageData) -> Result<i64> {
+        let (types, values) = entity_rows(page);
+        if types.is_empty() {
+            return Ok(0);
+        }
+        let row: (i64,) = sqlx::query_as(
+            "SELECT COUNT(DISTINCT (t.entity_type, t.value))
+             FROM UNNEST($1::varchar[], $2::text[]) AS t(entity_type, value)
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM entities e WHERE e.entity_type = t.entity_type AND e.value = t.value
+             )",
+        )
+        .bind(&types)
+        .bind(&values)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.0)
+    }
+
     pub async fn get_page_count(&self) -> Result<i64> {
         let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pages")
             .fetch_one(&self.pool)
@@ -189,8 +223,8 @@ impl Storage {
         domain: &str,
         correlation_type: &str,
         value: &str,
-    ) -> Result<()> {
-        sqlx::query(
+    ) -> Result<bool> {
+        let result = sqlx::query(
             "INSERT INTO correlations (domain, correlation_type, value) VALUES ($1, $2, $3) ON CONFLICT (domain, correlation_type, value) DO NOTHING",
         )
         .bind(domain)
@@ -198,9 +232,307 @@ impl Storage {
         .bind(value)
         .execute(&self.pool)
         .await?;
+        // false = already known
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record that `source_domain` declares `mirror_domain` as a mirror.
+    /// Re-declarations only bump `last_seen_at` (markets rotate mirrors constantly).
+    pub async fn store_mirror(
+        &self,
+        source_domain: &str,
+        mirror_domain: &str,
+        mirror_url: &str,
+        signal: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO mirrors (source_domain, mirror_domain, mirror_url, signal)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (source_domain, mirror_domain, signal) DO UPDATE SET
+                mirror_url = EXCLUDED.mirror_url,
+                last_seen_at = NOW()",
+        )
+        .bind(source_domain)
+        .bind(mirror_domain)
+        .bind(mirror_url)
+        .bind(signal)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a mirror listed in a PGP-signed mirror list.
+    /// `verified` = signature checked out against a key previously seen on `source_domain`.
+    pub async fn store_signed_mirror(
+        &self,
+        source_domain: &str,
+        mirror_domain: &str,
+        mirror_url: &str,
+        verified: bool,
+        signer_fingerprint: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO mirrors (source_domain, mirror_domain, mirror_url, signal, verified, signer_fingerprint)
+             VALUES ($1, $2, $3, 'pgp_signed_list', $4, $5)
+             ON CONFLICT (source_domain, mirror_domain, signal) DO UPDATE SET
+                mirror_url = EXCLUDED.mirror_url,
+                verified = EXCLUDED.verified OR mirrors.verified,
+                signer_fingerprint = COALESCE(EXCLUDED.signer_fingerprint, mirrors.signer_fingerprint),
+                last_seen_at = NOW()",
+        )
+        .bind(source_domain)
+        .bind(mirror_domain)
+        .bind(mirror_url)
+        .bind(verified)
+        .bind(signer_fingerprint)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store a public key block seen on a site.
+    pub async fn store_pgp_key(
+        &self,
+        domain: &str,
+        key_hash: &str,
+        fingerprint: &str,
+        armored: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO pgp_keys (domain, key_hash, fingerprint, armored)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (domain, key_hash) DO NOTHING",
+        )
+        .bind(domain)
+        .bind(key_hash)
+        .bind(fingerprint)
+        .bind(armored)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Load armored public keys previously seen on a site, either stored directly
+    /// or referenced by a `pgp_key_hash` correlation of that domain.
+    pub async fn load_pgp_keys(&self, domain: &str) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT DISTINCT armored FROM pgp_keys
+             WHERE domain = $1
+                OR key_hash IN (
+                    SELECT value FROM correlations
+                    WHERE domain = $1 AND correlation_type = 'pgp_key_hash'
+                )",
+        )
+        .bind(domain)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(armored,)| armored).collect())
+    }
+
+    /// Record a warrant canary observation. The same signed text seen again
+    /// only bumps `last_seen_at`, so rows track how the canary changes over time.
+    pub async fn store_canary(
+        &self,
+        domain: &str,
+        url: &str,
+        content_hash: &str,
+        canary_date: Option<chrono::NaiveDate>,
+        verified: bool,
+        signer_fingerprint: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO canaries (domain, url, content_hash, canary_date, verified, signer_fingerprint)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (domain, content_hash) DO UPDATE SET
+                verified = EXCLUDED.verified OR canaries.verified,
+                signer_fingerprint = COALESCE(EXCLUDED.signer_fingerprint, canaries.signer_fingerprint),
+                last_seen_at = NOW()",
+        )
+        .bind(domain)
+        .bind(url)
+        .bind(content_hash)
+        .bind(canary_date)
+        .bind(verified)
+        .bind(signer_fingerprint)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Sites whose most recent canary date is older than `stale_days`.
+    /// Returns (domain, canary_date, verified).
+    pub async fn get_stale_canaries(
+        &self,
+        stale_days: u32,
+    ) -> Result<Vec<(String, chrono::NaiveDate, bool)>> {
+        let rows: Vec<(String, chrono::NaiveDate, bool)> = sqlx::query_as(
+            "SELECT domain, canary_date, verified FROM canary_latest
+             WHERE age_days > $1
+             ORDER BY canary_date",
+        )
+        .bind(stale_days as i32)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Upsert the front-page fingerprint of a site (for clone detection).
+    pub async fn store_site_fingerprint(
+        &self,
+        domain: &str,
+        network: &str,
+        url: &str,
+        content_simhash: u64,
+        dom_simhash: u64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO site_fingerprints (domain, network, url, content_simhash, dom_simhash)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (domain) DO UPDATE SET
+                url = EXCLUDED.url,
+                content_simhash = EXCLUDED.content_simhash,
+                dom_simhash = EXCLUDED.dom_simhash,
+                updated_at = NOW()",
+        )
+        .bind(domain)
+        .bind(network)
+        .bind(url)
+        .bind(content_simhash as i64)
+        .bind(dom_simhash as i64)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
+    /// Load all site fingerprints.
+    /// Returns (domain, network, content_simhash, dom_simhash, first_seen_at).
+    pub async fn load_site_fingerprints(
+        &self,
+    ) -> Result<Vec<(String, String, u64, u64, chrono::DateTime<chrono::Utc>)>> {
+        let rows: Vec<(String, String, i64, i64, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+            "SELECT domain, network, content_simhash, dom_simhash, first_seen_at FROM site_fingerprints",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(d, n, c, dom, seen)| (d, n, c as u64, dom as u64, seen))
+            .collect())
+    }
+
+    /// Domains with strong provenance: both ends of verified signed mirror lists
+    /// and sites publishing verified canaries.
+    pub async fn load_verified_domains(&self) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT source_domain FROM mirrors WHERE verified
+             UNION SELECT mirror_domain FROM mirrors WHERE verified
+             UNION SELECT domain FROM canaries WHERE verified",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(d,)| d).collect())
+    }
+
+    /// Distinct (entity_type, value) pairs found on any page of a domain.
+    pub async fn load_domain_entities(&self, domain: &str) -> Result<Vec<(String, String)>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT DISTINCT e.entity_type, e.value
+             FROM entities e
+             JOIN pages p ON p.id = e.page_id
+             WHERE p.domain = $1",
+        )
+        .bind(domain)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Start a transaction for writes that must land together.
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>> {
+        Ok(self.pool.begin().await?)
+    }
+
+    /// Drop previous clone-analysis output before a new run. Runs inside the run's
+    /// transaction, so a failed run leaves the previous results in place.
+    pub async fn clear_clone_results(&self, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+        sqlx::query("DELETE FROM clone_entity_diffs")
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query("DELETE FROM suspected_clones")
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_suspected_clone(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        clone_domain: &str,
+        canonical_domain: &str,
+        content_distance: u32,
+        dom_distance: u32,
+        vanity_prefix_len: usize,
+        reason: &str,
+        canonical_basis: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO suspected_clones (clone_domain, canonical_domain, content_distance, dom_distance, vanity_prefix_len, reason, canonical_basis)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (clone_domain) DO UPDATE SET
+                canonical_domain = EXCLUDED.canonical_domain,
+                content_distance = EXCLUDED.content_distance,
+                dom_distance = EXCLUDED.dom_distance,
+                vanity_prefix_len = EXCLUDED.vanity_prefix_len,
+                reason = EXCLUDED.reason,
+                canonical_basis = EXCLUDED.canonical_basis,
+                detected_at = NOW()",
+        )
+        .bind(clone_domain)
+        .bind(canonical_domain)
+        .bind(content_distance as i32)
+        .bind(dom_distance as i32)
+        .bind(vanity_prefix_len as i32)
+        .bind(reason)
+        .bind(canonical_basis)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_clone_entity_diff(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        clone_domain: &str,
+        canonical_domain: &str,
+        entity_type: &str,
+        value: &str,
+        side: &str,
+        substituted: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO clone_entity_diffs (clone_domain, canonical_domain, entity_type, value, side, substituted)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(clone_domain)
+        .bind(canonical_domain)
+        .bind(entity_type)
+        .bind(value)
+        .bind(side)
+        .bind(substituted)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_mirror_count(&self) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM mirrors")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0)
+    }
+
     pub async fn get_correlation_count(&self) -> Result<i64> {
         let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM correlations")
             .fetch_one(&self.pool)
@@ -254,6 +586,89 @@ impl Storage {
         Ok(row.0 > 0)
     }
 
+    /// Check if a page has been stored under this canonical URL.
+    pub async fn has_page(&self, url: &str) -> Result<bool> {
+        let row: (bool,) = sqlx::query_as("SELECT EXISTS(SELECT 1 FROM pages WHERE canonical_url = $1)")
+            .bind(url)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0)
+    }
+
+    /// ETag / Last-Modified of the latest stored copy of a canonical URL (empty if never stored).
+    pub async fn load_validators(&self, url: &str) -> Result<Validators> {
+        let row: Option<(Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT etag, last_modified FROM pages WHERE canonical_url = $1 ORDER BY fetched_at DESC LIMIT 1",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await?;
+        let (etag, last_modified) = row.unwrap_or_default();
+        Ok(Validators { etag, last_modified })
+    }
+
+    /// A conditional re-fetch came back 304: bump `last_seen_at` of the latest stored
+    /// copy instead of storing it again. Returns that copy's content hash.
+    pub async fn touch_page(&self, url: &str) -> Result<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "UPDATE pages SET last_seen_at = NOW()
+             WHERE id = (SELECT id FROM pages WHERE canonical_url = $1 ORDER BY fetched_at DESC LIMIT 1)
+             RETURNING raw_html_hash",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(hash,)| hash))
+    }
+
+    /// Record a body written to (or found in) the blob store and the URL it was fetched from.
+    pub async fn record_blob(
+        &self,
+        blob: &StoredBlob,
+        content_type: Option<&str>,
+        url: &str,
+        network: &str,
+        domain: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO blobs (hash, size_bytes, stored_bytes, content_type)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (hash) DO UPDATE SET last_seen_at = NOW()",
+        )
+        .bind(&blob.hash)
+        .bind(blob.size_bytes as i64)
+        .bind(blob.stored_bytes as i64)
+        .bind(content_type)
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            "INSERT INTO blob_urls (url, hash, network, domain)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (url, hash) DO UPDATE SET last_seen_at = NOW()",
+        )
+        .bind(url)
+        .bind(&blob.hash)
+        .bind(network)
+        .bind(domain)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Blob store totals: (blobs, uncompressed bytes, bytes on disk, URLs referencing them).
+    pub async fn get_blob_stats(&self) -> Result<(i64, i64, i64, i64)> {
+        let row = sqlx::query_as(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(size_bytes), 0)::BIGINT,
+                    COALESCE(SUM(stored_bytes), 0)::BIGINT,
+                    (SELECT COUNT(*) FROM blob_urls)
+             FROM blobs",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
     /// Load all dead URLs into a set (for startup).
     pub async fn load_dead_urls(&self) -> Result<std::collections::HashSet<String>> {
         let rows: Vec<(String,)> = sqlx::query_as("SELECT url FROM dead_urls")
@@ -282,6 +697,358 @@ impl Storage {
         Ok(row.0)
     }
 
+    /// Record a URL quarantined as a suspected crawler trap.
+    pub async fn store_quarantined_url(
+        &self,
+        url: &str,
+        network: &str,
+        domain: &str,
+        reason: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO quarantined_urls (url, network, domain, reason)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (url) DO NOTHING",
+        )
+        .bind(url)
+        .bind(network)
+        .bind(domain)
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Quarantined URL counts per trap reason.
+    pub async fn get_quarantine_stats(&self) -> Result<Vec<(String, i64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT reason, COUNT(*) FROM quarantined_urls GROUP BY reason ORDER BY COUNT(*) DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Domains with the most quarantined URLs.
+    pub async fn get_top_trapped_domains(&self, limit: i64) -> Result<Vec<(String, i64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT domain, COUNT(*) FROM quarantined_urls GROUP BY domain ORDER BY COUNT(*) DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Upsert learned yield totals: (kind, key, pages, new_domains, new_entities, new_correlations, errors).
+    pub async fn store_yield_scores(&self, rows: &[(String, String, i64, i64, i64, i64, i64)]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let mut kinds = Vec::with_capacity(rows.len());
+        let mut keys = Vec::with_capacity(rows.len());
+        let mut counts: [Vec<i64>; 5] = Default::default();
+        for (kind, key, pages, domains, entities, correlations, errors) in rows {
+            kinds.push(kind.clone());
+            keys.push(key.clone());
+            for (column, value) in counts.iter_mut().zip([pages, domains, entities, correlations, errors]) {
+                column.push(*value);
+            }
+        }
+        sqlx::query(
+            "INSERT INTO yield_scores (kind, key, pages, new_domains, new_entities, new_correlations, errors)
+             SELECT * FROM UNNEST($1::varchar[], $2::text[], $3::bigint[], $4::bigint[], $5::bigint[], $6::bigint[], $7::bigint[])
+             ON CONFLICT (kind, key) DO UPDATE SET
+                pages = EXCLUDED.pages,
+                new_domains = EXCLUDED.new_domains,
+                new_entities = EXCLUDED.new_entities,
+                new_correlations = EXCLUDED.new_correlations,
+                errors = EXCLUDED.errors,
+                updated_at = NOW()",
+        )
+        .bind(&kinds)
+        .bind(&keys)
+        .bind(&counts[0])
+        .bind(&counts[1])
+        .bind(&counts[2])
+        .bind(&counts[3])
+        .bind(&counts[4])
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Load all learned yield totals (same tuple layout as `store_yield_scores`).
+    pub async fn load_yield_scores(&self) -> Result<Vec<(String, String, i64, i64, i64, i64, i64)>> {
+        let rows = sqlx::query_as(
+            "SELECT kind, key, pages, new_domains, new_entities, new_correlations, errors FROM yield_scores",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Revisit history of a URL: (content_hash, first_visit_at, visits, changes).
+    pub async fn get_revisit_state(
+        &self,
+        url: &str,
+    ) -> Result<Option<(String, chrono::DateTime<chrono::Utc>, i32, i32)>> {
+        let row = sqlx::query_as(
+            "SELECT content_hash, first_visit_at, visits, changes FROM page_revisits WHERE url = $1",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Record a visit and when the page is due again.
+    pub async fn store_revisit(
+        &self,
+        target: &RevisitTarget,
+        content_hash: &str,
+        first_visit_at: chrono::DateTime<chrono::Utc>,
+        visits: i32,
+        changes: i32,
+        next_visit_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO page_revisits (url, network, domain, content_hash, first_visit_at, last_visit_at, next_visit_at, visits, changes,
+                                         fetch_url, depth, domain_hops, source_url)
+             VALUES ($1, $2, $3, $4, $5, NOW(), $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (url) DO UPDATE SET
+                content_hash = EXCLUDED.content_hash,
+                fetch_url = EXCLUDED.fetch_url,
+                depth = LEAST(page_revisits.depth, EXCLUDED.depth),
+                domain_hops = LEAST(page_revisits.domain_hops, EXCLUDED.domain_hops),
+                source_url = COALESCE(page_revisits.source_url, EXCLUDED.source_url),
+                last_visit_at = NOW(),
+                next_visit_at = EXCLUDED.next_visit_at,
+                visits = EXCLUDED.visits,
+                changes = EXCLUDED.changes",
+        )
+        .bind(&target.key)
+        .bind(&target.network)
+        .bind(&target.domain)
+        .bind(content_hash)
+        .bind(first_visit_at)
+        .bind(next_visit_at)
+        .bind(visits)
+        .bind(changes)
+        .bind(&target.url)
+        .bind(target.depth as i32)
+        .bind(target.domain_hops as i32)
+        .bind(&target.source_url)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Claim up to `limit` pages of a network that are due for a revisit, most overdue first.
+    /// Claimed pages are pushed `lease_seconds` into the future so they aren't claimed twice;
+    /// the next visit overwrites that with the real schedule.
+    pub async fn claim_due_revisits(&self, network: &str, limit: i64, lease_seconds: i64) -> Result<Vec<RevisitTarget>> {
+        let rows: Vec<RevisitRow> = sqlx::query_as(
+            "UPDATE page_revisits SET next_visit_at = NOW() + make_interval(secs => $3)
+             WHERE url IN (
+                 SELECT url FROM page_revisits
+                 WHERE network = $1 AND next_visit_at <= NOW()
+                 ORDER BY next_visit_at
+                 LIMIT $2
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING url, COALESCE(fetch_url, url), network, domain, depth, domain_hops, source_url",
+        )
+        .bind(network)
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(revisit_target).collect())
+    }
+
+    /// Record a fetched RSS/Atom feed and when it is due for its next poll.
+    pub async fn register_feed(
+        &self,
+        target: &RevisitTarget,
+        title: Option<&str>,
+        feed: &Feed,
+        poll_interval_seconds: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO feeds (url, network, domain, format, title, item_count, next_poll_at,
+                                fetch_url, depth, domain_hops, source_url)
+             VALUES ($1, $2, $3, $4, $5, $6, NOW() + make_interval(secs => $7), $8, $9, $10, $11)
+             ON CONFLICT (url) DO UPDATE SET
+                format = EXCLUDED.format,
+                fetch_url = EXCLUDED.fetch_url,
+                depth = LEAST(feeds.depth, EXCLUDED.depth),
+                domain_hops = LEAST(feeds.domain_hops, EXCLUDED.domain_hops),
+                source_url = COALESCE(feeds.source_url, EXCLUDED.source_url),
+                title = COALESCE(EXCLUDED.title, feeds.title),
+                item_count = EXCLUDED.item_count,
+                last_polled_at = NOW(),
+                next_poll_at = EXCLUDED.next_poll_at",
+        )
+        .bind(&target.key)
+        .bind(&target.network)
+        .bind(&target.domain)
+        .bind(&feed.format)
+        .bind(title)
+        .bind(feed.items.len() as i32)
+        .bind(poll_interval_seconds as f64)
+        .bind(&target.url)
+        .bind(target.depth as i32)
+        .bind(target.domain_hops as i32)
+        .bind(&target.source_url)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store a feed's items; returns how many were not seen in an earlier poll.
+    pub async fn store_feed_items(&self, feed_url: &str, feed: &Feed) -> Result<u64> {
+        let items: Vec<_> = feed.items.iter().filter(|i| i.link.is_some()).collect();
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let links: Vec<String> = items.iter().filter_map(|i| i.link.clone()).collect();
+        let titles: Vec<Option<String>> = items.iter().map(|i| i.title.clone()).collect();
+        let guids: Vec<Option<String>> = items.iter().map(|i| i.guid.clone()).collect();
+        let published: Vec<Option<chrono::DateTime<chrono::Utc>>> =
+            items.iter().map(|i| i.published_at).collect();
+        let result = sqlx::query(
+            "INSERT INTO feed_items (feed_url, link, title, guid, published_at)
+             SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::text[], $5::timestamptz[])
+             ON CONFLICT (feed_url, link) DO NOTHING",
+        )
+        .bind(feed_url)
+        .bind(&links)
+        .bind(&titles)
+        .bind(&guids)
+        .bind(&published)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Push a known feed's next poll out after a 304. Returns false if `url` isn't a feed.
+    pub async fn touch_feed(&self, url: &str, poll_interval_seconds: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE feeds SET last_polled_at = NOW(), next_poll_at = NOW() + make_interval(secs => $2)
+             WHERE url = $1",
+        )
+        .bind(url)
+        .bind(poll_interval_seconds as f64)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claim up to `limit` feeds of a network that are due for a poll, most overdue first
+    /// (leased like `claim_due_revisits`).
+    pub async fn claim_due_feeds(&self, network: &str, limit: i64, lease_seconds: i64) -> Result<Vec<RevisitTarget>> {
+        let rows: Vec<RevisitRow> = sqlx::query_as(
+            "UPDATE feeds SET next_poll_at = NOW() + make_interval(secs => $3)
+             WHERE url IN (
+                 SELECT url FROM feeds
+                 WHERE network = $1 AND next_poll_at <= NOW()
+                 ORDER BY next_poll_at
+                 LIMIT $2
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING url, COALESCE(fetch_url, url), network, domain, depth, domain_hops, source_url",
+        )
+        .bind(network)
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(revisit_target).collect())
+    }
+
+    /// Record the root CID an IPNS name resolved to. Returns the CID the name last
+    /// resolved to when it was republished since (None on a first or unchanged resolution).
+    pub async fn record_ipns_resolution(&self, name: &str, root_cid: &str, network: &str) -> Result<Option<String>> {
+        let previous: Option<(String,)> = sqlx::query_as(
+            "SELECT root_cid FROM ipns_resolutions WHERE name = $1 ORDER BY last_seen_at DESC LIMIT 1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+        sqlx::query(
+            "INSERT INTO ipns_resolutions (name, root_cid, network)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (name, root_cid) DO UPDATE SET last_seen_at = NOW(), times_seen = ipns_resolutions.times_seen + 1",
+        )
+        .bind(name)
+        .bind(root_cid)
+        .bind(network)
+        .execute(&self.pool)
+        .await?;
+        Ok(previous.map(|(cid,)| cid).filter(|cid| cid != root_cid))
+    }
+
+    /// Store the metadata record of a document linked to the page it was fetched as
+    /// (or embedded in). Fields are grouped by key: {"author": ["a", "b"], ...}.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_document_metadata(
+        &self,
+        page_id: i64,
+        url: &str,
+        domain: &str,
+        content_type: &str,
+        metadata_type: &str,
+        size_bytes: usize,
+        fields: &[(String, String)],
+        entries: &serde_json::Value,
+    ) -> Result<()> {
+        let mut grouped = serde_json::Map::new();
+        for (key, value) in fields {
+            if let serde_json::Value::Array(values) = grouped
+                .entry(key.clone())
+                .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+            {
+                values.push(serde_json::Value::String(value.clone()));
+            }
+        }
+        sqlx::query(
+            "INSERT INTO document_metadata (page_id, url, domain, content_type, metadata_type, size_bytes, fields, entries)
+             VALUES ($1, $2, $3, $4, $5, $6, $7::jsonb, $8::jsonb)
+             ON CONFLICT (page_id, url) DO UPDATE SET
+                content_type = EXCLUDED.content_type,
+                size_bytes = EXCLUDED.size_bytes,
+                fields = EXCLUDED.fields,
+                entries = EXCLUDED.entries,
+                found_at = NOW()",
+        )
+        .bind(page_id)
+        .bind(url)
+        .bind(domain)
+        .bind(content_type)
+        .bind(metadata_type)
+        .bind(size_bytes as i64)
+        .bind(serde_json::Value::Object(grouped).to_string())
+        .bind(entries.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Revisit schedule per network: (network, scheduled URLs, due now, changed at least once).
+    pub async fn get_revisit_stats(&self) -> Result<Vec<(String, i64, i64, i64)>> {
+        let rows = sqlx::query_as(
+            "SELECT network,
+                    COUNT(*),
+                    COUNT(*) FILTER (WHERE next_visit_at <= NOW()),
+                    COUNT(*) FILTER (WHERE changes > 0)
+             FROM page_revisits GROUP BY network ORDER BY network",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
     /// Load all previously crawled URLs from pages + dead_urls tables.
     /// Used at startup to prime the bloom filter so we don't re-crawl.
     pub async fn load_all_known_urls(&self) -> Result<Vec<String>> {
@@ -313,3 +1080,46 @@ impl Storage {
         &self.pool
     }
 }
+
+/// Flatten a page's entities into parallel (type, value) columns for UNNEST.
+fn entity_rows(page: &PageData) -> (Vec<String>, Vec<String>) {
+    let entity_groups: Vec<(&str, &[String])> = vec![
+        ("email", &page.entities.emails),
+        ("onion_address", &page.entities.onion_addresses),
+        ("i2p_address", &page.entities.i2p_addresses),
+        ("bitcoin", &page.entities.bitcoin_addresses),
+        ("monero", &page.entities.monero_addresses),
+        ("ethereum", &page.entities.ethereum_addresses),
+        ("phone", &page.entities.phone_numbers),
+        ("pgp_fingerprint", &page.entities.pgp_fingerprints),
+        ("username", &page.entities.usernames),
+        ("gps_coordinate", &page.entities.gps_coordinates),
+        ("camera_serial", &page.entities.camera_serials),
+    ];
+
+    let mut all_types: Vec<String> = Vec::new();
+    let mut all_values: Vec<String> = Vec::new();
+    for (entity_type, values) in entity_groups {
+        for value in values.iter() {
+            all_types.push(entity_type.to_string());
+            all_values.push(value.clone());
+        }
+    }
+    (all_types, all_values)
+}
+
+/// (url, fetch_url, network, domain, depth, domain_hops, source_url) as claimed for a
+/// revisit or feed poll.
+type RevisitRow = (String, String, String, String, i32, i32, Option<String>);
+
+fn revisit_target((key, url, network, domain, depth, domain_hops, source_url): RevisitRow) -> RevisitTarget {
+    RevisitTarget {
+        key,
+        url,
+        network,
+        domain,
+        depth: depth.max(0) as u32,
+        domain_hops: domain_hops.max(0) as u32,
+        source_url,
+    }
+}
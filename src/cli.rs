@@ -23,9 +23,37 @@ pub enum Commands {
         #[arg(long)]
         seed: Option<String>,
 
-        /// Maximum crawl depth
+        /// Maximum in-site link depth (overrides config for every network)
         #[arg(short, long)]
         depth: Option<u32>,
+
+        /// Maximum domain hops away from a seed (overrides config for every network)
+        #[arg(long)]
+        max_hops: Option<u32>,
+
+        /// Only crawl these domains and their subdomains (comma-separated, repeatable)
+        #[arg(long, value_delimiter = ',')]
+        allow_domain: Vec<String>,
+
+        /// Never crawl these domains or their subdomains (comma-separated, repeatable)
+        #[arg(long, value_delimiter = ',')]
+        deny_domain: Vec<String>,
+
+        /// Only crawl URLs whose path+query matches this regex (repeatable)
+        #[arg(long)]
+        include: Vec<String>,
+
+        /// Skip URLs whose path+query matches this regex (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Only crawl the hosts of the seed URLs
+        #[arg(long)]
+        stay_on_seeds: bool,
+
+        /// Only crawl these networks (comma-separated: tor,i2p,zeronet,hyphanet,lokinet)
+        #[arg(long, value_delimiter = ',')]
+        networks: Vec<String>,
     },
     /// Search crawled data
     Search {
@@ -41,12 +69,26 @@ pub enum Commands {
         #[arg(short = 't', long)]
         entity_type: Option<String>,
 
+        /// Only pages in this language (ISO 639-1, e.g. ru); stems the query in that language
+        #[arg(long)]
+        language: Option<String>,
+
         /// Max results
         #[arg(short, long, default_value = "20")]
         limit: i64,
     },
     /// Show crawl status and database stats
     Status,
+    /// Detect phishing clones of known sites and list suspected clones
+    Clones {
+        /// Only show clusters involving this domain (with entity differences)
+        #[arg(short, long)]
+        domain: Option<String>,
+
+        /// Max results
+        #[arg(short, long, default_value = "20")]
+        limit: i64,
+    },
     /// Export crawled data
     Export {
         /// Output format (json)
@@ -57,4 +99,22 @@ pub enum Commands {
         #[arg(short, long)]
         output: String,
     },
+    /// Raw response bodies in the blob store
+    Blob {
+        #[command(subcommand)]
+        command: BlobCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BlobCommands {
+    /// Write a stored body, by its SHA-256 hash, to a file or stdout
+    Get {
+        /// SHA-256 of the body (pages.body_hash, blobs.hash)
+        hash: String,
+
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
 }
@@ -1,20 +1,26 @@
+use std::collections::HashSet;
+
 use anyhow::Result;
 use darkscraper_core::config::AppConfig;
+use darkscraper_discovery::{CloneDetector, SiteFingerprint};
 use darkscraper_search::SearchEngine;
-use darkscraper_storage::Storage;
+use darkscraper_storage::{BlobStore, Storage};
+
+use crate::seeds::get_all_seeds;
 
 pub async fn run_search(
     config: AppConfig,
     query: Option<String>,
     entity: Option<String>,
     entity_type: Option<String>,
+    language: Option<String>,
     limit: i64,
 ) -> Result<()> {
     let storage = Storage::new(&config.database.postgres_url).await?;
     let search = SearchEngine::new(storage.pool().clone());
 
     if let Some(q) = query {
-        let results = search.search_text(&q, limit).await?;
+        let results = search.search_text(&q, language.as_deref(), limit).await?;
         println!("Found {} results:\n", results.len());
         for r in results {
             println!(
@@ -66,14 +72,159 @@ pub async fn run_status(config: AppConfig) -> Result<()> {
     let entities = storage.get_entity_count().await?;
     let links = storage.get_link_count().await?;
     let correlations = storage.get_correlation_count().await?;
+    let mirrors = storage.get_mirror_count().await?;
     let dead = storage.get_dead_url_count().await?;
 
     println!("Pages crawled:    {}", pages);
     println!("Entities found:   {}", entities);
     println!("Links discovered: {}", links);
     println!("Correlations:     {}", correlations);
+    println!("Declared mirrors: {}", mirrors);
     println!("Dead URLs:        {}", dead);
 
+    let quarantine = storage.get_quarantine_stats().await?;
+    if !quarantine.is_empty() {
+        let total: i64 = quarantine.iter().map(|(_, count)| count).sum();
+        println!("Quarantined URLs: {} (suspected crawler traps)", total);
+        for (reason, count) in quarantine {
+            println!("  {:<24} {}", reason, count);
+        }
+        for (domain, count) in storage.get_top_trapped_domains(5).await? {
+            println!("  top: {} ({})", domain, count);
+        }
+    }
+
+    if config.blobs.enabled {
+        let (blobs, size, stored, urls) = storage.get_blob_stats().await?;
+        println!(
+            "Blob store:       {} bodies from {} URLs, {} MiB ({} MiB on disk)",
+            blobs,
+            urls,
+            size / (1024 * 1024),
+            stored / (1024 * 1024)
+        );
+    }
+
+    let stale = storage
+        .get_stale_canaries(config.extraction.canary_stale_days)
+        .await?;
+    if !stale.is_empty() {
+        println!(
+            "Stale warrant canaries (> {} days):",
+            config.extraction.canary_stale_days
+        );
+        for (domain, date, verified) in stale {
+            let status = if verified { "verified" } else { "unverified" };
+            println!("  {} - {} ({})", domain, date, status);
+        }
+    }
+
+    let revisits = storage.get_revisit_stats().await?;
+    if !revisits.is_empty() {
+        println!("Revisit schedule:");
+        for (network, scheduled, due, changing) in revisits {
+            println!(
+                "  {:<10} {} scheduled, {} due, {} seen changing",
+                network, scheduled, due, changing
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run_clones(config: AppConfig, domain: Option<String>, limit: i64) -> Result<()> {
+    let storage = Storage::new(&config.database.postgres_url).await?;
+    storage.run_migrations().await?;
+
+    // Canonical sites are picked by provenance: our own seeds + verified signed mirrors
+    let mut trusted: HashSet<String> = get_all_seeds()
+        .iter()
+        .filter_map(|s| url::Url::parse(s).ok())
+        .filter_map(|u| u.host_str().map(|h| h.to_string()))
+        .collect();
+    trusted.extend(storage.load_verified_domains().await?);
+
+    let sites: Vec<SiteFingerprint> = storage
+        .load_site_fingerprints()
+        .await?
+        .into_iter()
+        .map(|(domain, network, content_simhash, dom_simhash, first_seen_at)| SiteFingerprint {
+            domain,
+            network,
+            content_simhash,
+            dom_simhash,
+            first_seen_at,
+        })
+        .collect();
+
+    let clones = CloneDetector::find_clones(&sites, &trusted);
+    let mut diffs = Vec::with_capacity(clones.len());
+    for clone in &clones {
+        let canonical_entities = storage.load_domain_entities(&clone.canonical_domain).await?;
+        let clone_entities = storage.load_domain_entities(&clone.clone_domain).await?;
+        diffs.push(CloneDetector::diff_entities(&canonical_entities, &clone_entities));
+    }
+
+    // Replace the previous results in one transaction: a failed run keeps the old ones
+    let mut tx = storage.begin().await?;
+    storage.clear_clone_results(&mut tx).await?;
+    for (clone, clone_diffs) in clones.iter().zip(&diffs) {
+        storage
+            .store_suspected_clone(
+                &mut tx,
+                &clone.clone_domain,
+                &clone.canonical_domain,
+                clone.content_distance,
+                clone.dom_distance,
+                clone.vanity_prefix_len,
+                &clone.reason,
+                &clone.canonical_basis,
+            )
+            .await?;
+        for diff in clone_diffs {
+            storage
+                .store_clone_entity_diff(
+                    &mut tx,
+                    &clone.clone_domain,
+                    &clone.canonical_domain,
+                    &diff.entity_type,
+                    &diff.value,
+                    &diff.side,
+                    diff.substituted,
+                )
+                .await?;
+        }
+    }
+    tx.commit().await?;
+
+    println!(
+        "Analyzed {} sites: {} suspected clones\n",
+        sites.len(),
+        clones.len()
+    );
+
+    let search = SearchEngine::new(storage.pool().clone());
+    let results = search.search_clones(domain.as_deref(), limit).await?;
+    for r in &results {
+        println!(
+            "  {} -> clone of {} ({}, canonical by {}, content dist {}, dom dist {}, prefix {})",
+            r.clone_domain,
+            r.canonical_domain,
+            r.reason,
+            r.canonical_basis,
+            r.content_distance,
+            r.dom_distance,
+            r.vanity_prefix_len
+        );
+        if domain.is_some() {
+            for d in search.clone_entity_diffs(&r.clone_domain).await? {
+                let marker = if d.substituted { " [substituted]" } else { "" };
+                println!("      {} {} = {}{}", d.side, d.entity_type, d.value, marker);
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -93,3 +244,18 @@ pub async fn run_export(config: AppConfig, format: &str, output: &str) -> Result
 
     Ok(())
 }
+
+pub fn run_blob_get(config: AppConfig, hash: &str, output: Option<&str>) -> Result<()> {
+    let store = BlobStore::open(&config.general.data_dir, config.blobs.compression_level)?;
+    let Some(body) = store.get(hash)? else {
+        anyhow::bail!("no blob {} in {}/blobs", hash, config.general.data_dir);
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, &body)?;
+            eprintln!("Wrote {} bytes to {}", body.len(), path);
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout().lock(), &body)?,
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
@@ -6,127 +6,177 @@ use anyhow::Result;
 use dashmap::{DashMap, DashSet};
 use tokio::signal;
 use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
-use darkscraper_core::{AppConfig, CrawlJob, FetchConfig, NetworkDriver, PageData};
+use darkscraper_core::ipfs;
+use darkscraper_core::{AppConfig, CrawlJob, FetchConfig, NetworkDriver, PageData, ProxyState, RevisitTarget};
 use darkscraper_discovery::{
-    Correlation, CorrelationEngine, FormSpider, InfraProber, PatternMutator, SourceMiner,
+    CloneDetector, Correlation, CorrelationEngine, DocumentMetadata, FormSpider, InfraProber, MetadataExtractor,
+    MirrorHarvester, MirrorLink, PatternMutator, PgpVerifier, SourceMiner,
 };
-use darkscraper_frontier::CrawlFrontier;
-use darkscraper_networks::{HyphanetDriver, I2pDriver, LokinetDriver, TorDriver, ZeronetDriver};
-use darkscraper_parser::parse_response;
-use darkscraper_storage::Storage;
+use darkscraper_frontier::{CrawlFrontier, CrawlScope, RevisitPolicy, TopicScorer, YieldKind, YieldStats};
+use darkscraper_networks::{route, DriverRegistry};
+use darkscraper_parser::{decode_body, is_parseable, parse_document_text, parse_response};
+use darkscraper_storage::{BlobStore, Storage};
 
-use crate::seeds::{get_all_seeds, is_v3_onion};
+use crate::scheduler::{NetworkScheduler, NextJob};
+use crate::seeds::get_all_seeds;
 
 /// Bundled result from crawling + discovery on a single page.
 pub struct CrawlResult {
     page: PageData,
     correlations: Vec<Correlation>,
+    mirrors: Vec<MirrorLink>,
+    /// (content_simhash, dom_simhash) when the page is a site's front page
+    site_fingerprint: Option<(u64, u64)>,
+    /// Job URL and the page that linked to it, for yield accounting
+    url: url::Url,
+    source_url: Option<String>,
+    /// Content-addressed page: stored once, never revisited
+    immutable: bool,
+    /// How the page is scheduled for its next visit
+    revisit: RevisitTarget,
+    /// Metadata records of the page itself when it is a document (PDF) and of its images
+    documents: Vec<DocumentMetadata>,
 }
 
+/// Feeds claimed for polling per network per minute.
+const FEED_POLLS_PER_MINUTE: i64 = 50;
+/// Image URLs remembered as fetched; the set is cleared when it grows past this
+/// (a site logo is then fetched once more).
+const MAX_TRACKED_IMAGES: usize = 100_000;
+
 // MAX_PAGES_PER_DOMAIN removed - now defined per-network in NetworkDriver trait
 
-/// Create a CrawlJob from a discovered URL string, or None if it can't be handled.
-/// Only accepts http/https URLs with v3 .onion, .i2p, or .bit hosts.
+/// Per-network (in-site depth, domain hops) limits.
+type DepthLimits = HashMap<String, (u32, u32)>;
+
+/// Site identity for depth tracking: the host, or the key of an opaque hyphanet URL.
+fn site_key(url: &url::Url) -> &str {
+    match url.host_str() {
+        Some(host) => host,
+        None => url.path().split('/').next().unwrap_or(""),
+    }
+}
+
+/// Create a CrawlJob for a URL discovered on `parent`'s page, or None if it can't be
+/// handled by any driver, is out of scope, or exceeds the network's depth / domain-hop limits.
 fn make_crawl_job(
     url_str: &str,
-    depth: u32,
-    source_url: &url::Url,
+    parent: &CrawlJob,
     drivers: &[Box<dyn NetworkDriver>],
+    scope: &CrawlScope,
+    limits: &DepthLimits,
 ) -> Option<CrawlJob> {
     let parsed = url::Url::parse(url_str).ok()?;
+    // Clearnet, unsupported schemes and networks without an enabled driver are skipped
+    let driver = route(drivers, &parsed)?;
+    let network = driver.name().to_string();
 
-    // Allow http, https, hyphanet, and legacy freenet schemes
-    let scheme = parsed.scheme();
-    if scheme != "http" && scheme != "https" && scheme != "hyphanet" && scheme != "freenet" {
+    // Same site: one level deeper. New site: depth resets, one more hop from the seed.
+    let (depth, domain_hops) = if site_key(&parsed) == site_key(&parent.url) {
+        (parent.depth + 1, parent.domain_hops)
+    } else {
+        (0, parent.domain_hops + 1)
+    };
+    let (max_depth, max_hops) = limits.get(&network).copied().unwrap_or((u32::MAX, u32::MAX));
+    if depth > max_depth || domain_hops > max_hops {
         return None;
     }
 
-    if !drivers.iter().any(|d| d.can_handle(&parsed)) {
-        return None;
+    let priority =
+        CrawlFrontier::calculate_priority(&parsed, driver.is_key_address(&parsed), depth, domain_hops);
+    let job = CrawlJob {
+        url: parsed,
+        depth,
+        domain_hops,
+        source_url: Some(parent.url.to_string()),
+        network,
+        priority,
+        retry_count: 0,
+    };
+    scope.check(&job).ok()?;
+    Some(job)
+}
+
+/// How a fetched page is stored for revisits: under its canonical key, remembering the
+/// fetched URL and where in the crawl it was found.
+fn revisit_target(key: &str, job: &CrawlJob, domain: &str) -> RevisitTarget {
+    RevisitTarget {
+        key: key.to_string(),
+        url: job.url.to_string(),
+        network: job.network.clone(),
+        domain: domain.to_string(),
+        depth: job.depth,
+        domain_hops: job.domain_hops,
+        source_url: job.source_url.clone(),
     }
+}
 
-    let network = if scheme == "hyphanet" || scheme == "freenet" {
-        // Reject bogus hyphanet://unknown/ or freenet://unknown/ URLs from SourceMiner
-        // resolving relative paths against opaque hyphanet: base URLs
-        if url_str.starts_with("hyphanet://") || url_str.starts_with("freenet://") {
-            return None;
-        }
-        "hyphanet"
-    } else {
-        let host = parsed.host_str().unwrap_or("");
-        if host.ends_with(".onion") {
-            if !is_v3_onion(host) {
-                return None; // reject v2 onion addresses
-            }
-            "tor"
-        } else if host.ends_with(".i2p") {
-            "i2p"
-        } else if host.ends_with(".bit") {
-            "zeronet"
-        } else if host.ends_with(".loki") {
-            "lokinet"
-        } else {
-            return None; // skip clearnet URLs
-        }
-    };
-    let priority = CrawlFrontier::calculate_priority(&parsed, depth + 1);
-    Some(CrawlJob {
-        url: parsed,
-        depth: depth + 1,
-        source_url: Some(source_url.to_string()),
-        network: network.to_string(),
+/// Rebuild the job of a page due for a revisit, or None if it is now out of scope
+/// (the scope config may have changed since it was first fetched).
+fn revisit_job(
+    target: RevisitTarget,
+    drivers: &[Box<dyn NetworkDriver>],
+    scope: &CrawlScope,
+) -> Option<CrawlJob> {
+    let url = url::Url::parse(&target.url).ok()?;
+    let key_address = route(drivers, &url).is_some_and(|d| d.is_key_address(&url));
+    let priority =
+        CrawlFrontier::calculate_priority(&url, key_address, target.depth, target.domain_hops);
+    let job = CrawlJob {
+        url,
+        depth: target.depth,
+        domain_hops: target.domain_hops,
+        source_url: target.source_url,
+        network: target.network,
         priority,
         retry_count: 0,
-    })
+    };
+    scope.check(&job).ok()?;
+    Some(job)
 }
 
 pub async fn run_crawl(
     config: AppConfig,
+    config_path: &str,
     seeds: Option<String>,
     seed: Option<String>,
     max_depth: Option<u32>,
+    max_domain_hops: Option<u32>,
 ) -> Result<()> {
-    let max_depth = max_depth.unwrap_or(config.general.max_depth);
-
-    let tor_workers = if config.tor.enabled {
-        config.tor.max_concurrency
-    } else {
-        0
-    };
-    let i2p_workers = if config.i2p.enabled {
-        config.i2p.max_concurrency
-    } else {
-        0
-    };
-    let zeronet_workers = if config.zeronet.enabled {
-        config.zeronet.max_concurrency
-    } else {
-        0
-    };
-    let hyphanet_workers = if config.hyphanet.enabled {
-        config.hyphanet.max_concurrency
-    } else {
-        0
-    };
-    let lokinet_workers = if config.lokinet.enabled {
-        config.lokinet.max_concurrency
+    // Link limits per network; CLI flags override the config for every network
+    let depth_limits: Arc<DepthLimits> = Arc::new(
+        config
+            .networks
+            .keys()
+            .map(|network| {
+                let (depth, hops) = config.depth_limits(network);
+                let limits = (max_depth.unwrap_or(depth), max_domain_hops.unwrap_or(hops));
+                (network.to_string(), limits)
+            })
+            .collect(),
+    );
+    let canary_stale_days = config.extraction.canary_stale_days;
+    // Image metadata extraction: <img> sources fetched per page (0 = off) and their size cap
+    let max_images_per_page = if config.extraction.fetch_images { config.extraction.max_images_per_page } else { 0 };
+    let max_image_bytes = config.extraction.max_image_size_kb * 1024;
+    // Blob store for raw bodies of any type (evidence files); without it raw HTML stays inline
+    let blob_store = if config.blobs.enabled {
+        match BlobStore::open(&config.general.data_dir, config.blobs.compression_level) {
+            Ok(store) => {
+                info!(data_dir = %config.general.data_dir, "blob store enabled");
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                warn!("blob store disabled: {:#}", e);
+                None
+            }
+        }
     } else {
-        0
+        None
     };
-    let total_workers =
-        tor_workers + i2p_workers + zeronet_workers + hyphanet_workers + lokinet_workers;
-
-    // Scale DB pool to worker count + headroom for storage task
-    let pool_size = ((total_workers as u32) + 5).max(10);
-    let storage = Storage::with_pool_size(&config.database.postgres_url, pool_size).await?;
-    storage.run_migrations().await?;
-
-    // --- Frontier with fresh bloom filter each session ---
-    // Bloom starts empty: within-session dedup only, allowing cross-session
-    // re-crawling of pages that may have new content/links.
-    let frontier = Arc::new(CrawlFrontier::new());
+    let blob_policy = Arc::new(config.blobs.clone());
 
     // Collect seed URLs
     let mut seed_urls: Vec<String> = Vec::new();
@@ -153,126 +203,107 @@ pub async fn run_crawl(
         info!("no seeds provided, using default seeds");
     }
 
-    // Detect network from URL and add seeds
-    for url_str in &seed_urls {
-        let network = if url_str.starts_with("hyphanet:") || url_str.starts_with("freenet:") {
-            "hyphanet"
-        } else if url_str.contains(".onion") {
-            "tor"
-        } else if url_str.contains(".i2p") {
-            "i2p"
-        } else if url_str.contains(".bit") {
-            "zeronet"
-        } else if url_str.contains(".loki") {
-            "lokinet"
-        } else {
-            "tor"
-        };
-        frontier
-            .add_seeds(std::slice::from_ref(url_str), network)
-            .await;
-    }
-    info!(count = seed_urls.len(), "seeds loaded");
-
-    // Build network drivers
-    let mut drivers: Vec<Box<dyn NetworkDriver>> = Vec::new();
-
-    if config.tor.enabled {
-        match TorDriver::new(
-            &config.tor.socks_proxies,
-            config.tor.max_concurrency,
-            config.tor.min_delay_seconds,
-            config.tor.connect_timeout_seconds,
-            config.tor.request_timeout_seconds,
-        ) {
-            Ok(driver) => {
-                info!(proxies = ?config.tor.socks_proxies, workers = tor_workers, "tor driver ready");
-                drivers.push(Box::new(driver));
-            }
-            Err(e) => error!("failed to create tor driver: {}", e),
-        }
+    // Crawl scope — evaluated in make_crawl_job for every discovered URL
+    let seed_hosts: Vec<String> = seed_urls
+        .iter()
+        .filter_map(|s| url::Url::parse(s).ok())
+        .filter_map(|u| u.host_str().map(|h| h.to_string()))
+        .collect();
+    let scope = Arc::new(CrawlScope::new(&config.scope, &seed_hosts)?);
+
+    // Topic profile — scores pages and links for focused crawling
+    let topic = Arc::new(TopicScorer::new(&config.topic)?);
+    if topic.is_enabled() {
+        info!(min_relevance = config.topic.min_relevance, boost = config.topic.boost, "topic-focused crawling enabled");
     }
 
-    if config.i2p.enabled {
-        match I2pDriver::new(
-            &config.i2p.http_proxies,
-            config.i2p.max_concurrency,
-            config.i2p.min_delay_seconds,
-            config.i2p.connect_timeout_seconds,
-            config.i2p.request_timeout_seconds,
-        ) {
-            Ok(driver) => {
-                info!(proxies = ?config.i2p.http_proxies, workers = i2p_workers, "i2p driver ready");
-                drivers.push(Box::new(driver));
-            }
-            Err(e) => error!("failed to create i2p driver: {}", e),
-        }
-    }
+    // Shared worker pool: [general] max_workers, or the sum of the enabled networks' limits
+    let network_limits: usize = config
+        .enabled_networks()
+        .filter(|(network, _)| scope.network_enabled(network))
+        .map(|(_, network)| network.max_concurrency)
+        .sum();
+    let total_workers = config.general.max_workers.unwrap_or(network_limits).max(1);
 
-    if config.zeronet.enabled {
-        match ZeronetDriver::new(
-            &config.zeronet.http_proxies,
-            config.zeronet.max_concurrency,
-            config.zeronet.min_delay_seconds,
-            config.zeronet.connect_timeout_seconds,
-            config.zeronet.request_timeout_seconds,
-        ) {
-            Ok(driver) => {
-                info!(proxies = ?config.zeronet.http_proxies, workers = zeronet_workers, "zeronet driver ready");
-                drivers.push(Box::new(driver));
-            }
-            Err(e) => error!("failed to create zeronet driver: {}", e),
-        }
-    }
+    // Scale DB pool to worker count + headroom for storage task
+    let pool_size = ((total_workers as u32) + 5).max(10);
+    let storage = Storage::with_pool_size(&config.database.postgres_url, pool_size).await?;
+    storage.run_migrations().await?;
 
-    if config.hyphanet.enabled {
-        match HyphanetDriver::new(
-            &config.hyphanet.http_proxies,
-            config.hyphanet.max_concurrency,
-            config.hyphanet.min_delay_seconds,
-            config.hyphanet.connect_timeout_seconds,
-            config.hyphanet.request_timeout_seconds,
-        ) {
-            Ok(driver) => {
-                info!(proxies = ?config.hyphanet.http_proxies, workers = hyphanet_workers, "hyphanet driver ready");
-                drivers.push(Box::new(driver));
+    // --- Frontier with fresh bloom filter each session ---
+    // Bloom starts empty: within-session dedup only, allowing cross-session
+    // re-crawling of pages that may have new content/links.
+    let frontier = Arc::new(CrawlFrontier::from_config(&config.frontier));
+
+    // Yield scores learned in previous sessions steer priorities from the start
+    match storage.load_yield_scores().await {
+        Ok(rows) => {
+            info!(count = rows.len(), "loaded yield scores from database");
+            for (kind, key, pages, new_domains, new_entities, new_correlations, errors) in rows {
+                if let Some(kind) = YieldKind::parse(&kind) {
+                    let stats = YieldStats {
+                        pages: pages as u64,
+                        new_domains: new_domains as u64,
+                        new_entities: new_entities as u64,
+                        new_correlations: new_correlations as u64,
+                        errors: errors as u64,
+                    };
+                    frontier.load_yield(kind, key, stats);
+                }
             }
-            Err(e) => error!("failed to create hyphanet driver: {}", e),
         }
+        Err(e) => error!("failed to load yield scores: {}", e),
     }
 
-    if config.lokinet.enabled {
-        match LokinetDriver::new(
-            &config.lokinet.socks_proxies,
-            config.lokinet.max_concurrency,
-            config.lokinet.min_delay_seconds,
-            config.lokinet.connect_timeout_seconds,
-            config.lokinet.request_timeout_seconds,
-        ) {
-            Ok(driver) => {
-                info!(proxies = ?config.lokinet.socks_proxies, workers = lokinet_workers, "lokinet driver ready");
-                drivers.push(Box::new(driver));
-            }
-            Err(e) => error!("failed to create lokinet driver: {}", e),
+    // Build a driver for every enabled [networks.<name>] section
+    let drivers = DriverRegistry::default().build_enabled(&config);
+
+    // Route each seed to the network whose driver handles it; seeds are held to the
+    // crawl scope like discovered URLs (excluded hosts and paths, disabled networks)
+    let (mut seeded, mut unrouted, mut out_of_scope) = (0usize, 0usize, 0usize);
+    for url_str in &seed_urls {
+        let Some((url, network)) = url::Url::parse(url_str)
+            .ok()
+            .and_then(|url| route(&drivers, &url).map(|d| (url.clone(), d.name().to_string())))
+        else {
+            debug!(url = %url_str, "seed skipped: no enabled network handles it");
+            unrouted += 1;
+            continue;
+        };
+        let job = CrawlJob {
+            url,
+            depth: 0,
+            domain_hops: 0,
+            source_url: None,
+            network: network.clone(),
+            priority: 0.0,
+            retry_count: 0,
+        };
+        if let Err(rule) = scope.check(&job) {
+            debug!(url = %url_str, rule = rule.as_str(), "seed skipped: out of scope");
+            out_of_scope += 1;
+            continue;
         }
+        frontier.add_seeds(std::slice::from_ref(url_str), &network).await;
+        seeded += 1;
     }
+    info!(count = seeded, skipped = unrouted, out_of_scope, "seeds loaded");
 
     let drivers: Arc<Vec<Box<dyn NetworkDriver>>> = Arc::new(drivers);
     let storage = Arc::new(storage);
 
     let max_timeout = config
-        .tor
-        .request_timeout_seconds
-        .max(config.i2p.request_timeout_seconds)
-        .max(config.zeronet.request_timeout_seconds)
-        .max(config.hyphanet.request_timeout_seconds)
-        .max(config.lokinet.request_timeout_seconds);
+        .enabled_networks()
+        .map(|(_, network)| network.request_timeout_seconds)
+        .max()
+        .unwrap_or(120);
     let fetch_config = FetchConfig {
         timeout: std::time::Duration::from_secs(max_timeout),
         max_body_size: config.general.max_body_size_mb * 1024 * 1024,
         follow_redirects: true,
         user_agent: "Mozilla/5.0 (Windows NT 10.0; rv:128.0) Gecko/20100101 Firefox/128.0"
             .to_string(),
+        validators: Default::default(),
     };
 
     // Pipeline channels
@@ -282,6 +313,8 @@ pub async fn run_crawl(
 
     // Track which domains we've already probed for infrastructure - lock-free with DashSet
     let probed_domains: Arc<DashSet<String>> = Arc::new(DashSet::new());
+    // Image URLs already fetched for metadata (site logos appear on every page)
+    let fetched_images: Arc<DashSet<String>> = Arc::new(DashSet::new());
 
     // Track pages crawled per domain to prevent one domain from monopolizing the queue
     let domain_page_count: Arc<DashMap<String, AtomicUsize>> = Arc::new(DashMap::new());
@@ -313,14 +346,25 @@ pub async fn run_crawl(
         count = dead_set_loaded.len(),
         "loaded dead URLs from database"
     );
+    // Keyed by canonical URL, same as the frontier's bloom filter (older rows may predate canonicalization)
     let dead_urls: Arc<DashSet<String>> = Arc::new(DashSet::new());
     for url in dead_set_loaded {
-        dead_urls.insert(url);
+        match url::Url::parse(&url) {
+            Ok(parsed) => dead_urls.insert(frontier.normalize_url(&parsed)),
+            Err(_) => dead_urls.insert(url),
+        };
     }
 
+    // Revisit scheduling — every stored page gets a next_visit_at from its change rate
+    let revisit_policy = Arc::new(RevisitPolicy::new(&config.frontier));
+    // RSS/Atom feeds are polled on a fixed interval instead (0 = treated like any page)
+    let feed_poll_secs = (config.frontier.feed_poll_interval_minutes * 60) as i64;
+
     // Storage task — writes parsed pages + correlations to DB with batched inserts
     let storage_handle = {
         let storage = Arc::clone(&storage);
+        let frontier = Arc::clone(&frontier);
+        let revisit_policy = Arc::clone(&revisit_policy);
         let mut shutdown = shutdown_tx.subscribe();
         let result_tx_monitor = result_tx.clone();
         tokio::spawn(async move {
@@ -330,31 +374,21 @@ pub async fn run_crawl(
             loop {
                 tokio::select! {
                     Some(result) = result_rx.recv() => {
-                        match storage.store_page(&result.page).await {
-                            Ok(id) => {
-                                pages_stored += 1;
-                                last_store_time = std::time::Instant::now();
-
-                                // Warn if channel is getting full (potential deadlock risk)
-                                let capacity = result_tx_monitor.capacity();
-                                if capacity < 1000 {
-                                    warn!(
-                                        capacity,
-                                        pages_stored,
-                                        "storage channel low capacity - DB may be slow"
-                                    );
-                                }
-
-                                info!(page_id = id, url = %result.page.url, total = pages_stored, "stored page");
-                            }
-                            Err(e) => error!(url = %result.page.url, "store failed: {}", e),
-                        }
-                        for corr in &result.correlations {
-                            if let Err(e) = storage.store_correlation(
-                                &corr.domain, &corr.correlation_type, &corr.value
-                            ).await {
-                                error!(domain = %corr.domain, "correlation store failed: {}", e);
+                        if let Some(id) = store_result(&storage, &frontier, &revisit_policy, feed_poll_secs, &result).await {
+                            pages_stored += 1;
+                            last_store_time = std::time::Instant::now();
+
+                            // Warn if channel is getting full (potential deadlock risk)
+                            let capacity = result_tx_monitor.capacity();
+                            if capacity < 1000 {
+                                warn!(
+                                    capacity,
+                                    pages_stored,
+                                    "storage channel low capacity - DB may be slow"
+                                );
                             }
+
+                            info!(page_id = id, url = %result.page.url, total = pages_stored, "stored page");
                         }
                     }
                     _ = tokio::time::sleep(std::time::Duration::from_secs(60)) => {
@@ -370,12 +404,7 @@ pub async fn run_crawl(
                     _ = shutdown.recv() => {
                         info!(pages_stored, "storage task shutting down");
                         while let Ok(result) = result_rx.try_recv() {
-                            let _ = storage.store_page(&result.page).await;
-                            for corr in &result.correlations {
-                                let _ = storage.store_correlation(
-                                    &corr.domain, &corr.correlation_type, &corr.value
-                                ).await;
-                            }
+                            store_result(&storage, &frontier, &revisit_policy, feed_poll_secs, &result).await;
                         }
                         break;
                     }
@@ -426,128 +455,253 @@ pub async fn run_crawl(
         }
     }
 
-    // Spawn parallel crawl workers
-    info!(
-        total_workers,
-        tor_workers,
-        i2p_workers,
-        zeronet_workers,
-        hyphanet_workers,
-        lokinet_workers,
-        "spawning crawl workers"
-    );
-    let mut worker_handles = Vec::new();
-
-    for worker_id in 0..total_workers {
+    // Revisit scheduler — re-queues known pages that are due, within each network's
+    // hourly recrawl budget (claimed once a minute)
+    let mut revisit_handles = Vec::new();
+    for driver in drivers.iter() {
+        let network_name = driver.name().to_string();
+        let budget = config.revisit_budget(&network_name);
+        if budget == 0 || !scope.network_enabled(&network_name) {
+            continue;
+        }
         let frontier = Arc::clone(&frontier);
-        let drivers = Arc::clone(&drivers);
-        let probed = Arc::clone(&probed_domains);
+        let storage = Arc::clone(&storage);
         let dead = Arc::clone(&dead_urls);
-        let domain_counts = Arc::clone(&domain_page_count);
-        let crawl_storage = Arc::clone(&storage);
-        let result_tx = result_tx.clone();
+        let scope = Arc::clone(&scope);
+        let drivers = Arc::clone(&drivers);
+        let lease = revisit_policy.lease().as_secs() as i64;
+        let per_minute = budget.div_ceil(60) as i64;
         let mut shutdown = shutdown_tx.subscribe();
-        let fetch_config = fetch_config.clone();
 
-        // Determine which network this worker handles (for startup probes)
-        let worker_network = if worker_id < tor_workers {
-            "tor"
-        } else if worker_id < tor_workers + i2p_workers {
-            "i2p"
-        } else if worker_id < tor_workers + i2p_workers + zeronet_workers {
-            "zeronet"
-        } else if worker_id < tor_workers + i2p_workers + zeronet_workers + hyphanet_workers {
-            "hyphanet"
-        } else {
-            "lokinet"
-        };
-        let worker_network = worker_network.to_string();
-
-        let handle = tokio::spawn(async move {
-            // Startup probe: wait UNTIL network proxy is actually ready
-            // Don't start crawling until the network can handle requests
-            if worker_network != "tor" {
-                info!(worker_id, network = %worker_network, "waiting for network to be ready...");
+        revisit_handles.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        match storage.claim_due_revisits(&network_name, per_minute, lease).await {
+                            Ok(targets) => {
+                                let jobs: Vec<CrawlJob> = targets
+                                    .into_iter()
+                                    .filter(|t| !dead.contains(&t.key))
+                                    .filter_map(|t| revisit_job(t, &drivers, &scope))
+                                    .collect();
+                                if !jobs.is_empty() {
+                                    let added = frontier.add_revisits(jobs, &network_name).await;
+                                    info!(network = network_name, added, "due pages re-queued for revisit");
+                                }
+                            }
+                            Err(e) => error!(network = network_name, "revisit claim failed: {}", e),
+                        }
+                    }
+                    _ = shutdown.recv() => break,
+                }
+            }
+        }));
+    }
 
-                // Hyphanet and I2P need HTTP probe to verify they're actually ready with peers/tunnels
-                // ZeroNet and Lokinet just need TCP port check
-                let needs_http_probe = worker_network == "hyphanet" || worker_network == "i2p";
+    // Feed poller — re-queues RSS/Atom feeds whose poll interval has passed, so new
+    // items are picked up without waiting for the change-rate revisit schedule
+    let mut feed_handles = Vec::new();
+    if feed_poll_secs > 0 {
+        for driver in drivers.iter() {
+            let network_name = driver.name().to_string();
+            if !scope.network_enabled(&network_name) {
+                continue;
+            }
+            let frontier = Arc::clone(&frontier);
+            let storage = Arc::clone(&storage);
+            let dead = Arc::clone(&dead_urls);
+            let scope = Arc::clone(&scope);
+            let drivers = Arc::clone(&drivers);
+            let mut shutdown = shutdown_tx.subscribe();
 
-                let mut probe_attempts = 0u32;
+            feed_handles.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
                 loop {
-                    let is_ready = if needs_http_probe {
-                        // HTTP probe for Hyphanet/I2P - check it's actually ready, not just port open
-                        let probe_url = match worker_network.as_str() {
-                            "hyphanet" => std::env::var("HYPHANET_PROXY")
-                                .unwrap_or_else(|_| "hyphanet1:8888".to_string()),
-                            "i2p" => std::env::var("I2P_PROXY")
-                                .unwrap_or_else(|_| "i2p1:4444".to_string()),
-                            _ => unreachable!(),
-                        };
-
-                        // Try to fetch a test page and check response
-                        match reqwest::Client::builder()
-                            .timeout(std::time::Duration::from_secs(10))
-                            .build()
-                            .ok()
-                            .and_then(|client| {
-                                tokio::task::block_in_place(|| {
-                                    tokio::runtime::Handle::current().block_on(async {
-                                        client
-                                            .get(format!("http://{}/", probe_url))
-                                            .send()
-                                            .await
-                                            .ok()
-                                    })
-                                })
-                            })
-                            .and_then(|resp| {
-                                tokio::task::block_in_place(|| {
-                                    tokio::runtime::Handle::current()
-                                        .block_on(async { resp.text().await.ok() })
-                                })
-                            }) {
-                            Some(text) => {
-                                if worker_network == "hyphanet" {
-                                    // Hyphanet is ready when NOT showing setup wizard
-                                    !text.contains("Set Up Freenet")
-                                        && !text.contains("First Time Wizard")
-                                } else {
-                                    // I2P is ready when NOT showing proxy errors (means tunnels are built)
-                                    !text.contains("Proxy error")
-                                        && !text.contains("Can't create connection")
-                                        && !text.contains("Host is down")
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            match storage.claim_due_feeds(&network_name, FEED_POLLS_PER_MINUTE, feed_poll_secs).await {
+                                Ok(targets) => {
+                                    let jobs: Vec<CrawlJob> = targets
+                                        .into_iter()
+                                        .filter(|t| !dead.contains(&t.key))
+                                        .filter_map(|t| revisit_job(t, &drivers, &scope))
+                                        .collect();
+                                    if !jobs.is_empty() {
+                                        let added = frontier.add_revisits(jobs, &network_name).await;
+                                        info!(network = network_name, added, "due feeds re-queued for polling");
+                                    }
                                 }
+                                Err(e) => error!(network = network_name, "feed claim failed: {}", e),
                             }
-                            None => false,
-                        }
-                    } else {
-                        // TCP probe for ZeroNet, Lokinet
-                        let probe_addr = match worker_network.as_str() {
-                            "zeronet" => std::env::var("ZERONET_PROXY")
-                                .unwrap_or_else(|_| "zeronet1:43110".to_string()),
-                            "lokinet" => std::env::var("LOKINET_PROXY")
-                                .unwrap_or_else(|_| "lokinet1:1080".to_string()),
-                            _ => unreachable!(),
-                        };
-                        tokio::net::TcpStream::connect(&probe_addr).await.is_ok()
-                    };
+                        }
+                        _ = shutdown.recv() => break,
+                    }
+                }
+            }));
+        }
+    }
 
-                    if is_ready {
-                        info!(worker_id, network = %worker_network, "network is ready");
-                        break;
-                    } else {
-                        probe_attempts += 1;
-                        if probe_attempts % 10 == 1 {
-                            info!(worker_id, network = %worker_network, attempts = probe_attempts, "still waiting for network...");
+    // Quarantine task — persists URLs the frontier held back as suspected crawler traps
+    // and the learned yield scores, and reports scope rejections
+    let quarantine_handle = {
+        let frontier = Arc::clone(&frontier);
+        let scope = Arc::clone(&scope);
+        let storage = Arc::clone(&storage);
+        let mut shutdown = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            let mut last_rejected = 0usize;
+            loop {
+                let stopping = tokio::select! {
+                    _ = interval.tick() => false,
+                    _ = shutdown.recv() => true,
+                };
+                let quarantined = frontier.drain_quarantined().await;
+                for q in &quarantined {
+                    if let Err(e) = storage.store_quarantined_url(&q.url, &q.network, &q.domain, q.reason.as_str()).await {
+                        error!(url = %q.url, "quarantine store failed: {}", e);
+                    }
+                }
+                if !quarantined.is_empty() {
+                    info!(count = quarantined.len(), totals = ?frontier.quarantine_counts(), "quarantined suspected crawler traps");
+                }
+                flush_yield_scores(&frontier, &storage).await;
+                let rejections = scope.rejection_counts();
+                let rejected: usize = rejections.iter().map(|(_, n)| n).sum();
+                if rejected != last_rejected {
+                    info!(rejected, by_rule = ?rejections, "out-of-scope URLs rejected");
+                    last_rejected = rejected;
+                }
+                if stopping {
+                    break;
+                }
+            }
+        })
+    };
+
+    // Network scheduler — per-network slots sized from each driver's max_concurrency,
+    // shared by the whole worker pool
+    let scheduler = Arc::new(NetworkScheduler::new(
+        drivers
+            .iter()
+            .filter(|d| scope.network_enabled(d.name()))
+            .map(|d| (d.name().to_string(), d.max_concurrency(), d.default_delay()))
+            .collect(),
+        total_workers,
+    ));
+
+    // Network supervisors: one health-check loop per network gates its workers.
+    // Jobs are handed out only while at least one of the network's proxies is ready;
+    // a network whose proxies all degrade is paused until they recover.
+    let health_interval = std::time::Duration::from_secs(config.general.health_check_interval_seconds.max(1));
+    let mut supervisor_handles = Vec::new();
+    for (index, driver) in drivers.iter().enumerate() {
+        let network = driver.name().to_string();
+        if !scope.network_enabled(&network) {
+            continue;
+        }
+        let drivers = Arc::clone(&drivers);
+        let scheduler = Arc::clone(&scheduler);
+        let mut shutdown = shutdown_tx.subscribe();
+        supervisor_handles.push(tokio::spawn(async move {
+            let driver = &drivers[index];
+            let mut ready = false;
+            let mut last_states: Option<Vec<(String, &'static str)>> = None;
+            loop {
+                let health = driver.health_check().await;
+                let now_ready = health.is_empty() || health.iter().any(|h| h.state == ProxyState::Ready);
+                let states: Vec<(String, &'static str)> =
+                    health.iter().map(|h| (h.proxy.clone(), h.state.as_str())).collect();
+
+                if now_ready != ready {
+                    scheduler.set_ready(&network, now_ready);
+                    if now_ready {
+                        info!(network, proxies = ?states, "network is ready");
+                    } else if last_states.is_some() {
+                        warn!(network, proxies = ?states, "network degraded, paused until a proxy recovers");
+                    }
+                    ready = now_ready;
+                } else if last_states.as_ref() != Some(&states) {
+                    for h in health.iter().filter(|h| h.state != ProxyState::Ready) {
+                        info!(network, proxy = %h.proxy, state = h.state.as_str(), detail = ?h.detail, "proxy not ready");
+                    }
+                }
+                if !ready && last_states.is_none() {
+                    info!(network, proxies = ?states, "waiting for network to be ready...");
+                }
+                last_states = Some(states);
+
+                // Re-check sooner while the network is down
+                let wait = if ready { health_interval } else { health_interval.min(std::time::Duration::from_secs(30)) };
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = shutdown.recv() => break,
+                }
+            }
+        }));
+    }
+
+    // SIGHUP reloads per-network limits and the pool size from the config file, with the
+    // same environment overrides as at startup. Workers are spawned once: the pool size can
+    // shrink below the startup worker count and grow back up to it, but not beyond
+    #[cfg(unix)]
+    {
+        let scheduler = Arc::clone(&scheduler);
+        let config_path = config_path.to_string();
+        tokio::spawn(async move {
+            let Ok(mut hangups) = signal::unix::signal(signal::unix::SignalKind::hangup()) else {
+                return;
+            };
+            while hangups.recv().await.is_some() {
+                let reloaded = std::fs::read_to_string(&config_path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|s| Ok(toml::from_str::<AppConfig>(&s)?));
+                match reloaded {
+                    Ok(mut reloaded) => {
+                        crate::apply_env_overrides(&mut reloaded);
+                        for network in scheduler.network_names() {
+                            if let Some(limit) = reloaded.max_concurrency(&network) {
+                                scheduler.resize(&network, limit);
+                            }
                         }
-                        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-                        // No timeout - keep waiting until ready
+                        scheduler.set_global_limit(reloaded.general.max_workers.unwrap_or(usize::MAX));
+                        info!(limits = ?scheduler.limits(), "concurrency limits reloaded");
                     }
+                    Err(e) => error!(path = %config_path, "config reload failed: {}", e),
                 }
             }
+        });
+    }
+
+    // Spawn the shared worker pool
+    info!(total_workers, limits = ?scheduler.limits(), "spawning crawl workers");
+    let mut worker_handles = Vec::new();
 
-            info!(worker_id, network = %worker_network, "worker started");
+    for worker_id in 0..total_workers {
+        let frontier = Arc::clone(&frontier);
+        let scheduler = Arc::clone(&scheduler);
+        let drivers = Arc::clone(&drivers);
+        let scope = Arc::clone(&scope);
+        let topic = Arc::clone(&topic);
+        let depth_limits = Arc::clone(&depth_limits);
+        let probed = Arc::clone(&probed_domains);
+        let fetched_images = Arc::clone(&fetched_images);
+        let blob_store = blob_store.clone();
+        let blob_policy = Arc::clone(&blob_policy);
+        let dead = Arc::clone(&dead_urls);
+        let domain_counts = Arc::clone(&domain_page_count);
+        let crawl_storage = Arc::clone(&storage);
+        let revisit_policy = Arc::clone(&revisit_policy);
+        let result_tx = result_tx.clone();
+        let mut shutdown = shutdown_tx.subscribe();
+        let fetch_config = fetch_config.clone();
+
+        let handle = tokio::spawn(async move {
+            info!(worker_id, "worker started");
             let mut idle_count = 0u32;
             loop {
                 tokio::select! {
@@ -556,35 +710,77 @@ pub async fn run_crawl(
                         break;
                     }
                     _ = async {
-                        let Some(job) = frontier.pop_for_network(&worker_network).await else {
-                            idle_count += 1;
-                            if idle_count % 30 == 1 {
-                                info!(worker_id, network = %worker_network, idle_count, "queue empty, waiting for URLs");
+                        // The slot is held until this job is done (dropped on every return)
+                        let (job, _slot) = match scheduler.next_job(&frontier).await {
+                            NextJob::Job(job, slot) => (job, slot),
+                            NextJob::Busy => {
+                                // Queued work, but every eligible network or host is at its limit
+                                tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+                                return;
+                            }
+                            NextJob::Idle => {
+                                idle_count += 1;
+                                if idle_count % 30 == 1 {
+                                    info!(worker_id, idle_count, "queues empty, waiting for URLs");
+                                }
+                                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                return;
                             }
-                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                            return;
                         };
                         idle_count = 0;
 
                         let url = job.url.clone();
                         let depth = job.depth;
 
+                        // The polite pop reserved an in-flight slot for this host;
+                        // release it via finish_host_visit on every path, before or after the fetch
+                        let host = url.host_str().map(|h| h.to_string());
+                        let canonical_url = frontier.normalize_url(&url);
+
                         // Skip if this URL is permanently dead (lock-free check via DashSet)
-                        if dead.contains(url.as_str()) {
+                        if dead.contains(&canonical_url) {
+                            if let Some(host) = &host {
+                                frontier.finish_host_visit(host, None, None);
+                            }
                             return;
                         }
 
                         // Find appropriate driver
-                        let driver = drivers.iter().find(|d| d.can_handle(&url));
-                        let Some(driver) = driver else {
+                        let Some(driver) = route(&drivers, &url) else {
                             warn!(worker_id, url = %url, "no driver for URL");
+                            if let Some(host) = &host {
+                                frontier.finish_host_visit(host, None, None);
+                            }
                             return;
                         };
 
-                        // NO POLITENESS CHECKS - just crawl everything in parallel
+                        // Content-addressed pages never change: one stored copy is enough
+                        if driver.is_immutable(&url) && crawl_storage.has_page(&canonical_url).await.unwrap_or(false) {
+                            debug!(worker_id, url = %url, "immutable page already stored");
+                            if let Some(host) = &host {
+                                frontier.finish_host_visit(host, None, None);
+                            }
+                            return;
+                        }
 
-                        info!(worker_id, url = %url, depth, retry = job.retry_count, "fetching");
-                        let resp = match driver.fetch(&url, &fetch_config, job.retry_count).await {
+                        // Known page: send the stored copy's validators so an unchanged page comes back as a 304
+                        let validators = crawl_storage.load_validators(&canonical_url).await.unwrap_or_default();
+                        let conditional = !validators.is_empty();
+                        let config = FetchConfig { validators, ..fetch_config.clone() };
+
+                        info!(worker_id, url = %url, depth, retry = job.retry_count, conditional, "fetching");
+                        let fetched = driver.fetch(&url, &config, job.retry_count).await;
+                        if let Some(host) = &host {
+                            let status = fetched.as_ref().ok().map(|r| r.status);
+                            let retry_after = fetched
+                                .as_ref()
+                                .ok()
+                                .and_then(|r| r.headers.get("retry-after"))
+                                .and_then(|v| v.trim().parse::<u64>().ok())
+                                .map(std::time::Duration::from_secs);
+                            frontier.finish_host_visit(host, status, retry_after);
+                        }
+                        let resp = match fetched {
                             Ok(r) => r,
                             Err(e) => {
                                 let retries = job.retry_count;
@@ -612,28 +808,102 @@ pub async fn run_crawl(
                                         "fetch failed permanently: {}",
                                         err_msg
                                     );
-                                    dead.insert(url.to_string());
+                                    dead.insert(canonical_url.clone());
                                     let _ = crawl_storage.mark_dead(
-                                        url.as_str(), network, domain, retries, &err_msg, failure_type
+                                        &canonical_url, network, domain, retries, &err_msg, failure_type
                                     ).await;
+                                    frontier.record_yield(&url, job.source_url.as_deref(), YieldStats {
+                                        errors: 1,
+                                        ..Default::default()
+                                    });
                                 }
                                 return;
                             }
                         };
 
+                        // Host is throttling us (rate limit / DDoS guard): backoff was raised above,
+                        // requeue the URL instead of storing the error page
+                        if (resp.status == 429 || resp.status == 503) && job.retry_count < driver.max_retries() {
+                            warn!(worker_id, url = %url, status = resp.status, "host throttling, requeued");
+                            frontier.push(CrawlJob {
+                                retry_count: job.retry_count + 1,
+                                ..job
+                            }).await;
+                            return;
+                        }
+
+                        // Not modified: the stored copy is current, only confirm it was seen again
+                        if resp.status == 304 {
+                            match crawl_storage.touch_page(&canonical_url).await {
+                                Ok(Some(hash)) => {
+                                    debug!(worker_id, url = %url, "not modified since last fetch");
+                                    let polled_feed = feed_poll_secs > 0
+                                        && crawl_storage.touch_feed(&canonical_url, feed_poll_secs).await.unwrap_or(false);
+                                    if !driver.is_immutable(&url) && !polled_feed {
+                                        let revisit = revisit_target(&canonical_url, &job, &resp.domain);
+                                        if let Err(e) = schedule_revisit(
+                                            &crawl_storage, &revisit_policy, &revisit, &hash
+                                        ).await {
+                                            error!(url = %url, "revisit schedule failed: {}", e);
+                                        }
+                                    }
+                                }
+                                Ok(None) => debug!(worker_id, url = %url, "304 for a page that was never stored"),
+                                Err(e) => error!(url = %url, "last-seen update failed: {}", e),
+                            }
+                            return;
+                        }
+
+                        // -- Blob store: raw bodies kept by SHA-256 (identical bodies stored once),
+                        // whatever their type, within the per-type / per-size policy --
+                        let body_hash = match &blob_store {
+                            Some(store)
+                                if resp.status < 400
+                                    && !resp.body.is_empty()
+                                    && blob_policy.should_store(resp.content_type.as_deref(), resp.body.len()) =>
+                            {
+                                let store = Arc::clone(store);
+                                let body = resp.body.clone();
+                                match tokio::task::spawn_blocking(move || store.put(&body)).await {
+                                    Ok(Ok(blob)) => {
+                                        debug!(worker_id, url = %url, hash = %blob.hash, created = blob.created, "body kept in blob store");
+                                        if let Err(e) = crawl_storage.record_blob(
+                                            &blob, resp.content_type.as_deref(), &canonical_url, &job.network, &resp.domain
+                                        ).await {
+                                            error!(url = %url, "blob record failed: {}", e);
+                                        }
+                                        Some(blob.hash)
+                                    }
+                                    Ok(Err(e)) => {
+                                        error!(url = %url, "blob store write failed: {:#}", e);
+                                        None
+                                    }
+                                    Err(e) => {
+                                        error!(url = %url, "blob store task failed: {}", e);
+                                        None
+                                    }
+                                }
+                            }
+                            _ => None,
+                        };
+
                         let domain = url.host_str().unwrap_or("unknown").to_string();
                         let url_path = url.path().to_string();
-                        let raw_html = String::from_utf8_lossy(&resp.body);
+                        let (raw_html, _) = decode_body(&resp.body, resp.content_type.as_deref());
 
                         // -- Handle special probe responses --
 
                         // robots.txt
                         if url_path.ends_with("/robots.txt") && resp.status < 400 {
+                            if let Some(delay) = InfraProber::parse_crawl_delay(&raw_html) {
+                                info!(worker_id, domain = %domain, delay_secs = delay.as_secs_f64(), "robots.txt Crawl-delay");
+                                frontier.set_crawl_delay(&domain, delay);
+                            }
                             let extra = InfraProber::parse_robots_txt(&raw_html, &url);
                             info!(worker_id, url = %url, count = extra.len(), "robots.txt URLs");
                             for url_str in &extra {
-                                if let Some(job) = make_crawl_job(url_str, depth, &url, &drivers) {
-                                    frontier.push(job).await;
+                                if let Some(next_job) = make_crawl_job(url_str, &job, &drivers, &scope, &depth_limits) {
+                                    frontier.push(next_job).await;
                                 }
                             }
                             return;
@@ -644,8 +914,8 @@ pub async fn run_crawl(
                             let extra = InfraProber::parse_sitemap(&raw_html);
                             info!(worker_id, url = %url, count = extra.len(), "sitemap URLs");
                             for url_str in &extra {
-                                if let Some(job) = make_crawl_job(url_str, depth, &url, &drivers) {
-                                    frontier.push(job).await;
+                                if let Some(next_job) = make_crawl_job(url_str, &job, &drivers, &scope, &depth_limits) {
+                                    frontier.push(next_job).await;
                                 }
                             }
                             return;
@@ -660,25 +930,134 @@ pub async fn run_crawl(
                             return;
                         }
 
-                        // -- Normal HTML processing --
-                        let is_html = resp
-                            .content_type
-                            .as_deref()
-                            .map(|ct| ct.contains("text/html"))
-                            .unwrap_or(true);
+                        // -- PGP keys, signed mirror lists and warrant canaries --
+                        // Checked on any content type: mirrors.txt / canary.txt are usually text/plain
+                        if resp.status < 400 && PgpVerifier::has_pgp_content(&raw_html) {
+                            // Verify against keys seen before this response only: a clone that
+                            // publishes its own key next to its signed mirror list must not
+                            // vouch for itself
+                            let known_keys = crawl_storage.load_pgp_keys(&domain).await.unwrap_or_default();
+                            let signed_docs = PgpVerifier::verify_signed_messages(&raw_html, &known_keys);
+                            for key in PgpVerifier::extract_public_keys(&raw_html) {
+                                let _ = crawl_storage.store_pgp_key(
+                                    &domain, &key.key_hash, &key.fingerprint, &key.armored
+                                ).await;
+                            }
+                            let mut signed_jobs = Vec::new();
+                            for doc in &signed_docs {
+                                info!(
+                                    worker_id,
+                                    url = %url,
+                                    verified = doc.verified,
+                                    signer = ?doc.signer_fingerprint,
+                                    mirrors = doc.mirror_urls.len(),
+                                    canary = doc.is_canary,
+                                    "PGP-signed message"
+                                );
+                                for mirror_url in &doc.mirror_urls {
+                                    let Ok(parsed) = url::Url::parse(mirror_url) else {
+                                        continue;
+                                    };
+                                    let Some(mirror_domain) = parsed.host_str() else {
+                                        continue;
+                                    };
+                                    if mirror_domain != domain {
+                                        let _ = crawl_storage.store_signed_mirror(
+                                            &domain, mirror_domain, mirror_url,
+                                            doc.verified, doc.signer_fingerprint.as_deref()
+                                        ).await;
+                                    }
+                                    if let Some(next_job) = make_crawl_job(mirror_url, &job, &drivers, &scope, &depth_limits) {
+                                        signed_jobs.push(next_job);
+                                    }
+                                }
+                                if doc.is_canary {
+                                    let stale = doc.canary_date
+                                        .map(|d| (chrono::Utc::now().date_naive() - d).num_days() > canary_stale_days as i64)
+                                        .unwrap_or(false);
+                                    if stale {
+                                        warn!(worker_id, url = %url, canary_date = ?doc.canary_date, "stale warrant canary");
+                                    }
+                                    let _ = crawl_storage.store_canary(
+                                        &domain, url.as_str(), &doc.content_hash, doc.canary_date,
+                                        doc.verified, doc.signer_fingerprint.as_deref()
+                                    ).await;
+                                }
+                            }
+                            frontier.push_batch(signed_jobs).await;
+                        }
+
+                        // -- Documents: PDFs, office files and archives are parsed for their
+                        // text, metadata and file listings (leaked files) --
+                        let document = if resp.status < 400
+                            && MetadataExtractor::looks_like_document(resp.content_type.as_deref(), &resp.body)
+                        {
+                            let doc_url = url.to_string();
+                            let content_type = resp.content_type.clone();
+                            let body = resp.body.clone();
+                            let extracted = tokio::task::spawn_blocking(move || {
+                                MetadataExtractor::extract_document(&doc_url, content_type.as_deref(), &body)
+                            }).await.ok().flatten();
+                            match extracted {
+                                Some(doc) => Some(doc),
+                                None => {
+                                    warn!(worker_id, url = %url, "unreadable document");
+                                    return;
+                                }
+                            }
+                        } else {
+                            None
+                        };
 
-                        if !is_html {
+                        // -- Normal page processing (HTML, gemtext, gopher menus, text, JSON, XML/feeds) --
+                        if document.is_none() && !is_parseable(resp.content_type.as_deref(), &resp.body) {
                             return;
                         }
 
-                        let page = match parse_response(&resp) {
-                            Ok(p) => p,
-                            Err(e) => {
-                                warn!(worker_id, url = %url, "parse error: {}", e);
-                                return;
+                        let mut page = match &document {
+                            Some(doc) => {
+                                let mut page = parse_document_text(&resp, &doc.text);
+                                page.content_type = Some(doc.metadata.content_type.clone());
+                                if let Some((_, title)) = doc.metadata.fields.iter().find(|(k, _)| k == "title") {
+                                    page.title = Some(title.clone());
+                                }
+                                info!(
+                                    worker_id, url = %url, kind = %doc.metadata.metadata_type,
+                                    fields = doc.metadata.fields.len(), entries = doc.metadata.entries.len(),
+                                    chars = doc.text.len(), "document parsed"
+                                );
+                                page
                             }
+                            None => match parse_response(&resp) {
+                                Ok(p) => p,
+                                Err(e) => {
+                                    warn!(worker_id, url = %url, "parse error: {}", e);
+                                    return;
+                                }
+                            },
                         };
 
+                        // Detected language when confident: declared lang attributes are often template leftovers
+                        let language = page.metadata.effective_language();
+                        let relevance = topic.is_enabled().then(|| {
+                            topic.score_page(page.title.as_deref(), &page.body_text, language.as_deref())
+                        });
+                        page.relevance = relevance;
+                        page.body_hash = body_hash;
+
+                        // Label each link with the network that would crawl it
+                        for link in &mut page.links {
+                            link.network = url::Url::parse(&link.url)
+                                .ok()
+                                .and_then(|target| route(&drivers, &target))
+                                .map(|d| d.name().to_string());
+                        }
+
+                        // Low-novelty trap: the same content keeps coming back on this host
+                        if frontier.record_page_content(&url, &page.raw_html_hash) {
+                            warn!(worker_id, url = %url, domain = %domain, "repeated content, URL template quarantined as crawler trap");
+                        }
+
                         // Increment domain page counter and check network-specific limit
                         let domain_page_count = domain_counts.entry(domain.clone())
                             .or_insert_with(|| AtomicUsize::new(0))
@@ -716,12 +1095,96 @@ pub async fn run_crawl(
                             info!(worker_id, url = %url, count = correlations.len(), "correlations");
                         }
 
-                        // 2.5. Extract I2P base32 address (if visiting human-readable .i2p)
-                        // This is I2P-specific but called here because we need the response
-                        if url.host_str().map(|h| h.ends_with(".i2p") && !h.ends_with(".b32.i2p")).unwrap_or(false) {
-                            if let Some(b32_url) = I2pDriver::extract_base32_address(&resp.headers, &raw_html, &url) {
-                                info!(worker_id, url = %url, b32 = %b32_url, "discovered I2P base32 address");
-                                discovered_urls.push(b32_url);
+                        // 2.2. Declared mirrors (Onion-Location, Alt-Svc, rel=alternate)
+                        let mirrors = MirrorHarvester::extract(&url, &raw_html, &resp.headers);
+                        if !mirrors.is_empty() {
+                            info!(worker_id, url = %url, count = mirrors.len(), "declared mirrors");
+                        }
+                        discovered_urls.extend(mirrors.iter().map(|m| m.mirror_url.clone()));
+
+                        // 2.3. Front-page fingerprint for phishing-clone detection
+                        let site_fingerprint = if url.path() == "/" && url.host_str().is_some() {
+                            CloneDetector::fingerprint(&page.body_text, &raw_html)
+                        } else {
+                            None
+                        };
+
+                        // 2.4. Image metadata: EXIF/XMP/IPTC of the page's <img> sources (GPS positions,
+                        // camera serials), within the per-page count and per-image size budget
+                        let mut documents: Vec<DocumentMetadata> =
+                            document.map(|doc| vec![doc.metadata]).unwrap_or_default();
+                        let is_html = page.content_type.as_deref().is_some_and(|ct| ct.contains("html"));
+                        if max_images_per_page > 0 && is_html {
+                            let image_config = FetchConfig { max_body_size: max_image_bytes, ..fetch_config.clone() };
+                            let sources = MetadataExtractor::image_sources(&raw_html, &url);
+                            if fetched_images.len() >= MAX_TRACKED_IMAGES {
+                                fetched_images.clear();
+                            }
+                            let to_fetch: Vec<url::Url> = sources
+                                .into_iter()
+                                .filter(|u| driver.can_handle(u))
+                                .filter(|u| fetched_images.insert(frontier.normalize_url(u)))
+                                .take(max_images_per_page)
+                                .collect();
+                            for image_url in to_fetch {
+                                // Images share the host's politeness budget with its pages. The worker holds
+                                // its network slot, so a busy host is skipped rather than waited for; the
+                                // image is forgotten and tried again from a later page
+                                let image_host = image_url.host_str().map(|h| h.to_string());
+                                if let Some(image_host) = &image_host {
+                                    if !frontier.try_reserve_host(image_host, driver.default_delay()) {
+                                        debug!(worker_id, url = %image_url, "image host busy, skipped");
+                                        fetched_images.remove(&frontier.normalize_url(&image_url));
+                                        continue;
+                                    }
+                                }
+                                let fetched = driver.fetch(&image_url, &image_config, 0).await;
+                                if let Some(image_host) = &image_host {
+                                    frontier.finish_host_visit(image_host, fetched.as_ref().ok().map(|r| r.status), None);
+                                }
+                                let image = match fetched {
+                                    Ok(image) if image.status < 400 => image,
+                                    Ok(_) => continue,
+                                    Err(e) => {
+                                        debug!(worker_id, url = %image_url, "image fetch failed: {}", e);
+                                        continue;
+                                    }
+                                };
+                                let Some(meta) = MetadataExtractor::extract_image(image_url.as_str(), &image.body) else {
+                                    continue;
+                                };
+                                page.entities.gps_coordinates.extend(meta.values("gps_coordinate").map(String::from));
+                                page.entities.camera_serials.extend(meta.values("camera_serial").map(String::from));
+                                // Keep the record only when there is more than the format
+                                if meta.fields.len() > 1 {
+                                    info!(worker_id, url = %image_url, fields = meta.fields.len(), "image metadata");
+                                    documents.push(meta);
+                                }
+                            }
+                            page.entities.gps_coordinates.sort();
+                            page.entities.gps_coordinates.dedup();
+                            page.entities.camera_serials.sort();
+                            page.entities.camera_serials.dedup();
+                        }
+
+                        // 2.5. Network-specific alternate addresses (e.g. I2P base32 behind a .i2p name)
+                        for alternate in driver.alternate_addresses(&url, &resp.headers, &raw_html) {
+                            info!(worker_id, url = %url, alternate = %alternate, "discovered alternate address");
+                            discovered_urls.push(alternate);
+                        }
+
+                        // IPNS names are mutable: keep every snapshot a name resolved to
+                        if url.scheme() == "ipns" && resp.status == 200 {
+                            let name = url::Url::parse(&canonical_url).ok().and_then(|u| u.host_str().map(String::from));
+                            let root = resp.headers.get("x-ipfs-roots").and_then(|roots| ipfs::root_cid(roots));
+                            if let (Some(name), Some(root)) = (name, root) {
+                                match crawl_storage.record_ipns_resolution(&name, &root, &job.network).await {
+                                    Ok(Some(previous)) => {
+                                        info!(worker_id, name = %name, previous = %previous, current = %root, "ipns name republished")
+                                    }
+                                    Ok(None) => debug!(worker_id, name = %name, root = %root, "ipns name resolved"),
+                                    Err(e) => error!(url = %url, "ipns resolution record failed: {}", e),
+                                }
                             }
                         }
 
@@ -744,19 +1207,17 @@ pub async fn run_crawl(
                         discovered_urls.extend(mutated);
 
                         // 5. Infrastructure probing (once per domain)
-                        // Skip for hyphanet — opaque scheme has no domain to probe
-                        if url.scheme() != "hyphanet" && url.scheme() != "freenet" {
-                            if !probed.contains(&domain) {
-                                probed.insert(domain.clone());
-
-                                let base = format!("{}://{}", url.scheme(), domain);
-                                if let Ok(base_url) = url::Url::parse(&base) {
-                                    let probes = InfraProber::generate_probes(&base_url);
-                                    info!(worker_id, domain = %domain, count = probes.len(), "infra probes queued");
-                                    let probe_urls: Vec<String> =
-                                        probes.into_iter().map(|p| p.probe_url).collect();
-                                    discovered_urls.extend(probe_urls);
-                                }
+                        // Skip for hyphanet — opaque scheme has no domain to probe.
+                        // The probes are HTTP paths, so gemini/gopher hosts are only marked seen.
+                        let first_visit = url.scheme() != "hyphanet" && url.scheme() != "freenet" && probed.insert(domain.clone());
+                        if first_visit && matches!(url.scheme(), "http" | "https") {
+                            let base = format!("{}://{}", url.scheme(), domain);
+                            if let Ok(base_url) = url::Url::parse(&base) {
+                                let probes = InfraProber::generate_probes(&base_url);
+                                info!(worker_id, domain = %domain, count = probes.len(), "infra probes queued");
+                                let probe_urls: Vec<String> =
+                                    probes.into_iter().map(|p| p.probe_url).collect();
+                                discovered_urls.extend(probe_urls);
                             }
                         }
 
@@ -765,52 +1226,95 @@ pub async fn run_crawl(
                             discovered_urls.push(link.url.clone());
                         }
 
-                        // Enqueue all discovered URLs (dedup + skip dead ones)
-                        if depth < max_depth {
-                            // Dedup within this page's discovered URLs
-                            let mut seen_this_page = HashSet::with_capacity(discovered_urls.len());
-                            discovered_urls.retain(|u| seen_this_page.insert(u.clone()));
-
-                            // Collect all jobs into a batch, then push once
-                            // Boost priority for NEW domains (never seen before)
-                            let mut batch: Vec<CrawlJob> = Vec::with_capacity(discovered_urls.len());
-                            for url_str in &discovered_urls {
+                        // Enqueue all discovered URLs (dedup + skip dead ones).
+                        // Depth and domain-hop limits are applied per URL in make_crawl_job.
+
+                        // Dedup within this page's discovered URLs
+                        let mut seen_this_page = HashSet::with_capacity(discovered_urls.len());
+                        discovered_urls.retain(|u| seen_this_page.insert(u.clone()));
+
+                        // Collect all jobs into a batch, then push once
+                        // Boost priority for NEW domains (never seen before)
+                        let mut batch: Vec<CrawlJob> = Vec::with_capacity(discovered_urls.len());
+                        let mut new_domains: HashSet<String> = HashSet::new();
+                        let anchors: HashMap<&str, &str> = page
+                            .links
+                            .iter()
+                            .filter_map(|l| Some((l.url.as_str(), l.anchor_text.as_deref()?)))
+                            .collect();
+                        let mut pruned = 0usize;
+                        for url_str in &discovered_urls {
+                            if let Some(mut next_job) = make_crawl_job(url_str, &job, &drivers, &scope, &depth_limits) {
                                 // Lock-free dead URL check via DashSet
-                                if dead.contains(url_str.as_str()) {
+                                if dead.contains(&frontier.normalize_url(&next_job.url)) {
                                     continue;
                                 }
-                                if let Some(mut job) = make_crawl_job(url_str, depth, &url, &drivers) {
-                                    let link_domain = job.url.host_str().unwrap_or("");
-                                    if !link_domain.is_empty() {
-                                        // MASSIVE priority boost for domains we haven't visited yet - lock-free check
-                                        if !probed.contains(link_domain) {
-                                            job.priority *= 1000.0;
-                                        }
-                                        // Penalty for domains approaching page limit
-                                        // (Hard stop enforced during fetch, this just deprioritizes)
-                                        if let Some(count_entry) = domain_counts.get(link_domain) {
-                                            let page_count = count_entry.load(Ordering::Relaxed);
-                                            // Get the limit from the driver that can handle this URL
-                                            if let Some(d) = drivers.iter().find(|d| d.can_handle(&job.url)) {
-                                                if page_count > d.max_pages_per_domain() / 2 {
-                                                    job.priority *= 0.1; // 10x penalty when > 50% of limit
-                                                }
+                                // Topic relevance: boost on-topic links, prune off-topic branches
+                                if let Some(page_relevance) = relevance {
+                                    let link_relevance = topic.score_link(
+                                        anchors.get(url_str.as_str()).copied(), &next_job.url, language.as_deref()
+                                    );
+                                    if topic.should_prune(page_relevance, link_relevance) {
+                                        pruned += 1;
+                                        continue;
+                                    }
+                                    next_job.priority *= topic.link_multiplier(page_relevance, link_relevance);
+                                }
+                                let link_domain = next_job.url.host_str().unwrap_or("");
+                                if !link_domain.is_empty() {
+                                    // MASSIVE priority boost for domains we haven't visited yet - lock-free check
+                                    if !probed.contains(link_domain) {
+                                        next_job.priority *= 1000.0;
+                                        new_domains.insert(link_domain.to_string());
+                                    }
+                                    // Penalty for domains approaching page limit
+                                    // (Hard stop enforced during fetch, this just deprioritizes)
+                                    if let Some(count_entry) = domain_counts.get(link_domain) {
+                                        let page_count = count_entry.load(Ordering::Relaxed);
+                                        // Get the limit from the driver that can handle this URL
+                                        if let Some(d) = route(&drivers, &next_job.url) {
+                                            if page_count > d.max_pages_per_domain() / 2 {
+                                                next_job.priority *= 0.1; // 10x penalty when > 50% of limit
                                             }
                                         }
                                     }
-                                    batch.push(job);
                                 }
+                                batch.push(next_job);
                             }
-                            if !batch.is_empty() {
-                                let enqueued = frontier.push_batch(batch).await;
-                                if enqueued > 0 {
-                                    info!(worker_id, url = %url, enqueued, "URLs enqueued");
-                                }
+                        }
+                        if pruned > 0 {
+                            info!(worker_id, url = %url, pruned, relevance = ?relevance, "off-topic URLs pruned");
+                        }
+
+                        // Credit before enqueueing so this page's links already see its yield
+                        frontier.record_yield(&url, job.source_url.as_deref(), YieldStats {
+                            pages: 1,
+                            new_domains: new_domains.len() as u64,
+                            ..Default::default()
+                        });
+                        if !batch.is_empty() {
+                            let enqueued = frontier.push_batch(batch).await;
+                            if enqueued > 0 {
+                                info!(worker_id, url = %url, enqueued, "URLs enqueued");
                             }
                         }
 
+                        // pages.url keeps the fetched URL; the canonical form is the dedup key
+                        let revisit = revisit_target(&canonical_url, &job, &page.domain);
+                        page.canonical_url = Some(canonical_url);
+
                         // Send to storage with timeout to prevent deadlock
-                        let result = CrawlResult { page, correlations };
+                        let result = CrawlResult {
+                            page,
+                            correlations,
+                            mirrors,
+                            site_fingerprint,
+                            url: url.clone(),
+                            source_url: job.source_url.clone(),
+                            immutable: driver.is_immutable(&url),
+                            revisit,
+                            documents,
+                        };
                         match tokio::time::timeout(
                             std::time::Duration::from_secs(30),
                             result_tx.send(result)
@@ -846,7 +1350,167 @@ pub async fn run_crawl(
         let _ = handle.await;
     }
     let _ = storage_handle.await;
+    let _ = quarantine_handle.await;
+    for handle in revisit_handles.into_iter().chain(feed_handles).chain(supervisor_handles) {
+        let _ = handle.await;
+    }
+    // The storage task may have credited yields after the quarantine task's last flush
+    flush_yield_scores(&frontier, &storage).await;
 
     info!("shutdown complete");
     Ok(())
 }
+
+
+/// Write one crawl result (page, document metadata, feed and its items, revisit
+/// schedule, correlations, mirrors, site fingerprint) and record its yield. Shared by
+/// the storage task and its shutdown drain. Returns the page id when the page was stored.
+async fn store_result(
+    storage: &Storage,
+    frontier: &CrawlFrontier,
+    revisit_policy: &RevisitPolicy,
+    feed_poll_secs: i64,
+    result: &CrawlResult,
+) -> Option<i64> {
+    // Count before storing, or every entity would already be known
+    let new_entities = storage.count_new_entities(&result.page).await.unwrap_or(0);
+    let stored = match storage.store_page(&result.page).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            error!(url = %result.page.url, "store failed: {}", e);
+            None
+        }
+    };
+    if let Some(id) = stored {
+        for doc in &result.documents {
+            if let Err(e) = storage
+                .store_document_metadata(
+                    id,
+                    &doc.url,
+                    &result.page.domain,
+                    &doc.content_type,
+                    &doc.metadata_type,
+                    doc.size_bytes,
+                    &doc.fields,
+                    &serde_json::to_value(&doc.entries).unwrap_or_default(),
+                )
+                .await
+            {
+                error!(url = %doc.url, "document metadata store failed: {}", e);
+            }
+        }
+        if let Some(feed) = &result.page.feed {
+            let page = &result.page;
+            match storage
+                .register_feed(&result.revisit, page.title.as_deref(), feed, feed_poll_secs)
+                .await
+            {
+                Ok(()) => match storage.store_feed_items(page.key(), feed).await {
+                    Ok(new_items) if new_items > 0 => {
+                        info!(url = %page.url, new_items, "feed has new items");
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!(url = %page.url, "feed item store failed: {}", e),
+                },
+                Err(e) => error!(url = %page.url, "feed register failed: {}", e),
+            }
+        }
+        // Content-addressed pages never change; nothing to revisit.
+        // Feeds are re-fetched by the feed poller
+        let polled_feed = result.page.feed.is_some() && feed_poll_secs > 0;
+        if !result.immutable && !polled_feed {
+            if let Err(e) =
+                schedule_revisit(storage, revisit_policy, &result.revisit, &result.page.raw_html_hash).await
+            {
+                error!(url = %result.page.url, "revisit schedule failed: {}", e);
+            }
+        }
+    }
+
+    let mut new_correlations = 0;
+    for corr in &result.correlations {
+        match storage
+            .store_correlation(&corr.domain, &corr.correlation_type, &corr.value)
+            .await
+        {
+            Ok(true) => new_correlations += 1,
+            Ok(false) => {}
+            Err(e) => error!(domain = %corr.domain, "correlation store failed: {}", e),
+        }
+    }
+    frontier.record_yield(
+        &result.url,
+        result.source_url.as_deref(),
+        YieldStats {
+            new_entities: new_entities as u64,
+            new_correlations,
+            ..Default::default()
+        },
+    );
+    for mirror in &result.mirrors {
+        if let Err(e) = storage
+            .store_mirror(&mirror.source_domain, &mirror.mirror_domain, &mirror.mirror_url, &mirror.signal)
+            .await
+        {
+            error!(domain = %mirror.source_domain, "mirror store failed: {}", e);
+        }
+    }
+    if let Some((content, dom)) = result.site_fingerprint {
+        if let Err(e) = storage
+            .store_site_fingerprint(&result.page.domain, &result.page.network, &result.page.url, content, dom)
+            .await
+        {
+            error!(domain = %result.page.domain, "fingerprint store failed: {}", e);
+        }
+    }
+    stored
+}
+
+/// Persist the yield scores that changed since the last flush.
+async fn flush_yield_scores(frontier: &CrawlFrontier, storage: &Storage) {
+    let changed = frontier.drain_dirty_yields();
+    if changed.is_empty() {
+        return;
+    }
+    let rows: Vec<(String, String, i64, i64, i64, i64, i64)> = changed
+        .into_iter()
+        .map(|(kind, key, stats)| {
+            (
+                kind.as_str().to_string(),
+                key,
+                stats.pages as i64,
+                stats.new_domains as i64,
+                stats.new_entities as i64,
+                stats.new_correlations as i64,
+                stats.errors as i64,
+            )
+        })
+        .collect();
+    if let Err(e) = storage.store_yield_scores(&rows).await {
+        error!(count = rows.len(), "yield score store failed: {}", e);
+    }
+}
+
+/// Record a visit of a stored page (`content_hash` = its raw_html_hash) and schedule
+/// its next one from the observed change rate.
+async fn schedule_revisit(
+    storage: &Storage,
+    policy: &RevisitPolicy,
+    target: &RevisitTarget,
+    content_hash: &str,
+) -> Result<()> {
+    let now = chrono::Utc::now();
+    let (first_visit_at, visits, changes) = match storage.get_revisit_state(&target.key).await? {
+        Some((hash, first_visit_at, visits, changes)) => {
+            let changed = hash != content_hash;
+            (first_visit_at, visits + 1, changes + changed as i32)
+        }
+        None => (now, 1, 0),
+    };
+    let observed = (now - first_visit_at).to_std().unwrap_or_default();
+    let interval = policy.next_interval(observed, changes as u32);
+    let next_visit_at = now + chrono::Duration::from_std(interval)?;
+    storage
+        .store_revisit(target, content_hash, first_visit_at, visits, changes, next_visit_at)
+        .await
+}
@@ -1,6 +1,7 @@
 mod cli;
 mod commands;
 mod crawl;
+mod scheduler;
 mod seeds;
 
 use anyhow::Result;
@@ -13,8 +14,8 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 use darkscraper_core::config::AppConfig;
 
-use crate::cli::{Cli, Commands};
-use crate::commands::{run_export, run_search, run_status};
+use crate::cli::{BlobCommands, Cli, Commands};
+use crate::commands::{run_blob_get, run_clones, run_export, run_search, run_status};
 use crate::crawl::run_crawl;
 
 #[tokio::main]
@@ -34,7 +35,64 @@ async fn main() -> Result<()> {
     });
     let mut config: AppConfig = toml::from_str(&config_str)?;
 
-    // Environment variable overrides for worker/concurrency tuning
+    apply_env_overrides(&mut config);
+
+    match cli.command {
+        Commands::Crawl {
+            seeds,
+            seed,
+            depth,
+            max_hops,
+            allow_domain,
+            deny_domain,
+            include,
+            exclude,
+            stay_on_seeds,
+            networks,
+        } => {
+            // CLI scope flags extend the [scope] config section
+            config.scope.allow_domains.extend(allow_domain);
+            config.scope.deny_domains.extend(deny_domain);
+            config.scope.include_patterns.extend(include);
+            config.scope.exclude_patterns.extend(exclude);
+            config.scope.stay_on_seed_domains |= stay_on_seeds;
+            if !networks.is_empty() {
+                config.scope.networks = networks;
+            }
+            run_crawl(config, &cli.config, seeds, seed, depth, max_hops).await?;
+        }
+        Commands::Search {
+            query,
+            entity,
+            entity_type,
+            language,
+            limit,
+        } => {
+            run_search(config, query, entity, entity_type, language, limit).await?;
+        }
+        Commands::Status => {
+            run_status(config).await?;
+        }
+        Commands::Clones { domain, limit } => {
+            run_clones(config, domain, limit).await?;
+        }
+        Commands::Export { format, output } => {
+            run_export(config, &format, &output).await?;
+        }
+        Commands::Blob { command } => match command {
+            BlobCommands::Get { hash, output } => {
+                run_blob_get(config, &hash, output.as_deref())?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Environment variable overrides for worker/concurrency tuning (SCALE_LEVEL,
+/// `<NAME>_WORKERS`, MAX_WORKERS, ...). Applied at startup and again to the config
+/// reloaded on SIGHUP, so a reload doesn't drop them.
+pub(crate) fn apply_env_overrides(config: &mut AppConfig) {
     let parse_workers =
         |v: &str| -> Option<usize> { v.parse::<usize>().ok().filter(|&n| n > 0 && n <= 128) };
 
@@ -43,29 +101,24 @@ async fn main() -> Result<()> {
     if let Ok(v) = std::env::var("SCALE_LEVEL") {
         if let Ok(level) = v.parse::<usize>() {
             if level >= 1 && level <= 5 {
-                // Tor: Most content, fast network - scale aggressively
-                // Level 1=16w, 2=48w, 3=64w, 4=96w, 5=128w
-                config.tor.max_concurrency = match level {
-                    1 => 16,
-                    2 => 48,
-                    3 => 64,
-                    4 => 96,
-                    5 => 128,
-                    _ => 32,
-                };
-                std::env::set_var("TOR_INSTANCES", (level * 2).min(10).to_string());
-
-                // I2P: Moderate content, moderate latency (1x instances, 4x workers)
-                config.i2p.max_concurrency = level * 4;
-                std::env::set_var("I2P_INSTANCES", level.to_string());
-
-                // Hyphanet: Very slow network, cap at 3 instances (2x workers, max 12)
-                config.hyphanet.max_concurrency = (level * 2).min(12);
-                std::env::set_var("HYPHANET_INSTANCES", level.min(3).to_string());
-
-                // Lokinet: Limited content, cap at 4 instances (4x workers, max 20)
-                config.lokinet.max_concurrency = (level * 4).min(20);
-                std::env::set_var("LOKINET_INSTANCES", level.min(4).to_string());
+                // (network, workers, proxy instances) at this level
+                let scaled = [
+                    // Tor: Most content, fast network - scale aggressively
+                    // Level 1=16w, 2=48w, 3=64w, 4=96w, 5=128w
+                    ("tor", [16, 48, 64, 96, 128][level - 1], (level * 2).min(10)),
+                    // I2P: Moderate content, moderate latency (1x instances, 4x workers)
+                    ("i2p", level * 4, level),
+                    // Hyphanet: Very slow network, cap at 3 instances (2x workers, max 12)
+                    ("hyphanet", (level * 2).min(12), level.min(3)),
+                    // Lokinet: Limited content, cap at 4 instances (4x workers, max 20)
+                    ("lokinet", (level * 4).min(20), level.min(4)),
+                ];
+                for (name, workers, instances) in scaled {
+                    if let Some(network) = config.networks.get_mut(name) {
+                        network.max_concurrency = workers;
+                        std::env::set_var(env_key(name, "INSTANCES"), instances.to_string());
+                    }
+                }
 
                 // Scale crawl limits
                 config.general.max_pages_per_domain = match level {
@@ -75,12 +128,14 @@ async fn main() -> Result<()> {
                     1 => 5,    2 => 8,    3 => 10,   4 => 15,   5 => 25,   _ => 10,
                 };
 
-                eprintln!("🎚️  SCALE_LEVEL={}: Tor={}i/{}w, I2P={}i/{}w, Hyphanet={}i/{}w, Lokinet={}i/{}w | depth={}, pages/domain={}",
+                let summary: Vec<String> = scaled
+                    .iter()
+                    .map(|(name, workers, instances)| format!("{}={}i/{}w", name, instances, workers))
+                    .collect();
+                eprintln!(
+                    "🎚️  SCALE_LEVEL={}: {} | depth={}, pages/domain={}",
                     level,
-                    (level * 2).min(10), config.tor.max_concurrency,
-                    level, level * 4,
-                    level.min(3), (level * 2).min(12),
-                    level.min(4), (level * 4).min(20),
+                    summary.join(", "),
                     config.general.max_depth,
                     config.general.max_pages_per_domain
                 );
@@ -88,101 +143,49 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Individual overrides (can override SCALE_LEVEL)
-    if let Ok(v) = std::env::var("TOR_WORKERS") {
-        if let Some(n) = parse_workers(&v) {
-            config.tor.max_concurrency = n;
-        }
-    }
-    if let Ok(v) = std::env::var("I2P_WORKERS") {
-        if let Some(n) = parse_workers(&v) {
-            config.i2p.max_concurrency = n;
-        }
-    }
-    if let Ok(v) = std::env::var("TOR_ENABLED") {
-        config.tor.enabled = v != "0" && v.to_lowercase() != "false";
-    }
-    if let Ok(v) = std::env::var("I2P_ENABLED") {
-        config.i2p.enabled = v != "0" && v.to_lowercase() != "false";
-    }
-    if let Ok(v) = std::env::var("ZERONET_WORKERS") {
-        if let Some(n) = parse_workers(&v) {
-            config.zeronet.max_concurrency = n;
+    // Per-network overrides (can override SCALE_LEVEL): <NAME>_WORKERS, <NAME>_ENABLED
+    for (name, network) in config.networks.iter_mut() {
+        if let Ok(v) = std::env::var(env_key(name, "WORKERS")) {
+            if let Some(n) = parse_workers(&v) {
+                network.max_concurrency = n;
+            }
         }
-    }
-    if let Ok(v) = std::env::var("ZERONET_ENABLED") {
-        config.zeronet.enabled = v != "0" && v.to_lowercase() != "false";
-    }
-    if let Ok(v) = std::env::var("HYPHANET_WORKERS") {
-        if let Some(n) = parse_workers(&v) {
-            config.hyphanet.max_concurrency = n;
+        if let Ok(v) = std::env::var(env_key(name, "ENABLED")) {
+            network.enabled = v != "0" && v.to_lowercase() != "false";
         }
     }
-    if let Ok(v) = std::env::var("HYPHANET_ENABLED") {
-        config.hyphanet.enabled = v != "0" && v.to_lowercase() != "false";
-    }
-    if let Ok(v) = std::env::var("LOKINET_WORKERS") {
-        if let Some(n) = parse_workers(&v) {
-            config.lokinet.max_concurrency = n;
+    // Shared worker pool size (unset = sum of the per-network limits above)
+    if let Ok(v) = std::env::var("MAX_WORKERS") {
+        if let Some(n) = v.parse::<usize>().ok().filter(|&n| n > 0) {
+            config.general.max_workers = Some(n);
         }
     }
-    if let Ok(v) = std::env::var("LOKINET_ENABLED") {
-        config.lokinet.enabled = v != "0" && v.to_lowercase() != "false";
-    }
     if let Ok(v) = std::env::var("MAX_DEPTH") {
         if let Ok(n) = v.parse::<u32>() {
             config.general.max_depth = n;
         }
     }
-
-    // Build proxy lists from TOR_INSTANCES / I2P_INSTANCES / ZERONET_INSTANCES env vars.
-    // When set, these override the config file's proxy lists with generated hostnames.
-    // Falls back to the config file values if env var is not set.
-    if let Ok(v) = std::env::var("TOR_INSTANCES") {
-        if let Ok(n) = v.parse::<usize>() {
-            config.tor.socks_proxies = (1..=n).map(|i| format!("tor{}:9050", i)).collect();
-        }
-    }
-    if let Ok(v) = std::env::var("I2P_INSTANCES") {
-        if let Ok(n) = v.parse::<usize>() {
-            config.i2p.http_proxies = (1..=n).map(|i| format!("i2p{}:4444", i)).collect();
-        }
-    }
-    if let Ok(v) = std::env::var("ZERONET_INSTANCES") {
-        if let Ok(n) = v.parse::<usize>() {
-            config.zeronet.http_proxies = (1..=n).map(|i| format!("zeronet{}:43110", i)).collect();
-        }
-    }
-    if let Ok(v) = std::env::var("HYPHANET_INSTANCES") {
-        if let Ok(n) = v.parse::<usize>() {
-            config.hyphanet.http_proxies = (1..=n).map(|i| format!("hyphanet{}:8888", i)).collect();
-        }
-    }
-    if let Ok(v) = std::env::var("LOKINET_INSTANCES") {
-        if let Ok(n) = v.parse::<usize>() {
-            config.lokinet.socks_proxies = (1..=n).map(|i| format!("lokinet{}:1080", i)).collect();
+    if let Ok(v) = std::env::var("MAX_DOMAIN_HOPS") {
+        if let Ok(n) = v.parse::<u32>() {
+            config.general.max_domain_hops = n;
         }
     }
 
-    match cli.command {
-        Commands::Crawl { seeds, seed, depth } => {
-            run_crawl(config, seeds, seed, depth).await?;
-        }
-        Commands::Search {
-            query,
-            entity,
-            entity_type,
-            limit,
-        } => {
-            run_search(config, query, entity, entity_type, limit).await?;
-        }
-        Commands::Status => {
-            run_status(config).await?;
-        }
-        Commands::Export { format, output } => {
-            run_export(config, &format, &output).await?;
+    // Build proxy lists from <NAME>_INSTANCES (TOR_INSTANCES, I2P_INSTANCES, ...).
+    // When set, these override the config file's proxy lists with numbered hostnames
+    // (see `proxy_template`). Falls back to the config file values if unset.
+    for (name, network) in config.networks.iter_mut() {
+        if let Ok(v) = std::env::var(env_key(name, "INSTANCES")) {
+            if let Ok(n) = v.parse::<usize>() {
+                if let Some(proxies) = network.instance_proxies(n) {
+                    network.proxies = proxies;
+                }
+            }
         }
     }
+}
 
-    Ok(())
+/// Environment variable for a per-network setting: ("tor", "WORKERS") → TOR_WORKERS.
+fn env_key(network: &str, setting: &str) -> String {
+    format!("{}_{}", network.to_uppercase().replace('-', "_"), setting)
 }
@@ -0,0 +1,189 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::info;
+
+use darkscraper_core::CrawlJob;
+use darkscraper_frontier::CrawlFrontier;
+
+/// Hands jobs to the shared worker pool.
+/// Workers aren't bound to a network: each asks for the next job and gets one from
+/// the least-loaded ready network that has queued work and a free slot. A network
+/// can burst up to its own limit while the others are idle, and the pool as a whole
+/// stays under the global limit. Limits are plain counters (counting semaphores),
+/// so they can be resized while the crawl runs.
+pub struct NetworkScheduler {
+    networks: Vec<NetworkSlots>,
+    /// Workers spawned — the global limit can't grow past this
+    pool_size: usize,
+    global_limit: AtomicUsize,
+    active: AtomicUsize,
+}
+
+struct NetworkSlots {
+    name: String,
+    /// Max concurrent fetches (sized from the driver's `max_concurrency()`)
+    limit: AtomicUsize,
+    in_use: AtomicUsize,
+    /// Whether the network's supervisor currently sees a ready proxy
+    ready: AtomicBool,
+    /// Per-host delay floor for polite pops
+    default_delay: Duration,
+}
+
+/// Outcome of asking the scheduler for work.
+pub enum NextJob {
+    /// A job plus the slot it holds; the slot is released when the guard drops
+    Job(CrawlJob, SlotGuard),
+    /// Work is queued but every eligible network or host is at its limit
+    Busy,
+    /// No ready network has queued work
+    Idle,
+}
+
+/// A claimed network slot (and global slot), released on drop.
+pub struct SlotGuard {
+    scheduler: Arc<NetworkScheduler>,
+    index: usize,
+}
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        self.scheduler.networks[self.index].in_use.fetch_sub(1, Ordering::AcqRel);
+        self.scheduler.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl NetworkScheduler {
+    /// `networks` = (name, concurrency limit, per-host delay floor).
+    pub fn new(networks: Vec<(String, usize, Duration)>, pool_size: usize) -> Self {
+        Self {
+            networks: networks
+                .into_iter()
+                .map(|(name, limit, default_delay)| NetworkSlots {
+                    name,
+                    limit: AtomicUsize::new(limit),
+                    in_use: AtomicUsize::new(0),
+                    ready: AtomicBool::new(false),
+                    default_delay,
+                })
+                .collect(),
+            pool_size,
+            global_limit: AtomicUsize::new(pool_size),
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn network_names(&self) -> Vec<String> {
+        self.networks.iter().map(|n| n.name.clone()).collect()
+    }
+
+    /// Start (its proxies are up) or stop (they all degraded) handing out jobs for a network.
+    pub fn set_ready(&self, network: &str, ready: bool) {
+        if let Some(slots) = self.networks.iter().find(|n| n.name == network) {
+            slots.ready.store(ready, Ordering::Release);
+        }
+    }
+
+    /// Change a network's concurrency limit. Lowering it doesn't interrupt
+    /// running fetches; new ones wait until the network is under the limit.
+    pub fn resize(&self, network: &str, limit: usize) {
+        if let Some(slots) = self.networks.iter().find(|n| n.name == network) {
+            let old = slots.limit.swap(limit, Ordering::AcqRel);
+            if old != limit {
+                info!(network, old, new = limit, "network concurrency resized");
+            }
+        }
+    }
+
+    /// Change the global limit (capped at the number of spawned workers).
+    pub fn set_global_limit(&self, limit: usize) {
+        let limit = limit.min(self.pool_size);
+        let old = self.global_limit.swap(limit, Ordering::AcqRel);
+        if old != limit {
+            info!(old, new = limit, pool = self.pool_size, "global concurrency resized");
+        }
+    }
+
+    /// (network, in use, limit) for every network.
+    pub fn limits(&self) -> Vec<(String, usize, usize)> {
+        self.networks
+            .iter()
+            .map(|n| {
+                (
+                    n.name.clone(),
+                    n.in_use.load(Ordering::Acquire),
+                    n.limit.load(Ordering::Acquire),
+                )
+            })
+            .collect()
+    }
+
+    /// Claim a slot and pop the next polite job, trying networks from least to most
+    /// loaded (in use / limit) so a busy network can't starve the others.
+    pub async fn next_job(self: &Arc<Self>, frontier: &CrawlFrontier) -> NextJob {
+        if !try_acquire(&self.active, &self.global_limit) {
+            return NextJob::Busy;
+        }
+
+        let mut candidates: Vec<(usize, f64)> = self
+            .networks
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.ready.load(Ordering::Acquire))
+            .map(|(i, n)| {
+                let limit = n.limit.load(Ordering::Acquire).max(1) as f64;
+                (i, n.in_use.load(Ordering::Acquire) as f64 / limit)
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut queued = false;
+        for (index, _) in candidates {
+            let slots = &self.networks[index];
+            if frontier.is_network_empty(&slots.name).await {
+                continue;
+            }
+            queued = true;
+            if !try_acquire(&slots.in_use, &slots.limit) {
+                continue;
+            }
+            // Guard from here on, so the slots are released on every path
+            let guard = SlotGuard {
+                scheduler: Arc::clone(self),
+                index,
+            };
+            if let Some(job) = frontier.pop_polite_for_network(&slots.name, slots.default_delay).await {
+                return NextJob::Job(job, guard);
+            }
+            // Every queued host on this network is busy; the global slot is
+            // re-claimed below for the next network
+            drop(guard);
+            if !try_acquire(&self.active, &self.global_limit) {
+                return NextJob::Busy;
+            }
+        }
+
+        self.active.fetch_sub(1, Ordering::AcqRel);
+        if queued {
+            NextJob::Busy
+        } else {
+            NextJob::Idle
+        }
+    }
+}
+
+/// Increment `in_use` if it is below `limit`.
+fn try_acquire(in_use: &AtomicUsize, limit: &AtomicUsize) -> bool {
+    let mut current = in_use.load(Ordering::Acquire);
+    loop {
+        if current >= limit.load(Ordering::Acquire) {
+            return false;
+        }
+        match in_use.compare_exchange_weak(current, current + 1, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return true,
+            Err(actual) => current = actual,
+        }
+    }
+}
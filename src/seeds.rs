@@ -128,14 +128,3 @@ pub fn get_all_seeds() -> Vec<&'static str> {
     seeds.extend_from_slice(LOKINET_SEEDS);
     seeds
 }
-
-/// Check if an .onion host is a valid v3 address (56 base32 chars).
-pub fn is_v3_onion(host: &str) -> bool {
-    let Some(name) = host.strip_suffix(".onion") else {
-        return false;
-    };
-    name.len() == 56
-        && name
-            .chars()
-            .all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c))
-}